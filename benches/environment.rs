@@ -0,0 +1,61 @@
+//! Loop-heavy scripts (the `for_loop` cases of the api tests at scale), which spend most of their
+//! time entering blocks and reading and writing variables.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rlox::{Backend, Interpreter};
+
+const COUNTING_LOOP: &str = r#"
+    var a = 0;
+    for {var i = 0;} {i < 10000} {i = i + 1;} {
+        a = a + 1;
+    }
+"#;
+
+const SHADOWING_LOOP: &str = r#"
+    var a = 0;
+    var b = 0;
+    for {var i = 0;} {i < 10000} {i = i + 1;} {
+        var a = i;
+        var c = a * 2;
+        b = b + c - a;
+    }
+"#;
+
+const NESTED_LOOPS: &str = r#"
+    var sum = 0;
+    for {var i = 0;} {i < 100} {i = i + 1;} {
+        for {var j = 0;} {j < 100} {j = j + 1;} {
+            {
+                var k = i + j;
+                sum = sum + k;
+            }
+        }
+    }
+"#;
+
+fn loops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("loops");
+    group.sample_size(20);
+    for (name, src) in [
+        ("counting", COUNTING_LOOP),
+        ("shadowing", SHADOWING_LOOP),
+        ("nested", NESTED_LOOPS),
+    ] {
+        for backend in [Backend::TreeWalker, Backend::Vm] {
+            group.bench_with_input(
+                BenchmarkId::new(name, format!("{backend:?}")),
+                src,
+                |b, src| {
+                    b.iter(|| {
+                        let mut interpreter = Interpreter::default().with_backend(backend);
+                        interpreter.interpret_src_str(src).expect("script runs")
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, loops);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+//! Scanner throughput on generated sources of a few megabytes, which mix all kinds of tokens.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rlox::Scanner;
+
+const SNIPPET: &str = r#"
+// sums up the numbers below a limit
+var total = 0;
+var message = "the total of all the numbers is";
+for {var i = 0;} {i < 1000.5} {i = i + 1;} {
+    if (i >= 10 and !(i == 500)) {
+        total = total + i * 2 / 3 - 1;
+    } else {
+        total = total - 1;
+    }
+}
+print message;
+print total;
+"#;
+
+fn generated_source(megabytes: usize) -> String {
+    let bytes = megabytes * 1024 * 1024;
+    SNIPPET.repeat(bytes / SNIPPET.len() + 1)
+}
+
+fn scanning(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scanner");
+    group.sample_size(10);
+    for megabytes in [1, 4, 16] {
+        let src = generated_source(megabytes);
+        group.throughput(Throughput::Bytes(src.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{megabytes}MiB")),
+            &src,
+            |b, src| b.iter(|| Scanner::new(src).count()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scanning);
+criterion_main!(benches);
@@ -1,16 +1,102 @@
+use std::path::PathBuf;
+
 use clap::Subcommand;
 
+use crate::{
+    Backend, DumpFormat, DumpStage, ErrorFormat, ErrorPolicy, Interpreter, Limits, OnError,
+    OptLevel, Output, Rule,
+};
+
 #[derive(clap::Parser)]
 pub struct Arguments {
     /// The mode to start the interpreter in
     #[command(subcommand)]
     mode: Mode,
+    /// The backend used to execute the code
+    #[arg(long, value_enum, default_value_t = Backend::TreeWalker, global = true)]
+    backend: Backend,
+    /// Print the byte code of each input before running it (only used by the vm backend)
+    #[arg(long, global = true)]
+    disassemble: bool,
+    /// The optimization level: `-O0` runs the program as written, `-O1` folds constants and removes
+    /// dead branches
+    #[arg(short = 'O', value_enum, default_value_t = OptLevel::O0, global = true)]
+    opt_level: OptLevel,
+    /// Cache the parsed programs in the given directory and reuse them while the source is unchanged
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+    /// Collect garbage at the first safe point after every allocation, i.e., before the next
+    /// declaration on the tree walker and before the next instruction on the VM (to debug the
+    /// collector)
+    #[arg(long, global = true)]
+    gc_stress: bool,
+    /// Stop each run after the given number of steps (statements and expressions, or instructions
+    /// on the vm backend)
+    #[arg(long, global = true)]
+    fuel: Option<u64>,
+    /// Reject code which nests blocks, groupings and unary operators deeper than the given depth
+    /// (128 by default)
+    #[arg(long, global = true)]
+    max_depth: Option<usize>,
+    /// Stop when a string longer than the given number of bytes is created
+    #[arg(long, global = true)]
+    max_string_len: Option<usize>,
+    /// Whether to stop or to continue with the next top-level declaration after a runtime error.
+    /// Files stop at the first error by default, the prompt continues.
+    #[arg(long, value_enum, global = true)]
+    on_error: Option<OnError>,
+    /// Stop after the given number of runtime errors; implies `--on-error=continue` unless set
+    /// otherwise
+    #[arg(long, global = true)]
+    max_errors: Option<usize>,
+    /// Only emit the output of the program, no diagnostic messages on stderr
+    #[arg(short, long, global = true)]
+    quiet: bool,
 }
 
 impl Arguments {
     pub fn mode(&self) -> &Mode {
         &self.mode
     }
+
+    fn error_policy(&self) -> ErrorPolicy {
+        let on_error = match (self.on_error, &self.mode, self.max_errors) {
+            (Some(on_error), _, _) => on_error,
+            (None, Mode::File { .. }, None) => OnError::Stop,
+            _ => OnError::Continue,
+        };
+        ErrorPolicy {
+            on_error,
+            max_errors: self.max_errors,
+        }
+    }
+
+    ///
+    /// Creates an interpreter configured according to the arguments
+    ///
+    pub fn interpreter(&self) -> Interpreter {
+        let interpreter = Interpreter::default()
+            .with_diagnostics(Output::stderr())
+            .with_backend(self.backend)
+            .with_disassembly(self.disassemble)
+            .with_opt_level(self.opt_level)
+            .with_gc_stress(self.gc_stress)
+            .with_limits(Limits {
+                fuel: self.fuel,
+                max_depth: self.max_depth,
+                max_string_len: self.max_string_len,
+            })
+            .with_error_policy(self.error_policy());
+        let interpreter = if self.quiet {
+            interpreter.quiet()
+        } else {
+            interpreter
+        };
+        match &self.cache_dir {
+            Some(dir) => interpreter.with_cache_dir(dir),
+            None => interpreter,
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -21,5 +107,100 @@ pub enum Mode {
     File {
         /// The string of lox source code
         file_path: String,
+        /// The format in which errors are reported on stderr; the machine-readable formats leave
+        /// out all other diagnostics
+        #[arg(long, value_enum, default_value_t = ErrorFormat::Human)]
+        error_format: ErrorFormat,
+    },
+    /// Runs the code in a file step by step, with breakpoints and a view of the variables; type
+    /// `help` at the prompt for the commands
+    Debug {
+        /// The path of the lox source file
+        file_path: String,
+    },
+    /// Scans, parses and resolves the code in a file without running it
+    Check {
+        /// The path of the lox source file
+        file_path: String,
+        /// The format in which errors are reported on stderr; the machine-readable formats leave
+        /// out all other diagnostics
+        #[arg(long, value_enum, default_value_t = ErrorFormat::Human)]
+        error_format: ErrorFormat,
+    },
+    /// Formats the code in the files in place, or checks whether they are formatted
+    Fmt {
+        /// Only report the files which would be changed, and fail if there are any
+        #[arg(long)]
+        check: bool,
+        /// The paths of the lox source files
+        #[arg(required = true)]
+        file_paths: Vec<String>,
+    },
+    /// Reports code which is valid but likely wrong, e.g., unused variables; the files are scanned,
+    /// parsed and resolved, but never run
+    Lint {
+        /// The format of the report
+        #[arg(long, value_enum, default_value_t = ErrorFormat::Human)]
+        format: ErrorFormat,
+        /// A rule not to check in any file; rules are allowed in a single file by a comment like
+        /// `// lint: allow(empty-block)`
+        #[arg(long, value_enum)]
+        allow: Vec<Rule>,
+        /// The paths of the lox source files
+        #[arg(required = true)]
+        file_paths: Vec<String>,
+    },
+    /// Prints a longer description of the error with the given code (e.g., L0101)
+    Explain {
+        /// The code of the error
+        code: String,
+    },
+    /// Prints the syntax tree of the code in a file as parsed, as an S-expression or in the
+    /// versioned JSON schema of `rlox::ast_json`
+    Ast {
+        /// The format of the syntax tree
+        #[arg(long, value_enum, default_value_t = DumpFormat::Text)]
+        format: DumpFormat,
+        /// The path of the lox source file
+        file_path: String,
+    },
+    /// Runs a language server for Lox on stdin and stdout, for editors to start
+    #[cfg(feature = "lsp")]
+    Lsp,
+    /// Prints the tokens or the syntax tree of the code provided via a file
+    Dump {
+        #[command(flatten)]
+        stage: DumpStageArgs,
+        /// The format of the dump
+        #[arg(long, value_enum, default_value_t = DumpFormat::Text)]
+        format: DumpFormat,
+        /// The path of the lox source file
+        file_path: String,
     },
 }
+
+#[derive(clap::Args)]
+#[group(required = true, multiple = false)]
+pub struct DumpStageArgs {
+    /// Dump the token stream with the locations of the tokens
+    #[arg(long)]
+    tokens: bool,
+    /// Dump the syntax tree as parsed, an alias of `rlox ast`
+    #[arg(long)]
+    ast: bool,
+    /// Dump the syntax tree after desugaring for loops into while loops
+    #[arg(long)]
+    desugared: bool,
+}
+
+impl DumpStageArgs {
+    pub fn stage(&self) -> DumpStage {
+        if self.tokens {
+            DumpStage::Tokens
+        } else if self.ast {
+            DumpStage::Ast
+        } else {
+            DumpStage::Desugared
+        }
+    }
+}
@@ -0,0 +1,858 @@
+//! A stable JSON representation of the syntax tree, for tools written in other languages. Unlike
+//! the JSON of `rlox dump`, which mirrors the internal types (including one level of nesting per
+//! precedence level), it is flat and versioned.
+//!
+//! A document is an object with the following fields:
+//!
+//! | field     | content                                     |
+//! |-----------|---------------------------------------------|
+//! | `schema`  | always `"rlox-ast"`                         |
+//! | `version` | [SCHEMA_VERSION]                            |
+//! | `program` | the array of the top-level declarations     |
+//!
+//! Every declaration and expression is an object whose `kind` field determines its other fields:
+//!
+//! | kind             | fields                                                                 |
+//! |------------------|------------------------------------------------------------------------|
+//! | `var`            | `name`, `name_span`, `initializer` (an expression or `null`)           |
+//! | `expression`     | `expression`, `span`                                                   |
+//! | `print`          | `expression`, `span`                                                   |
+//! | `assign`         | `name`, `name_span`, `value`, `span`                                   |
+//! | `block`          | `declarations`, `braces` (the span from `{` to `}`)                    |
+//! | `if`             | `condition`, `then`, `else` (a declaration or `null`), `span`          |
+//! | `while`          | `condition`, `body`, `span`                                            |
+//! | `for`            | `init`, `condition`, `update`, `body` (the blocks without `kind`), `span` |
+//! | `desugared_for`  | `body`, the block with the init declarations and the while loop, `span` |
+//! | `binary`         | `operator`, `operator_span`, `left`, `right`                           |
+//! | `unary`          | `operator` (`!` or `-`), `operator_span`, `operand`                    |
+//! | `group`          | `expression`, an expression in parentheses                             |
+//! | `number`         | `value`, `span`                                                        |
+//! | `string`         | `value`, `span`                                                        |
+//! | `boolean`        | `value`, `span`                                                        |
+//! | `nil`            | `span`                                                                 |
+//! | `variable`       | `name`, `span`                                                         |
+//!
+//! The binary operators are `or`, `and`, `==`, `!=`, `>`, `>=`, `<`, `<=`, `+`, `-`, `*` and `/`.
+//! A span is an object with a `start` and an `end` location (exclusive), and a location has a
+//! `line` and a `column` (both starting at 1) and the byte offset `pos`. The span of a statement runs
+//! from its first token to its semicolon, or to the token in front of its first block. Besides the
+//! statements, only literals, names, operators and the braces of blocks carry spans; the spans of
+//! the other nodes are derived from them.
+//!
+//! Reading a document accepts operands of any precedence: an operand which binds less tightly than
+//! its operator (e.g., an `or` as the left side of a `*`) is evaluated as if it were grouped. `for`
+//! loops are desugared into while loops, so that the program can be run.
+
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        grammar::{
+            Block, Comparison, Declaration, DesugeredFor, Equality, Expression, Factor, For,
+            IfThen, IfThenElse, LogicAnd, LogicOr, NumLiteral, Primary, Program, Statement,
+            StringLiteral, Term, Unary, VarDeclaration, Variable, While,
+        },
+        location::CodeSpan,
+    },
+    parser::desugared_for,
+};
+
+const SCHEMA_NAME: &str = "rlox-ast";
+/// Has to be increased whenever the representation changes in an incompatible way
+pub const SCHEMA_VERSION: u32 = 4;
+
+///
+/// Serializes the program into a JSON document of the current schema version
+///
+pub fn to_json(program: &Program) -> Result<String> {
+    let document = Document {
+        schema: SCHEMA_NAME.to_owned(),
+        version: SCHEMA_VERSION,
+        program: program.iter().map(JsonDeclaration::from).collect(),
+    };
+    serde_json::to_string_pretty(&document).context("error encoding the syntax tree as JSON")
+}
+
+///
+/// Reads a program from a JSON document of the current schema version. The program can be run with
+/// [crate::Interpreter::run].
+///
+pub fn from_json(json: &str) -> Result<Program> {
+    let document: Document =
+        serde_json::from_str(json).context("error decoding the syntax tree from JSON")?;
+    ensure!(
+        document.schema == SCHEMA_NAME,
+        "expected a document of the schema '{SCHEMA_NAME}', got '{schema}'",
+        schema = document.schema
+    );
+    ensure!(
+        document.version == SCHEMA_VERSION,
+        "unsupported version {version} of the syntax tree schema; expected {SCHEMA_VERSION}",
+        version = document.version
+    );
+    let declarations = document.program.into_iter().map(Declaration::from);
+    Ok(Program(declarations.collect()))
+}
+
+#[derive(Serialize, Deserialize)]
+struct Document {
+    schema: String,
+    version: u32,
+    program: Vec<JsonDeclaration>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum JsonDeclaration {
+    Var {
+        name: String,
+        name_span: CodeSpan,
+        initializer: Option<JsonExpression>,
+    },
+    Expression {
+        expression: JsonExpression,
+        span: CodeSpan,
+    },
+    Print {
+        expression: JsonExpression,
+        span: CodeSpan,
+    },
+    Assign {
+        name: String,
+        name_span: CodeSpan,
+        value: JsonExpression,
+        span: CodeSpan,
+    },
+    Block(JsonBlock),
+    If {
+        condition: JsonExpression,
+        then: Box<JsonDeclaration>,
+        #[serde(rename = "else")]
+        else_block: Option<Box<JsonDeclaration>>,
+        span: CodeSpan,
+    },
+    While {
+        condition: JsonExpression,
+        body: Box<JsonDeclaration>,
+        span: CodeSpan,
+    },
+    For {
+        init: JsonBlock,
+        condition: JsonExpression,
+        update: JsonBlock,
+        body: JsonBlock,
+        span: CodeSpan,
+    },
+    DesugaredFor {
+        body: Box<JsonDeclaration>,
+        span: CodeSpan,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonBlock {
+    declarations: Vec<JsonDeclaration>,
+    braces: CodeSpan,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum JsonExpression {
+    Binary {
+        operator: BinaryOperator,
+        operator_span: CodeSpan,
+        left: Box<JsonExpression>,
+        right: Box<JsonExpression>,
+    },
+    Unary {
+        operator: UnaryOperator,
+        operator_span: CodeSpan,
+        operand: Box<JsonExpression>,
+    },
+    Group {
+        expression: Box<JsonExpression>,
+    },
+    Number {
+        value: f64,
+        span: CodeSpan,
+    },
+    String {
+        value: String,
+        span: CodeSpan,
+    },
+    Boolean {
+        value: bool,
+        span: CodeSpan,
+    },
+    Nil {
+        span: CodeSpan,
+    },
+    Variable {
+        name: String,
+        span: CodeSpan,
+    },
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum BinaryOperator {
+    #[serde(rename = "or")]
+    Or,
+    #[serde(rename = "and")]
+    And,
+    #[serde(rename = "==")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = ">=")]
+    GreaterEqual,
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "<=")]
+    LessEqual,
+    #[serde(rename = "+")]
+    Add,
+    #[serde(rename = "-")]
+    Subtract,
+    #[serde(rename = "*")]
+    Multiply,
+    #[serde(rename = "/")]
+    Divide,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum UnaryOperator {
+    #[serde(rename = "!")]
+    Not,
+    #[serde(rename = "-")]
+    Negate,
+}
+
+impl From<&Declaration> for JsonDeclaration {
+    fn from(decl: &Declaration) -> Self {
+        match decl {
+            Declaration::Declaration(VarDeclaration::Declare(name)) => JsonDeclaration::Var {
+                name: name.value.clone(),
+                name_span: name.span,
+                initializer: None,
+            },
+            Declaration::Declaration(VarDeclaration::DeclareAndAssign(name, expr)) => {
+                JsonDeclaration::Var {
+                    name: name.value.clone(),
+                    name_span: name.span,
+                    initializer: Some(expr.into()),
+                }
+            }
+            Declaration::Statement(statement, span) => statement_json(statement, *span),
+            Declaration::Block(block) => JsonDeclaration::Block(block.into()),
+        }
+    }
+}
+
+fn statement_json(statement: &Statement, span: CodeSpan) -> JsonDeclaration {
+    match statement {
+        Statement::Expression(expr) => JsonDeclaration::Expression {
+            expression: expr.into(),
+            span,
+        },
+        Statement::Print(expr) => JsonDeclaration::Print {
+            expression: expr.into(),
+            span,
+        },
+        Statement::Assignment(var, expr) => JsonDeclaration::Assign {
+            name: var.name.value.clone(),
+            name_span: var.name.span,
+            value: expr.into(),
+            span,
+        },
+        Statement::IfThen(if_then) => if_json(if_then, None, span),
+        Statement::IfThenElse(IfThenElse {
+            if_then,
+            else_block,
+        }) => if_json(if_then, Some(else_block), span),
+        Statement::While(While { condition, block }) => JsonDeclaration::While {
+            condition: condition.into(),
+            body: Box::new(block.as_ref().into()),
+            span,
+        },
+        Statement::For(DesugeredFor { for_block }) => JsonDeclaration::DesugaredFor {
+            body: Box::new(for_block.as_ref().into()),
+            span,
+        },
+        Statement::RawFor(For {
+            init,
+            condition,
+            update,
+            block,
+        }) => JsonDeclaration::For {
+            init: init.into(),
+            condition: condition.into(),
+            update: update.into(),
+            body: block.into(),
+            span,
+        },
+    }
+}
+
+fn if_json(if_then: &IfThen, else_block: Option<&Declaration>, span: CodeSpan) -> JsonDeclaration {
+    JsonDeclaration::If {
+        condition: (&if_then.condition).into(),
+        then: Box::new(if_then.then.as_ref().into()),
+        else_block: else_block.map(|decl| Box::new(decl.into())),
+        span,
+    }
+}
+
+impl From<&Block> for JsonBlock {
+    fn from(block: &Block) -> Self {
+        JsonBlock {
+            declarations: block.as_ref().iter().map(JsonDeclaration::from).collect(),
+            braces: block.braces(),
+        }
+    }
+}
+
+impl From<JsonDeclaration> for Declaration {
+    fn from(decl: JsonDeclaration) -> Self {
+        let (statement, span) = match decl {
+            JsonDeclaration::Var {
+                name,
+                name_span,
+                initializer,
+            } => {
+                let name = StringLiteral {
+                    value: name,
+                    span: name_span,
+                };
+                let var_decl = match initializer {
+                    Some(expr) => VarDeclaration::DeclareAndAssign(name, expr.into()),
+                    None => VarDeclaration::Declare(name),
+                };
+                return Declaration::Declaration(var_decl);
+            }
+            JsonDeclaration::Block(block) => return Declaration::Block(block.into()),
+            JsonDeclaration::Expression { expression, span } => {
+                (Statement::Expression(expression.into()), span)
+            }
+            JsonDeclaration::Print { expression, span } => {
+                (Statement::Print(expression.into()), span)
+            }
+            JsonDeclaration::Assign {
+                name,
+                name_span,
+                value,
+                span,
+            } => {
+                let name = StringLiteral {
+                    value: name,
+                    span: name_span,
+                };
+                (
+                    Statement::Assignment(Variable::new(name), value.into()),
+                    span,
+                )
+            }
+            JsonDeclaration::If {
+                condition,
+                then,
+                else_block,
+                span,
+            } => {
+                let if_then = IfThen::new(condition.into(), (*then).into());
+                let statement = match else_block {
+                    Some(else_block) => {
+                        Statement::IfThenElse(IfThenElse::new(if_then, (*else_block).into()))
+                    }
+                    None => Statement::IfThen(if_then),
+                };
+                (statement, span)
+            }
+            JsonDeclaration::While {
+                condition,
+                body,
+                span,
+            } => (
+                Statement::While(While::new(condition.into(), (*body).into())),
+                span,
+            ),
+            JsonDeclaration::For {
+                init,
+                condition,
+                update,
+                body,
+                span,
+            } => {
+                let for_loop = For::new(init.into(), condition.into(), update.into(), body.into());
+                (Statement::For(desugared_for(for_loop)), span)
+            }
+            JsonDeclaration::DesugaredFor { body, span } => {
+                (Statement::For(DesugeredFor::new((*body).into())), span)
+            }
+        };
+        Declaration::Statement(statement, span)
+    }
+}
+
+impl From<JsonBlock> for Block {
+    fn from(block: JsonBlock) -> Self {
+        let declarations = block.declarations.into_iter().map(Declaration::from);
+        Block::new(declarations.collect(), block.braces)
+    }
+}
+
+impl From<&Expression> for JsonExpression {
+    fn from(expr: &Expression) -> Self {
+        match expr {
+            Expression::LogicOr(l_or) => l_or.into(),
+        }
+    }
+}
+
+fn binary(
+    operator: BinaryOperator,
+    operator_span: CodeSpan,
+    left: impl Into<JsonExpression>,
+    right: impl Into<JsonExpression>,
+) -> JsonExpression {
+    JsonExpression::Binary {
+        operator,
+        operator_span,
+        left: Box::new(left.into()),
+        right: Box::new(right.into()),
+    }
+}
+
+impl From<&LogicOr> for JsonExpression {
+    fn from(l_or: &LogicOr) -> Self {
+        match l_or {
+            LogicOr::LogicAnd(l_and) => l_and.into(),
+            LogicOr::Or {
+                left,
+                operator,
+                right,
+            } => binary(BinaryOperator::Or, *operator, left.as_ref(), right),
+        }
+    }
+}
+
+impl From<&LogicAnd> for JsonExpression {
+    fn from(l_and: &LogicAnd) -> Self {
+        match l_and {
+            LogicAnd::Equality(eq) => eq.into(),
+            LogicAnd::And {
+                left,
+                operator,
+                right,
+            } => binary(BinaryOperator::And, *operator, left.as_ref(), right),
+        }
+    }
+}
+
+impl From<&Equality> for JsonExpression {
+    fn from(eq: &Equality) -> Self {
+        match eq {
+            Equality::Comparison(comp) => comp.into(),
+            Equality::EqualityCheck {
+                left,
+                operator,
+                right,
+            } => binary(BinaryOperator::Equal, *operator, left.as_ref(), right),
+            Equality::InequalityCheck {
+                left,
+                operator,
+                right,
+            } => binary(BinaryOperator::NotEqual, *operator, left.as_ref(), right),
+        }
+    }
+}
+
+impl From<&Comparison> for JsonExpression {
+    fn from(comp: &Comparison) -> Self {
+        let (operator, left, operator_span, right) = match comp {
+            Comparison::Term(term) => return term.into(),
+            Comparison::Greater {
+                left,
+                operator,
+                right,
+            } => (BinaryOperator::Greater, left, operator, right),
+            Comparison::GreaterEqual {
+                left,
+                operator,
+                right,
+            } => (BinaryOperator::GreaterEqual, left, operator, right),
+            Comparison::Less {
+                left,
+                operator,
+                right,
+            } => (BinaryOperator::Less, left, operator, right),
+            Comparison::LessEqual {
+                left,
+                operator,
+                right,
+            } => (BinaryOperator::LessEqual, left, operator, right),
+        };
+        binary(operator, *operator_span, left.as_ref(), right)
+    }
+}
+
+impl From<&Term> for JsonExpression {
+    fn from(term: &Term) -> Self {
+        match term {
+            Term::Factor(factor) => factor.into(),
+            Term::Addition {
+                left,
+                operator,
+                right,
+            } => binary(BinaryOperator::Add, *operator, left.as_ref(), right),
+            Term::Subtraction {
+                left,
+                operator,
+                right,
+            } => binary(BinaryOperator::Subtract, *operator, left.as_ref(), right),
+        }
+    }
+}
+
+impl From<&Factor> for JsonExpression {
+    fn from(factor: &Factor) -> Self {
+        match factor {
+            Factor::Unary(unary) => unary.into(),
+            Factor::Multiplication {
+                left,
+                operator,
+                right,
+            } => binary(BinaryOperator::Multiply, *operator, left.as_ref(), right),
+            Factor::Division {
+                left,
+                operator,
+                right,
+            } => binary(BinaryOperator::Divide, *operator, left.as_ref(), right),
+        }
+    }
+}
+
+impl From<&Unary> for JsonExpression {
+    fn from(unary: &Unary) -> Self {
+        let (operator, operator_span, operand) = match unary {
+            Unary::Primary(primary) => return primary.into(),
+            Unary::LogicalNegation { operator, operand } => (UnaryOperator::Not, operator, operand),
+            Unary::ArithmNegation { operator, operand } => {
+                (UnaryOperator::Negate, operator, operand)
+            }
+        };
+        JsonExpression::Unary {
+            operator,
+            operator_span: *operator_span,
+            operand: Box::new(operand.as_ref().into()),
+        }
+    }
+}
+
+impl From<&Primary> for JsonExpression {
+    fn from(primary: &Primary) -> Self {
+        match primary {
+            Primary::Number(num) => JsonExpression::Number {
+                value: num.value,
+                span: num.span,
+            },
+            Primary::String(string) => JsonExpression::String {
+                value: string.value.clone(),
+                span: string.span,
+            },
+            Primary::Identifier(var) => JsonExpression::Variable {
+                name: var.name.value.clone(),
+                span: var.name.span,
+            },
+            Primary::True(span) => JsonExpression::Boolean {
+                value: true,
+                span: *span,
+            },
+            Primary::False(span) => JsonExpression::Boolean {
+                value: false,
+                span: *span,
+            },
+            Primary::Nil(span) => JsonExpression::Nil { span: *span },
+            Primary::GroupedExpression(expr) => JsonExpression::Group {
+                expression: Box::new(expr.as_ref().into()),
+            },
+        }
+    }
+}
+
+impl From<JsonExpression> for Expression {
+    fn from(expr: JsonExpression) -> Self {
+        let (operator, operator_span, left, right) = match expr {
+            JsonExpression::Binary {
+                operator,
+                operator_span,
+                left,
+                right,
+            } => (
+                operator,
+                operator_span,
+                Expression::from(*left),
+                Expression::from(*right),
+            ),
+            JsonExpression::Unary {
+                operator: unary_operator,
+                operator_span: operator,
+                operand,
+            } => {
+                let operand = Box::new(unary(Expression::from(*operand)));
+                let unary = match unary_operator {
+                    UnaryOperator::Not => Unary::LogicalNegation { operator, operand },
+                    UnaryOperator::Negate => Unary::ArithmNegation { operator, operand },
+                };
+                return unary.into();
+            }
+            JsonExpression::Group { expression } => {
+                return grouped(Expression::from(*expression));
+            }
+            JsonExpression::Number { value, span } => {
+                return primary(Primary::Number(NumLiteral { value, span }));
+            }
+            JsonExpression::String { value, span } => {
+                return primary(Primary::String(StringLiteral { value, span }));
+            }
+            JsonExpression::Boolean { value: true, span } => return primary(Primary::True(span)),
+            JsonExpression::Boolean { value: false, span } => {
+                return primary(Primary::False(span));
+            }
+            JsonExpression::Nil { span } => return primary(Primary::Nil(span)),
+            JsonExpression::Variable { name, span } => {
+                let name = StringLiteral { value: name, span };
+                return primary(Primary::Identifier(Variable::new(name)));
+            }
+        };
+        match operator {
+            BinaryOperator::Or => LogicOr::Or {
+                left: Box::new(logic_or(left)),
+                operator: operator_span,
+                right: logic_and(right),
+            }
+            .into(),
+            BinaryOperator::And => LogicOr::from(LogicAnd::And {
+                left: Box::new(logic_and(left)),
+                operator: operator_span,
+                right: equality(right),
+            })
+            .into(),
+            BinaryOperator::Equal => Equality::EqualityCheck {
+                left: Box::new(equality(left)),
+                operator: operator_span,
+                right: comparison(right),
+            }
+            .into(),
+            BinaryOperator::NotEqual => Equality::InequalityCheck {
+                left: Box::new(equality(left)),
+                operator: operator_span,
+                right: comparison(right),
+            }
+            .into(),
+            BinaryOperator::Greater => Comparison::Greater {
+                left: Box::new(comparison(left)),
+                operator: operator_span,
+                right: term(right),
+            }
+            .into(),
+            BinaryOperator::GreaterEqual => Comparison::GreaterEqual {
+                left: Box::new(comparison(left)),
+                operator: operator_span,
+                right: term(right),
+            }
+            .into(),
+            BinaryOperator::Less => Comparison::Less {
+                left: Box::new(comparison(left)),
+                operator: operator_span,
+                right: term(right),
+            }
+            .into(),
+            BinaryOperator::LessEqual => Comparison::LessEqual {
+                left: Box::new(comparison(left)),
+                operator: operator_span,
+                right: term(right),
+            }
+            .into(),
+            BinaryOperator::Add => Term::Addition {
+                left: Box::new(term(left)),
+                operator: operator_span,
+                right: factor(right),
+            }
+            .into(),
+            BinaryOperator::Subtract => Term::Subtraction {
+                left: Box::new(term(left)),
+                operator: operator_span,
+                right: factor(right),
+            }
+            .into(),
+            BinaryOperator::Multiply => Factor::Multiplication {
+                left: Box::new(factor(left)),
+                operator: operator_span,
+                right: unary(right),
+            }
+            .into(),
+            BinaryOperator::Divide => Factor::Division {
+                left: Box::new(factor(left)),
+                operator: operator_span,
+                right: unary(right),
+            }
+            .into(),
+        }
+    }
+}
+
+fn primary(primary: Primary) -> Expression {
+    Unary::Primary(primary).into()
+}
+
+fn grouped(expr: Expression) -> Expression {
+    primary(Primary::GroupedExpression(Box::new(expr)))
+}
+
+// The functions below narrow an expression to the given precedence level. An expression which
+// binds less tightly is put into a group, which is a primary and thus fits every level.
+
+fn logic_or(expr: Expression) -> LogicOr {
+    let Expression::LogicOr(l_or) = expr;
+    l_or
+}
+
+fn logic_and(expr: Expression) -> LogicAnd {
+    match logic_or(expr) {
+        LogicOr::LogicAnd(l_and) => l_and,
+        l_or => logic_and(grouped(l_or.into())),
+    }
+}
+
+fn equality(expr: Expression) -> Equality {
+    match logic_and(expr) {
+        LogicAnd::Equality(eq) => eq,
+        l_and => equality(grouped(LogicOr::from(l_and).into())),
+    }
+}
+
+fn comparison(expr: Expression) -> Comparison {
+    match equality(expr) {
+        Equality::Comparison(comp) => comp,
+        eq => comparison(grouped(eq.into())),
+    }
+}
+
+fn term(expr: Expression) -> Term {
+    match comparison(expr) {
+        Comparison::Term(term) => term,
+        comp => term(grouped(comp.into())),
+    }
+}
+
+fn factor(expr: Expression) -> Factor {
+    match term(expr) {
+        Term::Factor(factor) => factor,
+        t => factor(grouped(t.into())),
+    }
+}
+
+fn unary(expr: Expression) -> Unary {
+    match factor(expr) {
+        Factor::Unary(unary) => unary,
+        f => unary(grouped(f.into())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        parser::{parse, parse_without_desugaring},
+        scanner::scan_input,
+    };
+
+    use super::{from_json, to_json};
+
+    fn round_trip(src: &str, desugar: bool) -> (String, String) {
+        let tokens = scan_input(src).expect("scanning failed");
+        let program = match desugar {
+            true => parse(&tokens),
+            false => parse_without_desugaring(&tokens),
+        }
+        .expect("parsing failed");
+        let json = to_json(&program).expect("encoding failed");
+        let decoded = from_json(&json).expect("decoding failed");
+        let expected = parse(&tokens).expect("parsing failed");
+        (format!("{expected:?}"), format!("{decoded:?}"))
+    }
+
+    #[test]
+    fn programs_survive_the_round_trip() {
+        let src = r#"
+            var a;
+            var b = (1 + 2) * -3 / 4 - 5;
+            a = !(b >= 1) == true or b != nil and "text" < "zz";
+            if (a) { print a; } else { print b; }
+            if (b <= 0) { print b; }
+            while (b > 0) { b = b - 1; }
+            { var c = a; c; }
+        "#;
+        let (expected, decoded) = round_trip(src, true);
+        assert_eq!(expected, decoded);
+    }
+
+    #[test]
+    fn for_loops_are_desugared_when_read() {
+        let src = "for {var i = 0;} {i < 3} {i = i + 1;} { print i; }";
+        let (expected, decoded) = round_trip(src, false);
+        assert_eq!(expected, decoded);
+        let (expected, decoded) = round_trip(src, true);
+        assert_eq!(expected, decoded);
+    }
+
+    #[test]
+    fn operands_of_lower_precedence_are_grouped() {
+        let json = r#"{
+            "schema": "rlox-ast",
+            "version": 4,
+            "program": [{
+                "kind": "expression",
+                "span": {
+                    "start": { "line": 1, "column": 1, "pos": 0 },
+                    "end": { "line": 1, "column": 11, "pos": 10 } },
+                "expression": {
+                    "kind": "binary",
+                    "operator": "*",
+                    "operator_span": {
+                        "start": { "line": 1, "column": 7, "pos": 6 },
+                        "end": { "line": 1, "column": 8, "pos": 7 } },
+                    "left": {
+                        "kind": "binary",
+                        "operator": "+",
+                        "operator_span": {
+                            "start": { "line": 1, "column": 3, "pos": 2 },
+                            "end": { "line": 1, "column": 4, "pos": 3 } },
+                        "left": { "kind": "number", "value": 1.0, "span": {
+                            "start": { "line": 1, "column": 1, "pos": 0 },
+                            "end": { "line": 1, "column": 2, "pos": 1 } } },
+                        "right": { "kind": "number", "value": 2.0, "span": {
+                            "start": { "line": 1, "column": 5, "pos": 4 },
+                            "end": { "line": 1, "column": 6, "pos": 5 } } }
+                    },
+                    "right": { "kind": "number", "value": 3.0, "span": {
+                        "start": { "line": 1, "column": 9, "pos": 8 },
+                        "end": { "line": 1, "column": 10, "pos": 9 } } }
+                }
+            }]
+        }"#;
+        let program = from_json(json).expect("decoding failed");
+        assert_eq!("(* (group (+ 1 2)) 3)\n", program.to_string());
+    }
+
+    #[test]
+    fn other_schemas_and_versions_are_rejected() {
+        let err = from_json(r#"{"schema": "rlox-ast", "version": 3, "program": []}"#)
+            .expect_err("version 3 is outdated");
+        assert!(err.to_string().contains("unsupported version 3"), "{err}");
+        let err = from_json(r#"{"schema": "other", "version": 4, "program": []}"#)
+            .expect_err("the schema is unknown");
+        assert!(err.to_string().contains("'other'"), "{err}");
+    }
+}
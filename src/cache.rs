@@ -0,0 +1,288 @@
+//! On-disk cache of parsed programs. Each entry is stored in a file named after the hash of the
+//! source, the format version and the maximum nesting depth the program was parsed with, as a
+//! program which is parsed with a lower limit may be rejected. An entry has the following layout:
+//!
+//! | field           | size                                      |
+//! |-----------------|-------------------------------------------|
+//! | magic `RLXC`    | 4 bytes                                   |
+//! | format version  | 2 bytes                                   |
+//! | crate version   | 1 byte length + the version string        |
+//! | source hash     | 8 bytes                                   |
+//! | source length   | 8 bytes                                   |
+//! | maximum depth   | 8 bytes                                   |
+//! | payload hash    | 8 bytes                                   |
+//! | payload         | the program, encoded with `postcard`      |
+//!
+//! All numbers are little endian. The hashes are 64-bit FNV-1a hashes.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use anyhow::{bail, ensure, Context, Result};
+
+use crate::domain::grammar::Program;
+
+const MAGIC: &[u8; 4] = b"RLXC";
+/// Has to be increased whenever the layout of the entries or the grammar types change: the
+/// encoding of `postcard` is not self-describing, so an entry written for other grammar types may
+/// decode into the wrong nodes. The test `encoding_is_pinned_to_the_format_version` fails when
+/// the encoding of the grammar changes.
+const FORMAT_VERSION: u16 = 8;
+/// Entries are not shared between releases either
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const ENTRY_EXTENSION: &str = "rloxc";
+
+///
+/// A directory with cached programs, keyed by the hash of their source and the maximum nesting
+/// depth they were parsed with
+///
+#[derive(Debug, Clone)]
+pub(crate) struct ProgramCache {
+    dir: PathBuf,
+}
+
+impl ProgramCache {
+    pub(crate) fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    ///
+    /// Loads the cached program for the given source and maximum depth. Returns `None` if there is
+    /// no entry yet and an error if the entry is corrupt or was written for a different source or
+    /// version.
+    ///
+    pub(crate) fn load(&self, source: &str, max_depth: usize) -> Result<Option<Program>> {
+        let path = self.entry_path(source, max_depth);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).with_context(|| format!("error reading cache entry at {path:?}"))
+            }
+        };
+        let program = decode_entry(&bytes, source, max_depth)
+            .with_context(|| format!("invalid cache entry at {path:?}"))?;
+        Ok(Some(program))
+    }
+
+    ///
+    /// Stores the program parsed from the given source, replacing any existing entry. The entry is
+    /// written to a temporary file first, so that concurrent runs never read a partial entry.
+    ///
+    pub(crate) fn store(&self, source: &str, max_depth: usize, program: &Program) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("error creating cache directory {:?}", self.dir))?;
+        let path = self.entry_path(source, max_depth);
+        let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+
+        let entry = encode_entry(source, max_depth, program)?;
+        let mut file = fs::File::create(&tmp_path)
+            .with_context(|| format!("error creating cache entry at {tmp_path:?}"))?;
+        file.write_all(&entry)
+            .with_context(|| format!("error writing cache entry at {tmp_path:?}"))?;
+        fs::rename(&tmp_path, &path)
+            .with_context(|| format!("error moving cache entry to {path:?}"))?;
+        Ok(())
+    }
+
+    fn entry_path(&self, source: &str, max_depth: usize) -> PathBuf {
+        let hash = fnv1a(source.as_bytes());
+        self.dir.join(format!(
+            "{hash:016x}-v{FORMAT_VERSION}-d{max_depth}.{ENTRY_EXTENSION}"
+        ))
+    }
+
+    #[cfg(test)]
+    fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+}
+
+fn encode_entry(source: &str, max_depth: usize, program: &Program) -> Result<Vec<u8>> {
+    let payload = postcard::to_stdvec(program).context("error encoding program")?;
+
+    let mut entry = Vec::with_capacity(payload.len() + 64);
+    entry.extend_from_slice(MAGIC);
+    entry.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    entry.push(CRATE_VERSION.len() as u8);
+    entry.extend_from_slice(CRATE_VERSION.as_bytes());
+    entry.extend_from_slice(&fnv1a(source.as_bytes()).to_le_bytes());
+    entry.extend_from_slice(&(source.len() as u64).to_le_bytes());
+    entry.extend_from_slice(&(max_depth as u64).to_le_bytes());
+    entry.extend_from_slice(&fnv1a(&payload).to_le_bytes());
+    entry.extend_from_slice(&payload);
+    Ok(entry)
+}
+
+fn decode_entry(bytes: &[u8], source: &str, max_depth: usize) -> Result<Program> {
+    let mut reader = EntryReader { bytes };
+
+    ensure!(reader.take(MAGIC.len())? == MAGIC, "not a cache entry");
+    let format_version = u16::from_le_bytes(reader.take_array()?);
+    ensure!(
+        format_version == FORMAT_VERSION,
+        "stale entry (format version {format_version}, expected {FORMAT_VERSION})"
+    );
+    let version_len = reader.take(1)?[0] as usize;
+    let crate_version = reader.take(version_len)?;
+    ensure!(
+        crate_version == CRATE_VERSION.as_bytes(),
+        "stale entry (written by version {v}, expected {CRATE_VERSION})",
+        v = String::from_utf8_lossy(crate_version)
+    );
+    let source_hash = u64::from_le_bytes(reader.take_array()?);
+    let source_len = u64::from_le_bytes(reader.take_array()?);
+    ensure!(
+        source_hash == fnv1a(source.as_bytes()) && source_len == source.len() as u64,
+        "stale entry (written for a different source)"
+    );
+    let entry_depth = u64::from_le_bytes(reader.take_array()?);
+    ensure!(
+        entry_depth == max_depth as u64,
+        "stale entry (parsed with the maximum depth {entry_depth}, expected {max_depth})"
+    );
+    let payload_hash = u64::from_le_bytes(reader.take_array()?);
+    ensure!(
+        payload_hash == fnv1a(reader.bytes),
+        "corrupt entry (checksum mismatch)"
+    );
+    postcard::from_bytes(reader.bytes).context("corrupt entry (program cannot be decoded)")
+}
+
+struct EntryReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> EntryReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < n {
+            bail!("corrupt entry (truncated header)");
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self.take(N)?;
+        Ok(bytes.try_into().expect("slice has the requested length"))
+    }
+}
+
+///
+/// The 64-bit FNV-1a hash of the given bytes
+///
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::{domain::grammar::Program, parser::parse, scanner::scan_input};
+
+    use super::{fnv1a, ProgramCache, FORMAT_VERSION};
+
+    const SRC: &str = "var a = 1; { var b = a + 2; print b; }";
+    const DEPTH: usize = 128;
+
+    fn parsed(src: &str) -> Program {
+        parse(&scan_input(src).expect("scanning failed")).expect("parsing failed")
+    }
+
+    fn test_cache(name: &str) -> ProgramCache {
+        let dir = std::env::temp_dir().join(format!("rlox-cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        ProgramCache::new(dir)
+    }
+
+    fn only_entry(cache: &ProgramCache) -> std::path::PathBuf {
+        let mut entries: Vec<_> = fs::read_dir(cache.dir())
+            .expect("cache dir exists")
+            .map(|entry| entry.expect("readable entry").path())
+            .collect();
+        assert_eq!(1, entries.len());
+        entries.remove(0)
+    }
+
+    #[test]
+    fn fnv1a_reference_values() {
+        assert_eq!(0xcbf29ce484222325, super::fnv1a(b""));
+        assert_eq!(0xaf63dc4c8601ec8c, super::fnv1a(b"a"));
+        assert_eq!(0x85944171f73967e8, super::fnv1a(b"foobar"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let cache = test_cache("round-trip");
+        assert!(cache.load(SRC, DEPTH).unwrap().is_none());
+
+        cache.store(SRC, DEPTH, &parsed(SRC)).unwrap();
+        let loaded = cache.load(SRC, DEPTH).unwrap().expect("entry was stored");
+
+        assert_eq!(format!("{:?}", parsed(SRC)), format!("{loaded:?}"));
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn other_sources_miss() {
+        let cache = test_cache("miss");
+        cache.store(SRC, DEPTH, &parsed(SRC)).unwrap();
+        assert!(cache.load("print 1;", DEPTH).unwrap().is_none());
+        assert!(cache.load(SRC, 3).unwrap().is_none());
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn corrupt_entries_are_detected() {
+        let cache = test_cache("corrupt");
+        cache.store(SRC, DEPTH, &parsed(SRC)).unwrap();
+        let path = only_entry(&cache);
+
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+        let error = cache.load(SRC, DEPTH).unwrap_err();
+        assert!(format!("{error:#}").contains("checksum mismatch"));
+
+        fs::write(&path, &bytes[..10]).unwrap();
+        let error = cache.load(SRC, DEPTH).unwrap_err();
+        assert!(format!("{error:#}").contains("truncated"));
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn stale_entries_are_detected() {
+        let cache = test_cache("stale");
+        cache.store(SRC, DEPTH, &parsed(SRC)).unwrap();
+        let path = only_entry(&cache);
+
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[4] = bytes[4].wrapping_add(1);
+        fs::write(&path, &bytes).unwrap();
+        let error = cache.load(SRC, DEPTH).unwrap_err();
+        assert!(format!("{error:#}").contains("format version"));
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn encoding_is_pinned_to_the_format_version() {
+        let src = "var a; var b = -1 * (2 + 3) / 4 - 5; if a == nil and !true or b >= 1 { print \"s\"; } \
+                   else { a = b != 2 < 3 > 4 <= 5; } while false { a; } \
+                   for {var i = 0;} {i < 1} {i = i + 1;} {}";
+        let payload = postcard::to_stdvec(&parsed(src)).expect("encoding failed");
+        // if this fails, the encoding of the grammar changed: increase FORMAT_VERSION and update
+        // the expected hash
+        assert_eq!((8, 0x1789a8d654c72065), (FORMAT_VERSION, fnv1a(&payload)));
+    }
+}
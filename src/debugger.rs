@@ -0,0 +1,260 @@
+//! A line-oriented debugger for the `debug` subcommand
+
+use std::{
+    collections::BTreeSet,
+    io::{BufRead, Write},
+};
+
+use crate::{domain::location::Location, render_error, Debugger, Pause, Scope};
+
+/// The name reported in the errors of the expressions entered in the debugger
+const EXPRESSION_FILE_NAME: &str = "<expression>";
+
+const HELP: &str = "\
+commands:
+  break, b [line]   set a breakpoint on the line, or list the breakpoints
+  delete, d <line>  remove the breakpoint on the line
+  step, s           run to the next declaration, stepping into blocks
+  next, n           run to the next declaration in this block, stepping over blocks
+  finish, f         run until the current block is left
+  continue, c       run to the next breakpoint
+  print, p <expr>   evaluate the expression in the current scope
+  scopes, env       show the variables of each scope
+  list, l           show the code around the current line
+  quit, q           stop the program
+  help, h           show this help";
+
+///
+/// Reads commands from the input and writes what it shows to the output; the `help` command lists
+/// them. The run is paused before its first declaration.
+///
+pub struct ConsoleDebugger<R, W> {
+    input: R,
+    output: W,
+    source: String,
+    file_name: String,
+    breakpoints: BTreeSet<u64>,
+    resume: Resume,
+    /// The location and depth of the last declaration paused at or passed, so that a breakpoint
+    /// is only hit once when the line holds several declarations of one block
+    last: Option<(Location, usize)>,
+}
+
+///
+/// When the run is paused next
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Resume {
+    /// At the next declaration
+    Step,
+    /// At the next declaration which is not nested deeper than the given depth
+    Next { depth: usize },
+    /// At the next declaration outside of the block at the given depth
+    Finish { depth: usize },
+    /// At the next breakpoint
+    Continue,
+    /// Never, as the input has ended
+    Detached,
+}
+
+impl<R: BufRead, W: Write> ConsoleDebugger<R, W> {
+    pub fn new(
+        input: R,
+        output: W,
+        source: impl Into<String>,
+        file_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            input,
+            output,
+            source: source.into(),
+            file_name: file_name.into(),
+            breakpoints: BTreeSet::new(),
+            resume: Resume::Step,
+            last: None,
+        }
+    }
+
+    fn pauses_at(&self, pause: &Pause) -> bool {
+        let start = pause.span().start;
+        // the declarations following on the line in the same block are passed; a loop running the
+        // line again starts before the last declaration, a nested block at another depth
+        let passed = self.last.is_some_and(|(last, depth)| {
+            last.line == start.line && last.pos < start.pos && depth == pause.depth()
+        });
+        let at_breakpoint = self.breakpoints.contains(&start.line) && !passed;
+        match self.resume {
+            Resume::Step => true,
+            Resume::Next { depth } => pause.depth() <= depth || at_breakpoint,
+            Resume::Finish { depth } => pause.depth() < depth || at_breakpoint,
+            Resume::Continue => at_breakpoint,
+            Resume::Detached => false,
+        }
+    }
+
+    ///
+    /// Reads and runs commands until one resumes the run
+    ///
+    fn command_loop(&mut self, pause: &Pause) -> std::io::Result<()> {
+        let line = pause.span().start.line;
+        writeln!(
+            self.output,
+            "{file}:{line}: {code}",
+            file = self.file_name,
+            code = source_line(&self.source, line).unwrap_or_default().trim()
+        )?;
+        loop {
+            write!(self.output, "(rlox) ")?;
+            self.output.flush()?;
+            let mut command = String::new();
+            if self.input.read_line(&mut command)? == 0 {
+                writeln!(self.output)?;
+                self.resume = Resume::Detached;
+                return Ok(());
+            }
+            let (name, argument) = match command.trim().split_once(char::is_whitespace) {
+                Some((name, argument)) => (name, argument.trim()),
+                None => (command.trim(), ""),
+            };
+            let resume = match name {
+                "step" | "s" => Resume::Step,
+                "next" | "n" => Resume::Next {
+                    depth: pause.depth(),
+                },
+                "finish" | "f" => Resume::Finish {
+                    depth: pause.depth(),
+                },
+                "continue" | "c" => Resume::Continue,
+                "quit" | "q" => {
+                    pause.stop();
+                    Resume::Detached
+                }
+                _ => {
+                    self.run_command(pause, name, argument)?;
+                    continue;
+                }
+            };
+            self.resume = resume;
+            return Ok(());
+        }
+    }
+
+    ///
+    /// Runs a command which does not resume the run
+    ///
+    fn run_command(&mut self, pause: &Pause, name: &str, argument: &str) -> std::io::Result<()> {
+        match name {
+            "break" | "b" if argument.is_empty() => self.list_breakpoints(),
+            "break" | "b" => match self.line_argument(argument)? {
+                Some(line) if source_line(&self.source, line).is_some() => {
+                    self.breakpoints.insert(line);
+                    writeln!(self.output, "breakpoint set on line {line}")
+                }
+                Some(line) => writeln!(self.output, "there is no line {line}"),
+                None => Ok(()),
+            },
+            "delete" | "d" => match self.line_argument(argument)? {
+                Some(line) if self.breakpoints.remove(&line) => {
+                    writeln!(self.output, "breakpoint on line {line} deleted")
+                }
+                Some(line) => writeln!(self.output, "there is no breakpoint on line {line}"),
+                None => Ok(()),
+            },
+            "print" | "p" if argument.is_empty() => {
+                writeln!(self.output, "usage: print <expression>")
+            }
+            "print" | "p" => self.print(pause, argument),
+            "scopes" | "env" => self.print_scopes(&pause.scopes()),
+            "list" | "l" => self.list(pause.span().start.line),
+            "help" | "h" => writeln!(self.output, "{HELP}"),
+            "" => Ok(()),
+            _ => writeln!(
+                self.output,
+                "unknown command '{name}'; type 'help' for a list of commands"
+            ),
+        }
+    }
+
+    fn line_argument(&mut self, argument: &str) -> std::io::Result<Option<u64>> {
+        match argument.parse() {
+            Ok(line) => Ok(Some(line)),
+            Err(_) => {
+                writeln!(self.output, "'{argument}' is not a line number")?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn list_breakpoints(&mut self) -> std::io::Result<()> {
+        if self.breakpoints.is_empty() {
+            return writeln!(self.output, "no breakpoints");
+        }
+        let lines: Vec<_> = self.breakpoints.iter().map(u64::to_string).collect();
+        writeln!(self.output, "breakpoints on lines {}", lines.join(", "))
+    }
+
+    fn print(&mut self, pause: &Pause, expression: &str) -> std::io::Result<()> {
+        match pause.evaluate(expression) {
+            Ok(value) => writeln!(self.output, "{value}"),
+            Err(errors) => {
+                for error in &errors {
+                    let rendered = render_error(error, expression, EXPRESSION_FILE_NAME, false);
+                    write!(self.output, "{rendered}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn print_scopes(&mut self, scopes: &[Scope]) -> std::io::Result<()> {
+        for scope in scopes.iter().rev() {
+            match scope.level {
+                0 => writeln!(self.output, "globals:")?,
+                level => writeln!(self.output, "block {level}:")?,
+            }
+            for (name, value) in &scope.variables {
+                match value {
+                    Some(value) => writeln!(self.output, "  {name} = {value}")?,
+                    None => writeln!(self.output, "  {name} (uninitialized)")?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Shows the lines around the current one, marking it and the breakpoints
+    ///
+    fn list(&mut self, current: u64) -> std::io::Result<()> {
+        let first = current.saturating_sub(2).max(1);
+        for line in first..=current + 2 {
+            let Some(code) = source_line(&self.source, line) else {
+                break;
+            };
+            let marker = match (line == current, self.breakpoints.contains(&line)) {
+                (true, _) => "->",
+                (false, true) => " *",
+                (false, false) => "  ",
+            };
+            writeln!(self.output, "{marker} {line:>4} | {code}")?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// The line with the given number, counted from 1
+///
+fn source_line(source: &str, line: u64) -> Option<&str> {
+    source.lines().nth(line.checked_sub(1)? as usize)
+}
+
+impl<R: BufRead, W: Write> Debugger for ConsoleDebugger<R, W> {
+    fn before_declaration(&mut self, pause: &Pause) {
+        if self.pauses_at(pause) && self.command_loop(pause).is_err() {
+            // without a way to talk to the user, the program is run to its end
+            self.resume = Resume::Detached;
+        }
+        self.last = Some((pause.span().start, pause.depth()));
+    }
+}
@@ -0,0 +1,592 @@
+//! Rendering of errors together with the source code they refer to, e.g.:
+//!
+//! ```text
+//! error[L0401]: operator + not defined for types Number and Boolean
+//!  --> script.lox:1:9
+//!   |
+//! 1 | var a = 1 + true;
+//!   |           ^ operator
+//!   |         - left operand: Number
+//!   |             ---- right operand: Boolean
+//! ```
+//!
+//! Errors of other types than the ones in [crate::errors] are rendered with their message only.
+
+use std::{
+    fmt::{Display, Write},
+    io::IsTerminal,
+};
+
+use crate::{
+    domain::location::{CodeSpan, Location},
+    errors::{explain, CompileError, ParseError, ResolveError, RuntimeError, ScanError},
+};
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+///
+/// An error message with labelled spans of the source code it refers to. The first primary label
+/// determines the location reported in the header.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    message: String,
+    /// The stable code of the error, see [crate::errors::explain]
+    code: Option<&'static str>,
+    labels: Vec<Label>,
+    /// A hint on how to fix the error, e.g., a suggested name
+    help: Option<String>,
+    severity: Severity,
+}
+
+///
+/// Whether a diagnostic reports an error, which stops the program, or a warning about code which
+/// is likely wrong, e.g., from the linter
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Label {
+    span: CodeSpan,
+    text: String,
+    primary: bool,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            code: None,
+            labels: vec![],
+            help: None,
+            severity: Severity::Error,
+        }
+    }
+
+    pub(crate) fn with_severity(self, severity: Severity) -> Self {
+        Self { severity, ..self }
+    }
+
+    pub(crate) fn with_code(self, code: &'static str) -> Self {
+        Self {
+            code: Some(code),
+            ..self
+        }
+    }
+
+    ///
+    /// Adds a label underlined with `^`, marking the code which caused the error
+    ///
+    pub(crate) fn with_primary(mut self, span: CodeSpan, text: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            text: text.into(),
+            primary: true,
+        });
+        self
+    }
+
+    ///
+    /// Adds a label underlined with `-`, marking code which is related to the error
+    ///
+    pub(crate) fn with_secondary(mut self, span: CodeSpan, text: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            text: text.into(),
+            primary: false,
+        });
+        self
+    }
+
+    ///
+    /// Adds a hint on how to fix the error, which is rendered below the source lines
+    ///
+    pub(crate) fn with_help(self, help: impl Into<String>) -> Self {
+        Self {
+            help: Some(help.into()),
+            ..self
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn code(&self) -> Option<&'static str> {
+        self.code
+    }
+
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    ///
+    /// The start of the first primary label, if there is any
+    ///
+    pub fn location(&self) -> Option<Location> {
+        self.span().map(|span| span.start)
+    }
+
+    ///
+    /// The span of the first primary label, if there is any
+    ///
+    pub fn span(&self) -> Option<CodeSpan> {
+        self.labels
+            .iter()
+            .find(|label| label.primary)
+            .map(|label| label.span)
+    }
+
+    ///
+    /// Renders the diagnostic with the lines of the source the labels refer to
+    ///
+    pub fn render(&self, source: &str, file_name: &str, color: bool) -> String {
+        let style = Style { color };
+        let mut out = String::new();
+        let _ = self.write_rendered(&mut out, source, file_name, style);
+        out
+    }
+
+    fn write_rendered(
+        &self,
+        out: &mut String,
+        source: &str,
+        file_name: &str,
+        style: Style,
+    ) -> std::fmt::Result {
+        let severity = self.severity.name();
+        let header = match self.code {
+            Some(code) => format!("{severity}[{code}]"),
+            None => severity.to_owned(),
+        };
+        let header_color = match self.severity {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+        };
+        writeln!(
+            out,
+            "{header}: {message}",
+            header = style.paint(header_color, &header),
+            message = style.paint(BOLD, &self.message)
+        )?;
+        let Some(location) = self.location() else {
+            return self.write_help(out, 0, style);
+        };
+
+        let mut lines: Vec<u64> = self
+            .labels
+            .iter()
+            .flat_map(|l| [l.span.start.line, l.span.end.line])
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+        let width = lines.last().map_or(1, |line| line.to_string().len());
+        let gutter = style.paint(BLUE, &format!("{:width$} |", ""));
+
+        writeln!(
+            out,
+            "{:width$}{arrow} {file_name}:{line}:{column}",
+            "",
+            arrow = style.paint(BLUE, "-->"),
+            line = location.line,
+            column = location.column
+        )?;
+        writeln!(out, "{gutter}")?;
+        for line in lines {
+            let Some((line_start, text)) = source_line(source, line, &self.labels) else {
+                continue;
+            };
+            let number = style.paint(BLUE, &format!("{line:width$} |"));
+            writeln!(out, "{number} {text}")?;
+            for label in self.labels.iter() {
+                let Some((offset, len)) = underline(label.span, line, line_start, text) else {
+                    continue;
+                };
+                let (marker, c) = if label.primary {
+                    (RED, "^")
+                } else {
+                    (BLUE, "-")
+                };
+                let mut underline = c.repeat(len);
+                if !label.text.is_empty() && label.span.end.line == line {
+                    write!(underline, " {}", label.text)?;
+                }
+                // widths of formatting arguments are limited to u16, lines are not
+                writeln!(
+                    out,
+                    "{gutter} {}{}",
+                    " ".repeat(offset),
+                    style.paint(marker, &underline)
+                )?;
+            }
+        }
+        self.write_help(out, width, style)
+    }
+
+    fn write_help(&self, out: &mut String, width: usize, style: Style) -> std::fmt::Result {
+        match &self.help {
+            Some(help) => writeln!(
+                out,
+                "{:width$} {equals} {help_label}: {help}",
+                "",
+                equals = style.paint(BLUE, "="),
+                help_label = style.paint(BOLD, "help")
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+///
+/// The diagnostic of the error, if it is one of the errors reported by the interpreter
+///
+pub fn diagnostic_of(error: &anyhow::Error) -> Option<Diagnostic> {
+    if let Some(e) = error.downcast_ref::<ScanError>() {
+        Some(e.diagnostic())
+    } else if let Some(e) = error.downcast_ref::<ParseError>() {
+        Some(e.diagnostic())
+    } else if let Some(e) = error.downcast_ref::<ResolveError>() {
+        Some(e.diagnostic())
+    } else if let Some(e) = error.downcast_ref::<CompileError>() {
+        Some(e.diagnostic())
+    } else {
+        error
+            .downcast_ref::<RuntimeError>()
+            .map(RuntimeError::diagnostic)
+    }
+}
+
+///
+/// Renders the error with the source it refers to if it is one of the errors reported by the
+/// interpreter, otherwise with its message only
+///
+pub fn render_error(error: &anyhow::Error, source: &str, file_name: &str, color: bool) -> String {
+    match diagnostic_of(error) {
+        Some(diagnostic) => diagnostic.render(source, file_name, color),
+        None => {
+            let style = Style { color };
+            format!("{}: {error}\n", style.paint(RED, "error"))
+        }
+    }
+}
+
+///
+/// The format in which errors are reported
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ErrorFormat {
+    /// Rendered with the source snippets they refer to
+    #[default]
+    Human,
+    /// One JSON object per line and error
+    Json,
+    /// A SARIF 2.1.0 log, e.g., for code scanning in CI
+    Sarif,
+}
+
+///
+/// Formats the errors of the given source in the given format. In the human-readable format, each
+/// error is rendered without colors as by [render_error], followed by an empty line.
+///
+pub fn format_errors(
+    errors: &[anyhow::Error],
+    source: &str,
+    file_name: &str,
+    format: ErrorFormat,
+) -> String {
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|error| diagnostic_of(error).unwrap_or_else(|| Diagnostic::new(format!("{error:#}"))))
+        .collect();
+    format_diagnostics(&errors, source, file_name, format)
+}
+
+///
+/// Formats the diagnostics, which may also be warnings, in the given format
+///
+pub(crate) fn format_diagnostics(
+    diagnostics: &[Diagnostic],
+    source: &str,
+    file_name: &str,
+    format: ErrorFormat,
+) -> String {
+    match format {
+        ErrorFormat::Human => diagnostics
+            .iter()
+            .map(|diagnostic| format!("{}\n", diagnostic.render(source, file_name, false)))
+            .collect(),
+        ErrorFormat::Json => diagnostics
+            .iter()
+            .map(|diagnostic| format!("{}\n", json_line(diagnostic, file_name)))
+            .collect(),
+        ErrorFormat::Sarif => format!("{}\n", sarif_log(diagnostics, file_name)),
+    }
+}
+
+fn json_line(diagnostic: &Diagnostic, file_name: &str) -> serde_json::Value {
+    let location = |location: Location| serde_json::json!({ "line": location.line, "column": location.column });
+    serde_json::json!({
+        "severity": diagnostic.severity.name(),
+        "code": diagnostic.code,
+        "message": diagnostic.message,
+        "help": diagnostic.help,
+        "file": file_name,
+        "start": diagnostic.span().map(|span| location(span.start)),
+        "end": diagnostic.span().map(|span| location(span.end)),
+    })
+}
+
+fn sarif_log(diagnostics: &[Diagnostic], file_name: &str) -> serde_json::Value {
+    let mut codes: Vec<_> = diagnostics.iter().filter_map(|d| d.code).collect();
+    codes.sort_unstable();
+    codes.dedup();
+    let rules: Vec<_> = codes
+        .iter()
+        .map(|code| {
+            let summary = explain(code).and_then(|e| e.lines().next()).unwrap_or(code);
+            serde_json::json!({ "id": code, "shortDescription": { "text": summary } })
+        })
+        .collect();
+
+    let results: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut result = serde_json::json!({
+                "level": diagnostic.severity.name(),
+                "message": { "text": diagnostic.message },
+            });
+            if let Some(code) = diagnostic.code {
+                result["ruleId"] = code.into();
+            }
+            if let Some(span) = diagnostic.span() {
+                result["locations"] = serde_json::json!([{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": file_name },
+                        "region": {
+                            "startLine": span.start.line,
+                            "startColumn": span.start.column,
+                            "endLine": span.end.line,
+                            "endColumn": span.end.column,
+                        }
+                    }
+                }]);
+            }
+            result
+        })
+        .collect();
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rlox",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+///
+/// Whether rendered errors should be colored: only if the given stream is a terminal and the user
+/// did not opt out via `NO_COLOR`
+///
+pub fn use_color(stream: &impl IsTerminal) -> bool {
+    stream.is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+#[derive(Clone, Copy)]
+struct Style {
+    color: bool,
+}
+
+impl Style {
+    fn paint(&self, code: &str, s: &str) -> String {
+        if self.color {
+            format!("{code}{s}{RESET}")
+        } else {
+            s.to_owned()
+        }
+    }
+}
+
+///
+/// The byte offset and the text of the given line. The line is looked up via the position of a
+/// label starting or ending on it, as the positions are exact while lines are only counted.
+///
+fn source_line<'s>(source: &'s str, line: u64, labels: &[Label]) -> Option<(usize, &'s str)> {
+    let pos = labels.iter().find_map(|l| {
+        if l.span.start.line == line {
+            Some(l.span.start.pos)
+        } else if l.span.end.line == line {
+            // the end is exclusive, its last character is the one before
+            Some(l.span.end.pos.saturating_sub(1))
+        } else {
+            None
+        }
+    })?;
+    let pos = pos.min(source.len());
+    let start = source.get(..pos)?.rfind('\n').map_or(0, |i| i + 1);
+    let end = source[pos..].find('\n').map_or(source.len(), |i| pos + i);
+    Some((start, source[start..end].trim_end_matches('\r')))
+}
+
+///
+/// The column (in characters) at which the underline of the span on the given line starts and its
+/// length, if the span starts or ends on the line. A span covering several lines is underlined up
+/// to the end of its first line and from the indentation of its last line.
+///
+fn underline(span: CodeSpan, line: u64, line_start: usize, text: &str) -> Option<(usize, usize)> {
+    let start = if span.start.line == line {
+        (span.start.pos - line_start).min(text.len())
+    } else if span.end.line == line {
+        text.len() - text.trim_start().len()
+    } else {
+        return None;
+    };
+    let end = if span.end.line == line {
+        span.end.pos.saturating_sub(line_start)
+    } else {
+        text.len()
+    };
+    let end = end.clamp(start, text.len());
+    let offset = text.get(..start).map_or(start, |s| s.chars().count());
+    let len = text
+        .get(start..end)
+        .map_or(end - start, |s| s.chars().count());
+    Some((offset, len.max(1)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::location::{CodeSpan, Location};
+
+    use super::Diagnostic;
+
+    fn span(line: u64, column: u16, pos: usize, len: usize) -> CodeSpan {
+        let start = Location { line, column, pos };
+        CodeSpan {
+            start,
+            end: start.shifted(len),
+        }
+    }
+
+    #[test]
+    fn renders_labels_below_the_source_line() {
+        let source = "var a = 1;\nvar b = a + true;";
+        let diagnostic = Diagnostic::new("operator + not defined")
+            .with_primary(span(2, 11, 21, 1), "operator")
+            .with_secondary(span(2, 9, 19, 1), "left operand")
+            .with_secondary(span(2, 13, 23, 4), "right operand");
+
+        let expected = "\
+error: operator + not defined
+ --> test.lox:2:11
+  |
+2 | var b = a + true;
+  |           ^ operator
+  |         - left operand
+  |             ---- right operand
+";
+        assert_eq!(expected, diagnostic.render(source, "test.lox", false));
+    }
+
+    #[test]
+    fn renders_help_below_the_labels() {
+        let diagnostic = Diagnostic::new("identifier 'countr' not defined")
+            .with_primary(span(1, 7, 6, 6), "not defined")
+            .with_help("a variable with a similar name exists: 'counter'");
+
+        let expected = "\
+error: identifier 'countr' not defined
+ --> f:1:7
+  |
+1 | print countr;
+  |       ^^^^^^ not defined
+  = help: a variable with a similar name exists: 'counter'
+";
+        assert_eq!(expected, diagnostic.render("print countr;", "f", false));
+    }
+
+    #[test]
+    fn colors_only_when_requested() {
+        let diagnostic = Diagnostic::new("oops").with_primary(span(1, 1, 0, 1), "here");
+        assert!(!diagnostic.render("a", "f", false).contains('\x1b'));
+        assert!(diagnostic
+            .render("a", "f", true)
+            .contains("\x1b[1;31m^ here"));
+    }
+
+    #[test]
+    fn spans_are_cut_at_the_end_of_the_line() {
+        let source = "print \"abc\ndef\";";
+        let diagnostic = Diagnostic::new("unfinished").with_primary(span(1, 7, 6, 9), "");
+        let rendered = diagnostic.render(source, "f", false);
+        assert!(
+            rendered.contains("1 | print \"abc\n  |       ^^^^\n"),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn spans_over_several_lines_are_labelled_on_their_last_line() {
+        let source = "a = (1 +\n  2);";
+        let start = Location {
+            line: 1,
+            column: 6,
+            pos: 5,
+        };
+        let end = Location {
+            line: 2,
+            column: 4,
+            pos: 12,
+        };
+        let diagnostic = Diagnostic::new("oops").with_primary(CodeSpan { start, end }, "here");
+
+        let expected = "\
+error: oops
+ --> f:1:6
+  |
+1 | a = (1 +
+  |      ^^^
+2 |   2);
+  |   ^ here
+";
+        assert_eq!(expected, diagnostic.render(source, "f", false));
+    }
+}
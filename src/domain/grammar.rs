@@ -1,15 +1,20 @@
 mod display;
+mod span;
+pub mod visit;
+pub mod visit_mut;
 
 use std::ops::Deref;
 
+use serde::{Deserialize, Serialize};
+
 mod expressions;
 mod statements;
 
-pub(crate) use expressions::*;
-pub(crate) use statements::*;
+pub use expressions::*;
+pub use statements::*;
 
-#[derive(Debug)]
-pub(crate) struct Program(pub(crate) Vec<Declaration>);
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Program(pub(crate) Vec<Declaration>);
 
 impl Deref for Program {
     type Target = [Declaration];
@@ -1,10 +1,118 @@
 use std::fmt::Display;
 
 use super::{
-    Comparison, Equality, Expression, Factor, LogicAnd, LogicOr, NumLiteral, Primary,
-    StringLiteral, Term, Unary,
+    Block, Comparison, Declaration, DesugeredFor, Equality, Expression, Factor, For, IfThen,
+    IfThenElse, LogicAnd, LogicOr, NumLiteral, Primary, Program, Statement, StringLiteral, Term,
+    Unary, VarDeclaration, Variable, While,
 };
 
+impl Display for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for declaration in self.iter() {
+            writeln!(f, "{declaration}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Declaration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Declaration::Declaration(d) => write!(f, "{d}"),
+            Declaration::Statement(s, _) => write!(f, "{s}"),
+            Declaration::Block(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+impl Display for VarDeclaration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VarDeclaration::Declare(name) => write!(f, "(var {name})"),
+            VarDeclaration::DeclareAndAssign(name, e) => write!(f, "(var {name} {e})"),
+        }
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Statement::Expression(e) => write!(f, "{e}"),
+            Statement::Print(e) => write!(f, "(print {e})"),
+            Statement::Assignment(var, e) => write!(f, "(= {var} {e})"),
+            Statement::IfThen(if_then) => write!(f, "{if_then}"),
+            Statement::IfThenElse(if_then_else) => write!(f, "{if_then_else}"),
+            Statement::While(while_loop) => write!(f, "{while_loop}"),
+            Statement::For(desugered_for) => write!(f, "{desugered_for}"),
+            Statement::RawFor(for_loop) => write!(f, "{for_loop}"),
+        }
+    }
+}
+
+impl Display for IfThen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(if {condition}", condition = self.condition)?;
+        write_nested(f, &self.then)?;
+        write!(f, ")")
+    }
+}
+
+impl Display for IfThenElse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(if {condition}", condition = self.if_then.condition)?;
+        write_nested(f, &self.if_then.then)?;
+        write_nested(f, &self.else_block)?;
+        write!(f, ")")
+    }
+}
+
+impl Display for While {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(while {condition}", condition = self.condition)?;
+        write_nested(f, &self.block)?;
+        write!(f, ")")
+    }
+}
+
+impl Display for For {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(for")?;
+        write_nested(f, &self.init)?;
+        write_nested(f, &self.condition)?;
+        write_nested(f, &self.update)?;
+        write_nested(f, &self.block)?;
+        write!(f, ")")
+    }
+}
+
+impl Display for DesugeredFor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(for")?;
+        write_nested(f, &self.for_block)?;
+        write!(f, ")")
+    }
+}
+
+impl Display for Block {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(block")?;
+        for declaration in self.as_ref() {
+            write_nested(f, declaration)?;
+        }
+        write!(f, ")")
+    }
+}
+
+///
+/// Writes the item on a new line, indenting all of its lines by one level
+///
+fn write_nested(f: &mut std::fmt::Formatter<'_>, item: &impl Display) -> std::fmt::Result {
+    for line in item.to_string().lines() {
+        write!(f, "\n  {line}")?;
+    }
+    Ok(())
+}
+
 impl Display for Expression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -17,7 +125,7 @@ impl Display for LogicOr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             LogicOr::LogicAnd(l) => write!(f, "{l}"),
-            LogicOr::Or { left, right } => write!(f, "(OR {left} {right})"),
+            LogicOr::Or { left, right, .. } => write!(f, "(OR {left} {right})"),
         }
     }
 }
@@ -26,7 +134,7 @@ impl Display for LogicAnd {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             LogicAnd::Equality(e) => write!(f, "{e}"),
-            LogicAnd::And { left, right } => write!(f, "(AND {left} {right})"),
+            LogicAnd::And { left, right, .. } => write!(f, "(AND {left} {right})"),
         }
     }
 }
@@ -35,8 +143,8 @@ impl Display for Equality {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Equality::Comparison(c) => write!(f, "{c}"),
-            Equality::EqualityCheck { left, right } => write!(f, "(== {left} {right})"),
-            Equality::InequalityCheck { left, right } => write!(f, "(!= {left} {right})"),
+            Equality::EqualityCheck { left, right, .. } => write!(f, "(== {left} {right})"),
+            Equality::InequalityCheck { left, right, .. } => write!(f, "(!= {left} {right})"),
         }
     }
 }
@@ -45,10 +153,10 @@ impl Display for Comparison {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Comparison::Term(t) => write!(f, "{t}"),
-            Comparison::Greater { left, right } => write!(f, "(> {left} {right})"),
-            Comparison::GreaterEqual { left, right } => write!(f, "(>= {left} {right})"),
-            Comparison::Less { left, right } => write!(f, "(< {left} {right})"),
-            Comparison::LessEqual { left, right } => write!(f, "(<= {left} {right})"),
+            Comparison::Greater { left, right, .. } => write!(f, "(> {left} {right})"),
+            Comparison::GreaterEqual { left, right, .. } => write!(f, "(>= {left} {right})"),
+            Comparison::Less { left, right, .. } => write!(f, "(< {left} {right})"),
+            Comparison::LessEqual { left, right, .. } => write!(f, "(<= {left} {right})"),
         }
     }
 }
@@ -57,8 +165,8 @@ impl Display for Term {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Term::Factor(factor) => write!(f, "{factor}"),
-            Term::Addition { left, right } => write!(f, "(+ {left} {right})"),
-            Term::Subtraction { left, right } => write!(f, "(- {left} {right})"),
+            Term::Addition { left, right, .. } => write!(f, "(+ {left} {right})"),
+            Term::Subtraction { left, right, .. } => write!(f, "(- {left} {right})"),
         }
     }
 }
@@ -67,8 +175,8 @@ impl Display for Factor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Factor::Unary(u) => write!(f, "{u}"),
-            Factor::Multiplication { left, right } => write!(f, "(* {left} {right})"),
-            Factor::Division { left, right } => write!(f, "(/ {left} {right})"),
+            Factor::Multiplication { left, right, .. } => write!(f, "(* {left} {right})"),
+            Factor::Division { left, right, .. } => write!(f, "(/ {left} {right})"),
         }
     }
 }
@@ -77,8 +185,8 @@ impl Display for Unary {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Unary::Primary(p) => write!(f, "{p}"),
-            Unary::LogicalNegation(u) => write!(f, "(! {u})"),
-            Unary::ArithmNegation(u) => write!(f, "(- {u})"),
+            Unary::LogicalNegation { operand: u, .. } => write!(f, "(! {u})"),
+            Unary::ArithmNegation { operand: u, .. } => write!(f, "(- {u})"),
         }
     }
 }
@@ -103,6 +211,12 @@ impl Display for NumLiteral {
     }
 }
 
+impl Display for Variable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{name}", name = self.name)
+    }
+}
+
 impl Display for StringLiteral {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{s}", s = self.as_ref())
@@ -111,9 +225,13 @@ impl Display for StringLiteral {
 
 #[cfg(test)]
 mod test {
-    use crate::domain::{
-        grammar::{Expression, Factor, NumLiteral, Primary, Unary},
-        location::Location,
+    use crate::{
+        domain::{
+            grammar::{Expression, Factor, NumLiteral, Primary, Unary},
+            location::{CodeSpan, Location},
+        },
+        parser::{parse, parse_without_desugaring},
+        scanner::scan_input,
     };
 
     #[test]
@@ -123,12 +241,17 @@ mod test {
             Primary::Number(NumLiteral::new(45.67, Location::default())).into();
         let grouped_expr = Primary::GroupedExpression(Box::new(number)); // ( 45.67 )
 
-        let negated_number = Unary::ArithmNegation(Box::new(Unary::Primary(Primary::Number(
-            NumLiteral::new(123.0, Location::default()),
-        )))); // - 123s
+        let negated_number = Unary::ArithmNegation {
+            operator: CodeSpan::default(),
+            operand: Box::new(Unary::Primary(Primary::Number(NumLiteral::new(
+                123.0,
+                Location::default(),
+            )))),
+        }; // - 123s
 
         let overall: Expression = Factor::Multiplication {
             left: Box::new(Factor::Unary(negated_number)),
+            operator: CodeSpan::default(),
             right: Unary::Primary(grouped_expr),
         }
         .into();
@@ -136,4 +259,43 @@ mod test {
         let actual = format!("{overall}");
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn program_displayed_correctly() {
+        let src = "var a = 1; if a < 2 { print a; } else { a = 2; } for {var i = 0;} {i < 2} {i = i + 1;} { }";
+        let expected = "\
+(var a 1)
+(if (< a 2)
+  (block
+    (print a))
+  (block
+    (= a 2)))
+(for
+  (block
+    (var i 0))
+  (< i 2)
+  (block
+    (= i (+ i 1)))
+  (block))
+";
+        let tokens = scan_input(src).expect("scanning failed");
+        let program = parse_without_desugaring(&tokens).expect("parsing failed");
+        assert_eq!(expected, program.to_string());
+    }
+
+    #[test]
+    fn desugared_for_displayed_correctly() {
+        let src = "for {var i = 0;} {i < 2} {i = i + 1;} { print i; }";
+        let expected = "\
+(for
+  (block
+    (var i 0)
+    (while (< i 2)
+      (block
+        (print i)
+        (= i (+ i 1))))))
+";
+        let program = parse(&scan_input(src).expect("scanning failed")).expect("parsing failed");
+        assert_eq!(expected, program.to_string());
+    }
 }
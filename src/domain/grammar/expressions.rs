@@ -1,19 +1,39 @@
 mod primary;
 
-pub(crate) use primary::*;
+use serde::{Deserialize, Serialize};
 
+pub use primary::*;
+
+use crate::domain::location::CodeSpan;
 #[cfg(test)]
 use crate::domain::location::Location;
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) enum Expression {
+///
+/// The span of an operator of the given length at the default location, as in the token streams
+/// of the parser tests
+///
+#[cfg(test)]
+fn operator_span(len: usize) -> CodeSpan {
+    CodeSpan {
+        start: Location::default(),
+        end: Location::default().shifted(len),
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub enum Expression {
     LogicOr(LogicOr),
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) enum LogicOr {
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub enum LogicOr {
     LogicAnd(LogicAnd),
-    Or { left: Box<LogicOr>, right: LogicAnd },
+    Or {
+        left: Box<LogicOr>,
+        /// The span of the `or` keyword
+        operator: CodeSpan,
+        right: LogicAnd,
+    },
 }
 
 impl From<LogicOr> for Expression {
@@ -22,11 +42,12 @@ impl From<LogicOr> for Expression {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) enum LogicAnd {
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub enum LogicAnd {
     Equality(Equality),
     And {
         left: Box<LogicAnd>,
+        operator: CodeSpan,
         right: Equality,
     },
 }
@@ -37,15 +58,18 @@ impl From<LogicAnd> for LogicOr {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) enum Equality {
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::enum_variant_names)]
+pub enum Equality {
     Comparison(Comparison),
     EqualityCheck {
         left: Box<Equality>,
+        operator: CodeSpan,
         right: Comparison,
     },
     InequalityCheck {
         left: Box<Equality>,
+        operator: CodeSpan,
         right: Comparison,
     },
 }
@@ -64,6 +88,7 @@ impl Equality {
         ))));
         Equality::EqualityCheck {
             left: Box::new(left),
+            operator: operator_span(2),
             right,
         }
     }
@@ -83,13 +108,29 @@ impl From<Equality> for Expression {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) enum Comparison {
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub enum Comparison {
     Term(Term),
-    Greater { left: Box<Comparison>, right: Term },
-    GreaterEqual { left: Box<Comparison>, right: Term },
-    Less { left: Box<Comparison>, right: Term },
-    LessEqual { left: Box<Comparison>, right: Term },
+    Greater {
+        left: Box<Comparison>,
+        operator: CodeSpan,
+        right: Term,
+    },
+    GreaterEqual {
+        left: Box<Comparison>,
+        operator: CodeSpan,
+        right: Term,
+    },
+    Less {
+        left: Box<Comparison>,
+        operator: CodeSpan,
+        right: Term,
+    },
+    LessEqual {
+        left: Box<Comparison>,
+        operator: CodeSpan,
+        right: Term,
+    },
 }
 
 #[cfg(test)]
@@ -103,6 +144,7 @@ impl Comparison {
         ))));
         Comparison::LessEqual {
             left: Box::new(left),
+            operator: operator_span(2),
             right,
         }
     }
@@ -116,11 +158,19 @@ impl From<Comparison> for Expression {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) enum Term {
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub enum Term {
     Factor(Factor),
-    Addition { left: Box<Term>, right: Factor },
-    Subtraction { left: Box<Term>, right: Factor },
+    Addition {
+        left: Box<Term>,
+        operator: CodeSpan,
+        right: Factor,
+    },
+    Subtraction {
+        left: Box<Term>,
+        operator: CodeSpan,
+        right: Factor,
+    },
 }
 
 #[cfg(test)]
@@ -135,6 +185,7 @@ impl Term {
         ))));
         Term::Addition {
             left: Box::new(left),
+            operator: operator_span(1),
             right,
         }
     }
@@ -147,11 +198,19 @@ impl From<Term> for Expression {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) enum Factor {
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub enum Factor {
     Unary(Unary),
-    Multiplication { left: Box<Factor>, right: Unary },
-    Division { left: Box<Factor>, right: Unary },
+    Multiplication {
+        left: Box<Factor>,
+        operator: CodeSpan,
+        right: Unary,
+    },
+    Division {
+        left: Box<Factor>,
+        operator: CodeSpan,
+        right: Unary,
+    },
 }
 
 #[cfg(test)]
@@ -167,6 +226,7 @@ impl Factor {
         )));
         Factor::Multiplication {
             left: Box::new(left),
+            operator: operator_span(1),
             right,
         }
     }
@@ -179,19 +239,29 @@ impl From<Factor> for Expression {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) enum Unary {
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub enum Unary {
     Primary(Primary),
-    LogicalNegation(Box<Unary>),
-    ArithmNegation(Box<Unary>),
+    LogicalNegation {
+        operator: CodeSpan,
+        operand: Box<Unary>,
+    },
+    ArithmNegation {
+        operator: CodeSpan,
+        operand: Box<Unary>,
+    },
 }
 
 #[cfg(test)]
 impl Unary {
     pub(crate) fn string_arithm_negation(i: impl Into<String>) -> Self {
-        Unary::ArithmNegation(Box::new(Unary::Primary(Primary::String(
-            StringLiteral::new_string(i.into(), Location::default()),
-        ))))
+        Unary::ArithmNegation {
+            operator: operator_span(1),
+            operand: Box::new(Unary::Primary(Primary::String(StringLiteral::new_string(
+                i.into(),
+                Location::default(),
+            )))),
+        }
     }
 }
 
@@ -1,4 +1,5 @@
 use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
 
 use crate::domain::{
     location::{CodeSpan, Location},
@@ -10,11 +11,11 @@ use super::Expression;
 #[cfg(test)]
 use super::{Comparison, Equality, Factor, Term, Unary};
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) enum Primary {
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub enum Primary {
     Number(NumLiteral),
     String(StringLiteral),
-    Identifier(StringLiteral),
+    Identifier(Variable),
     True(CodeSpan),
     False(CodeSpan),
     Nil(CodeSpan),
@@ -81,8 +82,8 @@ impl Primary {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub(crate) struct StringLiteral {
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct StringLiteral {
     pub(crate) value: String,
     pub(crate) span: CodeSpan,
 }
@@ -106,12 +107,20 @@ impl StringLiteral {
         Self::new(value, span)
     }
 
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn span(&self) -> CodeSpan {
+        self.span
+    }
+
     pub(crate) fn identifier_from_token(token: &Token) -> Result<Self> {
         let TokenType::Identifier(iden) = token.t_type() else {
             bail!("current is not an identifier")
         };
         let start = token.location();
-        Ok(Self::new_identifier(iden, start))
+        Ok(Self::new_identifier(iden.as_ref(), start))
     }
 }
 
@@ -121,8 +130,61 @@ impl AsRef<str> for StringLiteral {
     }
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
-pub(crate) struct NumLiteral {
+///
+/// A use of a variable, either read in an expression or as the target of an assignment. The
+/// `binding` is filled in by the resolver and states where the variable lives at runtime; it stays
+/// `None` for variables which could not be resolved statically.
+///
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct Variable {
+    pub(crate) name: StringLiteral,
+    pub(crate) binding: Option<Binding>,
+    /// For variables which could not be resolved, the most similar name in scope, filled in by the
+    /// resolver to be suggested if the variable is still undefined when the program runs
+    #[serde(skip)]
+    pub(crate) suggestion: Option<String>,
+}
+
+impl Variable {
+    pub(crate) fn new(name: StringLiteral) -> Self {
+        Self {
+            name,
+            binding: None,
+            suggestion: None,
+        }
+    }
+
+    pub fn name(&self) -> &StringLiteral {
+        &self.name
+    }
+
+    ///
+    /// `None` until the program has been resolved, which happens when it is run
+    ///
+    pub fn binding(&self) -> Option<Binding> {
+        self.binding
+    }
+}
+
+///
+/// The storage location of a resolved variable
+///
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Binding {
+    /// Global variables are looked up by name
+    Global,
+    /// The variable at position `index` of the block `depth` levels above the use
+    Local { depth: usize, index: usize },
+}
+
+impl AsRef<str> for Variable {
+    fn as_ref(&self) -> &str {
+        self.name.as_ref()
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct NumLiteral {
     pub(crate) value: f64,
     pub(crate) span: CodeSpan,
 }
@@ -134,6 +196,14 @@ impl NumLiteral {
         let span = CodeSpan { start, end };
         Self { value, span }
     }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn span(&self) -> CodeSpan {
+        self.span
+    }
 }
 
 impl AsRef<f64> for NumLiteral {
@@ -0,0 +1,179 @@
+//! The source spans covered by the nodes of the syntax tree. The spans of expressions are derived
+//! from the literals, identifiers and operators in them, so that they do not have to be stored
+//! separately; statements and blocks store their spans, as keywords and braces are not kept.
+
+use crate::domain::location::CodeSpan;
+
+use super::{
+    Comparison, Declaration, Equality, Expression, Factor, LogicAnd, LogicOr, Primary, Term, Unary,
+    VarDeclaration,
+};
+
+impl Expression {
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Expression::LogicOr(l_or) => l_or.span(),
+        }
+    }
+}
+
+impl LogicOr {
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            LogicOr::LogicAnd(l_and) => l_and.span(),
+            LogicOr::Or { left, right, .. } => CodeSpan::merged(left.span(), right.span()),
+        }
+    }
+}
+
+impl LogicAnd {
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            LogicAnd::Equality(eq) => eq.span(),
+            LogicAnd::And { left, right, .. } => CodeSpan::merged(left.span(), right.span()),
+        }
+    }
+}
+
+impl Equality {
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Equality::Comparison(comp) => comp.span(),
+            Equality::EqualityCheck { left, right, .. }
+            | Equality::InequalityCheck { left, right, .. } => {
+                CodeSpan::merged(left.span(), right.span())
+            }
+        }
+    }
+}
+
+impl Comparison {
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Comparison::Term(term) => term.span(),
+            Comparison::Greater { left, right, .. }
+            | Comparison::GreaterEqual { left, right, .. }
+            | Comparison::Less { left, right, .. }
+            | Comparison::LessEqual { left, right, .. } => {
+                CodeSpan::merged(left.span(), right.span())
+            }
+        }
+    }
+}
+
+impl Term {
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Term::Factor(factor) => factor.span(),
+            Term::Addition { left, right, .. } | Term::Subtraction { left, right, .. } => {
+                CodeSpan::merged(left.span(), right.span())
+            }
+        }
+    }
+}
+
+impl Factor {
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Factor::Unary(unary) => unary.span(),
+            Factor::Multiplication { left, right, .. } | Factor::Division { left, right, .. } => {
+                CodeSpan::merged(left.span(), right.span())
+            }
+        }
+    }
+}
+
+impl Unary {
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Unary::Primary(primary) => primary.span(),
+            Unary::LogicalNegation { operator, operand }
+            | Unary::ArithmNegation { operator, operand } => {
+                CodeSpan::merged(*operator, operand.span())
+            }
+        }
+    }
+}
+
+impl Primary {
+    ///
+    /// The span of a grouped expression does not include the parentheses
+    ///
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Primary::Number(num) => num.span,
+            Primary::String(string) => string.span,
+            Primary::Identifier(var) => var.name.span,
+            Primary::True(span) | Primary::False(span) | Primary::Nil(span) => *span,
+            Primary::GroupedExpression(expr) => expr.span(),
+        }
+    }
+}
+
+impl Declaration {
+    ///
+    /// The span of the declaration; the span of a block runs from its opening to its closing brace
+    ///
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Declaration::Declaration(var_decl) => var_decl.span(),
+            Declaration::Statement(_, span) => *span,
+            Declaration::Block(block) => block.braces(),
+        }
+    }
+}
+
+impl VarDeclaration {
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            VarDeclaration::Declare(iden) => iden.span,
+            VarDeclaration::DeclareAndAssign(iden, expr) => {
+                CodeSpan::merged(iden.span, expr.span())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::grammar::{Declaration, Program, Statement},
+        parser::parse,
+        scanner::scan_input,
+    };
+
+    fn parsed(src: &str) -> Program {
+        parse(&scan_input(src).expect("scanning failed")).expect("parsing failed")
+    }
+
+    fn expression_span(src: &str) -> (usize, usize) {
+        let program = parsed(src);
+        let Declaration::Statement(Statement::Print(expr) | Statement::Expression(expr), _) =
+            &program[0]
+        else {
+            panic!("expected expression")
+        };
+        (expr.span().start.pos, expr.span().end.pos)
+    }
+
+    fn declaration_span(src: &str) -> (usize, usize) {
+        let span = parsed(src)[0].span();
+        (span.start.pos, span.end.pos)
+    }
+
+    #[test]
+    fn expression_spans() {
+        assert_eq!((6, 15), expression_span("print 1 + a * 3;"));
+        assert_eq!((0, 11), expression_span("-1 < 2 or b;"));
+    }
+
+    #[test]
+    fn statement_spans() {
+        assert_eq!((0, 10), declaration_span("a = 1 + 2;"));
+        assert_eq!((0, 8), declaration_span("print 1;"));
+        assert_eq!((0, 11), declaration_span("while a < 1 { }"));
+        assert_eq!((0, 4), declaration_span("if a { } else { }"));
+        assert_eq!((0, 3), declaration_span("for {} {true} {} { }"));
+        assert_eq!((0, 12), declaration_span("{ print 1; }"));
+    }
+}
@@ -1,52 +1,85 @@
-use super::{Expression, StringLiteral};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
-pub(crate) enum Declaration {
+use crate::domain::location::CodeSpan;
+
+use super::{Expression, StringLiteral, Variable};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::enum_variant_names)]
+pub enum Declaration {
     Declaration(VarDeclaration),
-    Statement(Statement),
+    /// A statement with its span, which runs from the first token of the statement to its
+    /// semicolon, or to the token in front of its first block
+    Statement(Statement, CodeSpan),
     Block(Block),
 }
 
-#[derive(Debug)]
-pub(crate) enum VarDeclaration {
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum VarDeclaration {
     Declare(StringLiteral),
     DeclareAndAssign(StringLiteral, Expression),
 }
 
-#[derive(Debug)]
-pub(crate) enum Statement {
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Statement {
     Expression(Expression),
     Print(Expression),
-    Assignment(StringLiteral, Expression),
+    Assignment(Variable, Expression),
     IfThen(IfThen),
     IfThenElse(IfThenElse),
     While(While),
     For(DesugeredFor),
+    /// A for loop as written in the source; only produced when parsing without desugaring
+    RawFor(For),
 }
 
-#[derive(Debug)]
-pub(crate) struct Block(Vec<Declaration>);
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Block {
+    declarations: Vec<Declaration>,
+    /// The span from the opening to the closing brace
+    braces: CodeSpan,
+}
 
 impl Block {
+    pub(crate) fn new(declarations: Vec<Declaration>, braces: CodeSpan) -> Self {
+        Self {
+            declarations,
+            braces,
+        }
+    }
+
     pub(crate) fn into_inner(self) -> Vec<Declaration> {
-        self.0
+        self.declarations
+    }
+
+    pub(crate) fn retain(&mut self, keep: impl FnMut(&Declaration) -> bool) {
+        self.declarations.retain(keep);
+    }
+
+    ///
+    /// The span from the opening to the closing brace, i.e., the scope of the variables declared
+    /// in the block
+    ///
+    pub fn braces(&self) -> CodeSpan {
+        self.braces
     }
 }
 
 impl AsRef<[Declaration]> for Block {
     fn as_ref(&self) -> &[Declaration] {
-        &self.0
+        &self.declarations
     }
 }
 
-impl Into<Block> for Vec<Declaration> {
-    fn into(self) -> Block {
-        Block(self)
+impl AsMut<[Declaration]> for Block {
+    fn as_mut(&mut self) -> &mut [Declaration] {
+        &mut self.declarations
     }
 }
 
-#[derive(Debug)]
-pub(crate) struct IfThen {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IfThen {
     pub(crate) condition: Expression,
     pub(crate) then: Box<Declaration>,
 }
@@ -58,10 +91,18 @@ impl IfThen {
             then: Box::new(then),
         }
     }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn then(&self) -> &Declaration {
+        &self.then
+    }
 }
 
-#[derive(Debug)]
-pub(crate) struct IfThenElse {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IfThenElse {
     pub(crate) if_then: IfThen,
     pub(crate) else_block: Box<Declaration>,
 }
@@ -73,10 +114,18 @@ impl IfThenElse {
             else_block: Box::new(else_block),
         }
     }
+
+    pub fn if_then(&self) -> &IfThen {
+        &self.if_then
+    }
+
+    pub fn else_block(&self) -> &Declaration {
+        &self.else_block
+    }
 }
 
-#[derive(Debug)]
-pub(crate) struct While {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct While {
     pub(crate) condition: Expression,
     pub(crate) block: Box<Declaration>,
 }
@@ -88,10 +137,18 @@ impl While {
             block: Box::new(block),
         }
     }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn block(&self) -> &Declaration {
+        &self.block
+    }
 }
 
-#[derive(Debug)]
-pub(crate) struct For {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct For {
     pub(crate) init: Block,
     pub(crate) condition: Expression,
     pub(crate) update: Block,
@@ -107,10 +164,26 @@ impl For {
             block,
         }
     }
+
+    pub fn init(&self) -> &Block {
+        &self.init
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn update(&self) -> &Block {
+        &self.update
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
 }
 
-#[derive(Debug)]
-pub(crate) struct DesugeredFor {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DesugeredFor {
     pub(crate) for_block: Box<Declaration>,
 }
 
@@ -120,4 +193,11 @@ impl DesugeredFor {
             for_block: Box::new(for_block),
         }
     }
+
+    ///
+    /// The block which declares the loop variables and contains the while loop
+    ///
+    pub fn for_block(&self) -> &Declaration {
+        &self.for_block
+    }
 }
@@ -0,0 +1,367 @@
+//! Traversal of the syntax tree by shared reference. A [Visitor] overrides the methods of the nodes
+//! it is interested in; the default methods walk into the children via the `walk_*` functions,
+//! which an overriding method calls to continue the traversal below its node.
+//!
+//! ```
+//! use rlox::domain::grammar::{visit::Visitor, Variable};
+//!
+//! #[derive(Default)]
+//! struct VariableUses<'ast>(Vec<&'ast str>);
+//!
+//! impl<'ast> Visitor<'ast> for VariableUses<'ast> {
+//!     fn visit_variable(&mut self, var: &'ast Variable) {
+//!         self.0.push(var.name().value());
+//!     }
+//! }
+//!
+//! let program = rlox::parse("var a = 1; print a + b;").unwrap();
+//! let mut uses = VariableUses::default();
+//! uses.visit_program(&program);
+//! assert_eq!(vec!["a", "b"], uses.0);
+//! ```
+
+use super::{
+    Block, Comparison, Declaration, DesugeredFor, Equality, Expression, Factor, For, IfThen,
+    IfThenElse, LogicAnd, LogicOr, Primary, Program, Statement, Term, Unary, VarDeclaration,
+    Variable, While,
+};
+
+///
+/// Visits the nodes of the syntax tree in the order in which they are evaluated. The lifetime
+/// `'ast` allows a visitor to keep references to the nodes it visits.
+///
+pub trait Visitor<'ast> {
+    fn visit_program(&mut self, program: &'ast Program) {
+        walk_program(self, program)
+    }
+
+    fn visit_declaration(&mut self, decl: &'ast Declaration) {
+        walk_declaration(self, decl)
+    }
+
+    fn visit_var_declaration(&mut self, var_decl: &'ast VarDeclaration) {
+        walk_var_declaration(self, var_decl)
+    }
+
+    fn visit_block(&mut self, block: &'ast Block) {
+        walk_block(self, block)
+    }
+
+    fn visit_statement(&mut self, statement: &'ast Statement) {
+        walk_statement(self, statement)
+    }
+
+    ///
+    /// The assigned variable is visited with [Visitor::visit_variable] after the value; override
+    /// this method to tell assignments from reads
+    ///
+    fn visit_assignment(&mut self, var: &'ast Variable, value: &'ast Expression) {
+        walk_assignment(self, var, value)
+    }
+
+    fn visit_if_then(&mut self, if_then: &'ast IfThen) {
+        walk_if_then(self, if_then)
+    }
+
+    fn visit_if_then_else(&mut self, if_then_else: &'ast IfThenElse) {
+        walk_if_then_else(self, if_then_else)
+    }
+
+    fn visit_while(&mut self, while_loop: &'ast While) {
+        walk_while(self, while_loop)
+    }
+
+    fn visit_for(&mut self, for_loop: &'ast For) {
+        walk_for(self, for_loop)
+    }
+
+    fn visit_desugared_for(&mut self, for_loop: &'ast DesugeredFor) {
+        walk_desugared_for(self, for_loop)
+    }
+
+    fn visit_expression(&mut self, expr: &'ast Expression) {
+        walk_expression(self, expr)
+    }
+
+    fn visit_logic_or(&mut self, l_or: &'ast LogicOr) {
+        walk_logic_or(self, l_or)
+    }
+
+    fn visit_logic_and(&mut self, l_and: &'ast LogicAnd) {
+        walk_logic_and(self, l_and)
+    }
+
+    fn visit_equality(&mut self, eq: &'ast Equality) {
+        walk_equality(self, eq)
+    }
+
+    fn visit_comparison(&mut self, comp: &'ast Comparison) {
+        walk_comparison(self, comp)
+    }
+
+    fn visit_term(&mut self, term: &'ast Term) {
+        walk_term(self, term)
+    }
+
+    fn visit_factor(&mut self, factor: &'ast Factor) {
+        walk_factor(self, factor)
+    }
+
+    fn visit_unary(&mut self, unary: &'ast Unary) {
+        walk_unary(self, unary)
+    }
+
+    fn visit_primary(&mut self, primary: &'ast Primary) {
+        walk_primary(self, primary)
+    }
+
+    ///
+    /// A variable which is read, or the target of an assignment
+    ///
+    fn visit_variable(&mut self, _var: &'ast Variable) {}
+}
+
+pub fn walk_program<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, program: &'ast Program) {
+    for decl in program.iter() {
+        visitor.visit_declaration(decl);
+    }
+}
+
+pub fn walk_declaration<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, decl: &'ast Declaration) {
+    match decl {
+        Declaration::Declaration(var_decl) => visitor.visit_var_declaration(var_decl),
+        Declaration::Statement(statement, _) => visitor.visit_statement(statement),
+        Declaration::Block(block) => visitor.visit_block(block),
+    }
+}
+
+pub fn walk_var_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    var_decl: &'ast VarDeclaration,
+) {
+    match var_decl {
+        VarDeclaration::Declare(_) => (),
+        VarDeclaration::DeclareAndAssign(_, expr) => visitor.visit_expression(expr),
+    }
+}
+
+pub fn walk_block<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, block: &'ast Block) {
+    for decl in block.as_ref() {
+        visitor.visit_declaration(decl);
+    }
+}
+
+pub fn walk_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    statement: &'ast Statement,
+) {
+    match statement {
+        Statement::Expression(expr) | Statement::Print(expr) => visitor.visit_expression(expr),
+        Statement::Assignment(var, expr) => visitor.visit_assignment(var, expr),
+        Statement::IfThen(if_then) => visitor.visit_if_then(if_then),
+        Statement::IfThenElse(if_then_else) => visitor.visit_if_then_else(if_then_else),
+        Statement::While(while_loop) => visitor.visit_while(while_loop),
+        Statement::For(for_loop) => visitor.visit_desugared_for(for_loop),
+        Statement::RawFor(for_loop) => visitor.visit_for(for_loop),
+    }
+}
+
+pub fn walk_assignment<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    var: &'ast Variable,
+    value: &'ast Expression,
+) {
+    visitor.visit_expression(value);
+    visitor.visit_variable(var);
+}
+
+pub fn walk_if_then<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, if_then: &'ast IfThen) {
+    visitor.visit_expression(&if_then.condition);
+    visitor.visit_declaration(&if_then.then);
+}
+
+pub fn walk_if_then_else<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    if_then_else: &'ast IfThenElse,
+) {
+    visitor.visit_if_then(&if_then_else.if_then);
+    visitor.visit_declaration(&if_then_else.else_block);
+}
+
+pub fn walk_while<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, while_loop: &'ast While) {
+    visitor.visit_expression(&while_loop.condition);
+    visitor.visit_declaration(&while_loop.block);
+}
+
+pub fn walk_for<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, for_loop: &'ast For) {
+    visitor.visit_block(&for_loop.init);
+    visitor.visit_expression(&for_loop.condition);
+    visitor.visit_block(&for_loop.block);
+    visitor.visit_block(&for_loop.update);
+}
+
+pub fn walk_desugared_for<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    for_loop: &'ast DesugeredFor,
+) {
+    visitor.visit_declaration(&for_loop.for_block);
+}
+
+pub fn walk_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, expr: &'ast Expression) {
+    match expr {
+        Expression::LogicOr(l_or) => visitor.visit_logic_or(l_or),
+    }
+}
+
+pub fn walk_logic_or<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, l_or: &'ast LogicOr) {
+    match l_or {
+        LogicOr::LogicAnd(l_and) => visitor.visit_logic_and(l_and),
+        LogicOr::Or { left, right, .. } => {
+            visitor.visit_logic_or(left);
+            visitor.visit_logic_and(right);
+        }
+    }
+}
+
+pub fn walk_logic_and<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, l_and: &'ast LogicAnd) {
+    match l_and {
+        LogicAnd::Equality(eq) => visitor.visit_equality(eq),
+        LogicAnd::And { left, right, .. } => {
+            visitor.visit_logic_and(left);
+            visitor.visit_equality(right);
+        }
+    }
+}
+
+pub fn walk_equality<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, eq: &'ast Equality) {
+    match eq {
+        Equality::Comparison(comp) => visitor.visit_comparison(comp),
+        Equality::EqualityCheck { left, right, .. }
+        | Equality::InequalityCheck { left, right, .. } => {
+            visitor.visit_equality(left);
+            visitor.visit_comparison(right);
+        }
+    }
+}
+
+pub fn walk_comparison<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, comp: &'ast Comparison) {
+    match comp {
+        Comparison::Term(term) => visitor.visit_term(term),
+        Comparison::Greater { left, right, .. }
+        | Comparison::GreaterEqual { left, right, .. }
+        | Comparison::Less { left, right, .. }
+        | Comparison::LessEqual { left, right, .. } => {
+            visitor.visit_comparison(left);
+            visitor.visit_term(right);
+        }
+    }
+}
+
+pub fn walk_term<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, term: &'ast Term) {
+    match term {
+        Term::Factor(factor) => visitor.visit_factor(factor),
+        Term::Addition { left, right, .. } | Term::Subtraction { left, right, .. } => {
+            visitor.visit_term(left);
+            visitor.visit_factor(right);
+        }
+    }
+}
+
+pub fn walk_factor<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, factor: &'ast Factor) {
+    match factor {
+        Factor::Unary(unary) => visitor.visit_unary(unary),
+        Factor::Multiplication { left, right, .. } | Factor::Division { left, right, .. } => {
+            visitor.visit_factor(left);
+            visitor.visit_unary(right);
+        }
+    }
+}
+
+pub fn walk_unary<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, unary: &'ast Unary) {
+    match unary {
+        Unary::Primary(primary) => visitor.visit_primary(primary),
+        Unary::LogicalNegation { operand, .. } | Unary::ArithmNegation { operand, .. } => {
+            visitor.visit_unary(operand)
+        }
+    }
+}
+
+pub fn walk_primary<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, primary: &'ast Primary) {
+    match primary {
+        Primary::Identifier(var) => visitor.visit_variable(var),
+        Primary::GroupedExpression(expr) => visitor.visit_expression(expr),
+        Primary::Number(_)
+        | Primary::String(_)
+        | Primary::True(_)
+        | Primary::False(_)
+        | Primary::Nil(_) => (),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::grammar::{Expression, Unary, Variable},
+        parser::{parse, parse_without_desugaring},
+        scanner::scan_input,
+    };
+
+    use super::{walk_unary, Visitor};
+
+    #[derive(Default)]
+    struct Events(Vec<String>);
+
+    impl<'ast> Visitor<'ast> for Events {
+        fn visit_assignment(&mut self, var: &'ast Variable, value: &'ast Expression) {
+            self.visit_expression(value);
+            self.0.push(format!("assign {}", var.name.value));
+        }
+
+        fn visit_unary(&mut self, unary: &'ast Unary) {
+            if let Unary::ArithmNegation { .. } = unary {
+                self.0.push("negate".to_owned());
+            }
+            walk_unary(self, unary);
+        }
+
+        fn visit_variable(&mut self, var: &'ast Variable) {
+            self.0.push(format!("read {}", var.name.value));
+        }
+    }
+
+    fn events(src: &str) -> Vec<String> {
+        let program = parse(&scan_input(src).expect("scanning failed")).expect("parsing failed");
+        let mut events = Events::default();
+        events.visit_program(&program);
+        events.0
+    }
+
+    #[test]
+    fn visits_nodes_in_evaluation_order() {
+        let events = events("var a = -b; if (a) { a = c; } else { print (d == e) or !f; }");
+        let expected = [
+            "negate", "read b", "read a", "read c", "assign a", "read d", "read e", "read f",
+        ];
+        assert_eq!(expected.as_slice(), events);
+    }
+
+    #[test]
+    fn visits_loops() {
+        let events = events("while (a) { a = a - 1; } for {var i = 0;} {i < n} {i = i + 1;} {}");
+        let expected = [
+            "read a", "read a", "assign a", "read i", "read n", "read i", "assign i",
+        ];
+        assert_eq!(expected.as_slice(), events);
+    }
+
+    #[test]
+    fn visits_raw_for_loops() {
+        let src = "for {var i = x;} {i < n} {i = i + 1;} { print i; }";
+        let program = parse_without_desugaring(&scan_input(src).expect("scanning failed"))
+            .expect("parsing failed");
+        let mut events = Events::default();
+        events.visit_program(&program);
+        let expected = ["read x", "read i", "read n", "read i", "read i", "assign i"];
+        assert_eq!(expected.as_slice(), events.0);
+    }
+}
@@ -0,0 +1,340 @@
+//! Traversal of the syntax tree by mutable reference, for passes which change the tree in place
+//! (e.g., the resolver, which fills in the bindings of the variables). It mirrors
+//! [super::visit::Visitor]: the default methods walk into the children via the `walk_*` functions.
+//!
+//! ```
+//! use rlox::domain::grammar::{
+//!     visit_mut::{walk_primary, VisitorMut},
+//!     Primary,
+//! };
+//!
+//! /// Replaces the reads of the variable `debug` by `false`
+//! struct NoDebug;
+//!
+//! impl VisitorMut for NoDebug {
+//!     fn visit_primary(&mut self, primary: &mut Primary) {
+//!         let debug = match primary {
+//!             Primary::Identifier(var) if var.name().value() == "debug" => Some(var.name().span()),
+//!             _ => None,
+//!         };
+//!         match debug {
+//!             Some(span) => *primary = Primary::False(span),
+//!             None => walk_primary(self, primary),
+//!         }
+//!     }
+//! }
+//!
+//! let mut program = rlox::parse("print !debug;").unwrap();
+//! NoDebug.visit_program(&mut program);
+//! assert_eq!("(print (! false))", program.to_string().trim());
+//! ```
+
+use super::{
+    Block, Comparison, Declaration, DesugeredFor, Equality, Expression, Factor, For, IfThen,
+    IfThenElse, LogicAnd, LogicOr, Primary, Program, Statement, Term, Unary, VarDeclaration,
+    Variable, While,
+};
+
+///
+/// Visits the nodes of the syntax tree in the order in which they are evaluated, with the
+/// permission to change them in place
+///
+pub trait VisitorMut {
+    fn visit_program(&mut self, program: &mut Program) {
+        walk_program(self, program)
+    }
+
+    fn visit_declaration(&mut self, decl: &mut Declaration) {
+        walk_declaration(self, decl)
+    }
+
+    fn visit_var_declaration(&mut self, var_decl: &mut VarDeclaration) {
+        walk_var_declaration(self, var_decl)
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        walk_block(self, block)
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement) {
+        walk_statement(self, statement)
+    }
+
+    ///
+    /// The assigned variable is visited with [VisitorMut::visit_variable] after the value; override
+    /// this method to tell assignments from reads
+    ///
+    fn visit_assignment(&mut self, var: &mut Variable, value: &mut Expression) {
+        walk_assignment(self, var, value)
+    }
+
+    fn visit_if_then(&mut self, if_then: &mut IfThen) {
+        walk_if_then(self, if_then)
+    }
+
+    fn visit_if_then_else(&mut self, if_then_else: &mut IfThenElse) {
+        walk_if_then_else(self, if_then_else)
+    }
+
+    fn visit_while(&mut self, while_loop: &mut While) {
+        walk_while(self, while_loop)
+    }
+
+    fn visit_for(&mut self, for_loop: &mut For) {
+        walk_for(self, for_loop)
+    }
+
+    fn visit_desugared_for(&mut self, for_loop: &mut DesugeredFor) {
+        walk_desugared_for(self, for_loop)
+    }
+
+    fn visit_expression(&mut self, expr: &mut Expression) {
+        walk_expression(self, expr)
+    }
+
+    fn visit_logic_or(&mut self, l_or: &mut LogicOr) {
+        walk_logic_or(self, l_or)
+    }
+
+    fn visit_logic_and(&mut self, l_and: &mut LogicAnd) {
+        walk_logic_and(self, l_and)
+    }
+
+    fn visit_equality(&mut self, eq: &mut Equality) {
+        walk_equality(self, eq)
+    }
+
+    fn visit_comparison(&mut self, comp: &mut Comparison) {
+        walk_comparison(self, comp)
+    }
+
+    fn visit_term(&mut self, term: &mut Term) {
+        walk_term(self, term)
+    }
+
+    fn visit_factor(&mut self, factor: &mut Factor) {
+        walk_factor(self, factor)
+    }
+
+    fn visit_unary(&mut self, unary: &mut Unary) {
+        walk_unary(self, unary)
+    }
+
+    fn visit_primary(&mut self, primary: &mut Primary) {
+        walk_primary(self, primary)
+    }
+
+    ///
+    /// A variable which is read, or the target of an assignment
+    ///
+    fn visit_variable(&mut self, _var: &mut Variable) {}
+}
+
+pub fn walk_program<V: VisitorMut + ?Sized>(visitor: &mut V, program: &mut Program) {
+    for decl in program.0.iter_mut() {
+        visitor.visit_declaration(decl);
+    }
+}
+
+pub fn walk_declaration<V: VisitorMut + ?Sized>(visitor: &mut V, decl: &mut Declaration) {
+    match decl {
+        Declaration::Declaration(var_decl) => visitor.visit_var_declaration(var_decl),
+        Declaration::Statement(statement, _) => visitor.visit_statement(statement),
+        Declaration::Block(block) => visitor.visit_block(block),
+    }
+}
+
+pub fn walk_var_declaration<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    var_decl: &mut VarDeclaration,
+) {
+    match var_decl {
+        VarDeclaration::Declare(_) => (),
+        VarDeclaration::DeclareAndAssign(_, expr) => visitor.visit_expression(expr),
+    }
+}
+
+pub fn walk_block<V: VisitorMut + ?Sized>(visitor: &mut V, block: &mut Block) {
+    for decl in block.as_mut() {
+        visitor.visit_declaration(decl);
+    }
+}
+
+pub fn walk_statement<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Expression(expr) | Statement::Print(expr) => visitor.visit_expression(expr),
+        Statement::Assignment(var, expr) => visitor.visit_assignment(var, expr),
+        Statement::IfThen(if_then) => visitor.visit_if_then(if_then),
+        Statement::IfThenElse(if_then_else) => visitor.visit_if_then_else(if_then_else),
+        Statement::While(while_loop) => visitor.visit_while(while_loop),
+        Statement::For(for_loop) => visitor.visit_desugared_for(for_loop),
+        Statement::RawFor(for_loop) => visitor.visit_for(for_loop),
+    }
+}
+
+pub fn walk_assignment<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    var: &mut Variable,
+    value: &mut Expression,
+) {
+    visitor.visit_expression(value);
+    visitor.visit_variable(var);
+}
+
+pub fn walk_if_then<V: VisitorMut + ?Sized>(visitor: &mut V, if_then: &mut IfThen) {
+    visitor.visit_expression(&mut if_then.condition);
+    visitor.visit_declaration(&mut if_then.then);
+}
+
+pub fn walk_if_then_else<V: VisitorMut + ?Sized>(visitor: &mut V, if_then_else: &mut IfThenElse) {
+    visitor.visit_if_then(&mut if_then_else.if_then);
+    visitor.visit_declaration(&mut if_then_else.else_block);
+}
+
+pub fn walk_while<V: VisitorMut + ?Sized>(visitor: &mut V, while_loop: &mut While) {
+    visitor.visit_expression(&mut while_loop.condition);
+    visitor.visit_declaration(&mut while_loop.block);
+}
+
+pub fn walk_for<V: VisitorMut + ?Sized>(visitor: &mut V, for_loop: &mut For) {
+    visitor.visit_block(&mut for_loop.init);
+    visitor.visit_expression(&mut for_loop.condition);
+    visitor.visit_block(&mut for_loop.block);
+    visitor.visit_block(&mut for_loop.update);
+}
+
+pub fn walk_desugared_for<V: VisitorMut + ?Sized>(visitor: &mut V, for_loop: &mut DesugeredFor) {
+    visitor.visit_declaration(&mut for_loop.for_block);
+}
+
+pub fn walk_expression<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expression) {
+    match expr {
+        Expression::LogicOr(l_or) => visitor.visit_logic_or(l_or),
+    }
+}
+
+pub fn walk_logic_or<V: VisitorMut + ?Sized>(visitor: &mut V, l_or: &mut LogicOr) {
+    match l_or {
+        LogicOr::LogicAnd(l_and) => visitor.visit_logic_and(l_and),
+        LogicOr::Or { left, right, .. } => {
+            visitor.visit_logic_or(left);
+            visitor.visit_logic_and(right);
+        }
+    }
+}
+
+pub fn walk_logic_and<V: VisitorMut + ?Sized>(visitor: &mut V, l_and: &mut LogicAnd) {
+    match l_and {
+        LogicAnd::Equality(eq) => visitor.visit_equality(eq),
+        LogicAnd::And { left, right, .. } => {
+            visitor.visit_logic_and(left);
+            visitor.visit_equality(right);
+        }
+    }
+}
+
+pub fn walk_equality<V: VisitorMut + ?Sized>(visitor: &mut V, eq: &mut Equality) {
+    match eq {
+        Equality::Comparison(comp) => visitor.visit_comparison(comp),
+        Equality::EqualityCheck { left, right, .. }
+        | Equality::InequalityCheck { left, right, .. } => {
+            visitor.visit_equality(left);
+            visitor.visit_comparison(right);
+        }
+    }
+}
+
+pub fn walk_comparison<V: VisitorMut + ?Sized>(visitor: &mut V, comp: &mut Comparison) {
+    match comp {
+        Comparison::Term(term) => visitor.visit_term(term),
+        Comparison::Greater { left, right, .. }
+        | Comparison::GreaterEqual { left, right, .. }
+        | Comparison::Less { left, right, .. }
+        | Comparison::LessEqual { left, right, .. } => {
+            visitor.visit_comparison(left);
+            visitor.visit_term(right);
+        }
+    }
+}
+
+pub fn walk_term<V: VisitorMut + ?Sized>(visitor: &mut V, term: &mut Term) {
+    match term {
+        Term::Factor(factor) => visitor.visit_factor(factor),
+        Term::Addition { left, right, .. } | Term::Subtraction { left, right, .. } => {
+            visitor.visit_term(left);
+            visitor.visit_factor(right);
+        }
+    }
+}
+
+pub fn walk_factor<V: VisitorMut + ?Sized>(visitor: &mut V, factor: &mut Factor) {
+    match factor {
+        Factor::Unary(unary) => visitor.visit_unary(unary),
+        Factor::Multiplication { left, right, .. } | Factor::Division { left, right, .. } => {
+            visitor.visit_factor(left);
+            visitor.visit_unary(right);
+        }
+    }
+}
+
+pub fn walk_unary<V: VisitorMut + ?Sized>(visitor: &mut V, unary: &mut Unary) {
+    match unary {
+        Unary::Primary(primary) => visitor.visit_primary(primary),
+        Unary::LogicalNegation { operand, .. } | Unary::ArithmNegation { operand, .. } => {
+            visitor.visit_unary(operand)
+        }
+    }
+}
+
+pub fn walk_primary<V: VisitorMut + ?Sized>(visitor: &mut V, primary: &mut Primary) {
+    match primary {
+        Primary::Identifier(var) => visitor.visit_variable(var),
+        Primary::GroupedExpression(expr) => visitor.visit_expression(expr),
+        Primary::Number(_)
+        | Primary::String(_)
+        | Primary::True(_)
+        | Primary::False(_)
+        | Primary::Nil(_) => (),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::grammar::{Binding, Variable},
+        parser::parse,
+        scanner::scan_input,
+    };
+
+    use super::VisitorMut;
+
+    /// Binds all variables globally
+    struct BindAll;
+
+    impl VisitorMut for BindAll {
+        fn visit_variable(&mut self, var: &mut Variable) {
+            var.binding = Some(Binding::Global);
+        }
+    }
+
+    #[derive(Default)]
+    struct Bindings(Vec<Option<Binding>>);
+
+    impl<'ast> crate::domain::grammar::visit::Visitor<'ast> for Bindings {
+        fn visit_variable(&mut self, var: &'ast Variable) {
+            self.0.push(var.binding);
+        }
+    }
+
+    #[test]
+    fn changes_the_visited_nodes() {
+        let src = "var a = b; { a = c + (d); } while (e) { print -f; }";
+        let mut program =
+            parse(&scan_input(src).expect("scanning failed")).expect("parsing failed");
+        BindAll.visit_program(&mut program);
+
+        let mut bindings = Bindings::default();
+        crate::domain::grammar::visit::Visitor::visit_program(&mut bindings, &program);
+        assert_eq!(vec![Some(Binding::Global); 6], bindings.0);
+    }
+}
@@ -3,10 +3,12 @@
 
 use std::{cmp::Ordering, fmt::Display};
 
+use serde::{Deserialize, Serialize};
+
 ///
 /// Represents the start location of a token within the source code.
 ///
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub struct Location {
     pub line: u64,
     pub column: u16,
@@ -17,24 +19,12 @@ impl Location {
     pub(crate) fn shifted(&self, shift: usize) -> Self {
         Self {
             line: self.line,
-            column: self.column + shift as u16,
+            column: self
+                .column
+                .saturating_add(shift.try_into().unwrap_or(u16::MAX)),
             pos: self.pos + shift,
         }
     }
-
-    pub(crate) fn shifted_back(&self, shift: usize) -> Self {
-        if self.pos < shift {
-            panic!(
-                "Cannot shift back by {} characters, as the pos is only {}",
-                shift, self.pos
-            );
-        }
-        Self {
-            line: self.line,
-            column: self.column - shift as u16,
-            pos: self.pos - shift,
-        }
-    }
 }
 
 impl Display for Location {
@@ -78,42 +68,19 @@ impl PartialOrd for Location {
 ///
 /// Represents a span of code, i.e., the code between a start and an end location
 ///
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct CodeSpan {
     pub start: Location,
     pub end: Location,
 }
 
 impl CodeSpan {
-    pub(crate) fn extend_to_left(&self, shift: usize) -> Self {
-        Self {
-            start: self.start.shifted_back(shift),
-            ..*self
-        }
-    }
-
     pub(crate) fn merged(left: CodeSpan, right: CodeSpan) -> Self {
         Self {
             start: left.start,
             end: right.end,
         }
     }
-
-    pub(crate) fn in_between(left: CodeSpan, right: CodeSpan) -> Self {
-        Self {
-            start: left.end.shifted(1),
-            end: right.start.shifted_back(1),
-        }
-    }
-}
-
-impl Default for CodeSpan {
-    fn default() -> Self {
-        Self {
-            start: Location::default(),
-            end: Location::default(),
-        }
-    }
 }
 
 impl Display for CodeSpan {
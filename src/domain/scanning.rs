@@ -1,23 +1,39 @@
-use std::fmt::{Debug, Display};
-
-use super::location::Location;
-
-#[derive(Debug, PartialEq)]
-pub struct Token {
-    pub t_type: TokenType,
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Display},
+};
+
+use serde::Serialize;
+
+use super::location::{CodeSpan, Location};
+
+///
+/// A token of the source; identifiers and strings borrow their text from the source they were
+/// scanned from
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Token<'src> {
+    pub t_type: TokenType<'src>,
     location: Location,
 }
 
-impl Token {
-    fn new(t_type: TokenType, location: Location) -> Self {
+impl<'src> Token<'src> {
+    pub(crate) fn new(t_type: TokenType<'src>, location: Location) -> Self {
         Self { t_type, location }
     }
 
-    pub fn keyword_or_identifier(chars: impl Into<String>, location: Location) -> Self {
+    ///
+    /// The words which are scanned as keywords instead of identifiers
+    ///
+    pub const KEYWORDS: [&'static str; 16] = [
+        "print", "and", "class", "else", "false", "for", "fun", "if", "nil", "or", "return",
+        "super", "this", "true", "var", "while",
+    ];
+
+    pub fn keyword_or_identifier(chars: impl Into<Cow<'src, str>>, location: Location) -> Self {
         let chars = chars.into();
-        dbg!(&chars);
 
-        let t_type = match chars.as_str() {
+        let t_type = match chars.as_ref() {
             "print" => TokenType::PRINT,
             "and" => TokenType::AND,
             "class" => TokenType::CLASS,
@@ -47,24 +63,24 @@ impl Token {
         }
     }
 
-    pub fn string(chars: impl Into<String>, location: Location) -> Self {
-        let chars = chars.into();
+    pub fn string(chars: impl Into<Cow<'src, str>>, location: Location) -> Self {
         Self {
-            t_type: TokenType::String(chars),
+            t_type: TokenType::String(chars.into()),
             location,
         }
     }
 
-    pub fn number(n: impl Into<String>, location: Location) -> Self {
-        let n = n.into();
-        let n = n.parse().expect("string {n} cannot be parsed as a number");
+    pub fn number(n: &str, location: Location) -> Self {
+        let n = n
+            .parse()
+            .unwrap_or_else(|_| panic!("string {n} cannot be parsed as a number"));
         Self {
             t_type: TokenType::Number(n),
             location,
         }
     }
 
-    pub fn one_char(t_type: TokenType, location: Location) -> Self {
+    pub fn one_char(t_type: TokenType<'src>, location: Location) -> Self {
         if t_type.is_one_char() {
             Self::new(t_type, location)
         } else {
@@ -72,7 +88,7 @@ impl Token {
         }
     }
 
-    pub fn one_two_char(t_type: TokenType, location: Location) -> Self {
+    pub fn one_two_char(t_type: TokenType<'src>, location: Location) -> Self {
         if t_type.is_one_two_char() {
             Self::new(t_type, location)
         } else {
@@ -87,17 +103,38 @@ impl Token {
         }
     }
 
-    pub fn t_type(&self) -> &TokenType {
+    pub fn t_type(&self) -> &TokenType<'src> {
         &self.t_type
     }
 
     pub fn location(&self) -> Location {
         self.location
     }
+
+    ///
+    /// The span of the token in the source. Numbers are assumed to be written in their shortest
+    /// form, as the scanner does not keep the original lexeme.
+    ///
+    pub(crate) fn span(&self) -> CodeSpan {
+        let len = match &self.t_type {
+            TokenType::Identifier(id) => id.len(),
+            TokenType::String(s) => s.len() + 2,
+            TokenType::Number(n) => n.to_string().len(),
+            TokenType::EOF => 1,
+            t_type => {
+                let s = t_type.to_string();
+                s.strip_prefix("KW: ").map_or(s.len(), str::len)
+            }
+        };
+        CodeSpan {
+            start: self.location,
+            end: self.location.shifted(len),
+        }
+    }
 }
 
-#[derive(PartialEq, Clone)]
-pub enum TokenType {
+#[derive(PartialEq, Clone, Serialize)]
+pub enum TokenType<'src> {
     // Single-character tokens
     BraceLeft,
     BraceRight,
@@ -122,9 +159,10 @@ pub enum TokenType {
     Division,
 
     // Literals
-    Identifier(String),
+    Identifier(Cow<'src, str>),
     Number(f64),
-    String(String),
+    /// The content of a string, without the quotes
+    String(Cow<'src, str>),
 
     // Keywords
     PRINT,
@@ -148,37 +186,83 @@ pub enum TokenType {
     EOF,
 }
 
-impl TokenType {
-    fn is_one_char(&self) -> bool {
+impl TokenType<'_> {
+    ///
+    /// The token type with its text copied out of the source, e.g., to report it in an error
+    ///
+    pub fn into_owned(self) -> TokenType<'static> {
         match self {
-            TokenType::BraceLeft => true,
-            TokenType::BraceRight => true,
-            TokenType::ParenLeft => true,
-            TokenType::ParenRight => true,
-            TokenType::Comma => true,
-            TokenType::Dot => true,
-            TokenType::Minus => true,
-            TokenType::Plus => true,
-            TokenType::Semicolon => true,
-            TokenType::Star => true,
-            _ => false,
+            TokenType::Identifier(id) => TokenType::Identifier(Cow::Owned(id.into_owned())),
+            TokenType::String(s) => TokenType::String(Cow::Owned(s.into_owned())),
+            TokenType::BraceLeft => TokenType::BraceLeft,
+            TokenType::BraceRight => TokenType::BraceRight,
+            TokenType::ParenLeft => TokenType::ParenLeft,
+            TokenType::ParenRight => TokenType::ParenRight,
+            TokenType::Comma => TokenType::Comma,
+            TokenType::Dot => TokenType::Dot,
+            TokenType::Minus => TokenType::Minus,
+            TokenType::Plus => TokenType::Plus,
+            TokenType::Semicolon => TokenType::Semicolon,
+            TokenType::Star => TokenType::Star,
+            TokenType::EqualEqual => TokenType::EqualEqual,
+            TokenType::BangEqual => TokenType::BangEqual,
+            TokenType::LessEqual => TokenType::LessEqual,
+            TokenType::GreaterEqual => TokenType::GreaterEqual,
+            TokenType::Equal => TokenType::Equal,
+            TokenType::Bang => TokenType::Bang,
+            TokenType::Less => TokenType::Less,
+            TokenType::Greater => TokenType::Greater,
+            TokenType::Division => TokenType::Division,
+            TokenType::Number(n) => TokenType::Number(n),
+            TokenType::PRINT => TokenType::PRINT,
+            TokenType::AND => TokenType::AND,
+            TokenType::CLASS => TokenType::CLASS,
+            TokenType::ELSE => TokenType::ELSE,
+            TokenType::FALSE => TokenType::FALSE,
+            TokenType::FOR => TokenType::FOR,
+            TokenType::FUN => TokenType::FUN,
+            TokenType::IF => TokenType::IF,
+            TokenType::NIL => TokenType::NIL,
+            TokenType::OR => TokenType::OR,
+            TokenType::RETURN => TokenType::RETURN,
+            TokenType::SUPER => TokenType::SUPER,
+            TokenType::THIS => TokenType::THIS,
+            TokenType::TRUE => TokenType::TRUE,
+            TokenType::VAR => TokenType::VAR,
+            TokenType::WHILE => TokenType::WHILE,
+            TokenType::EOF => TokenType::EOF,
         }
     }
 
+    fn is_one_char(&self) -> bool {
+        matches!(
+            self,
+            TokenType::BraceLeft
+                | TokenType::BraceRight
+                | TokenType::ParenLeft
+                | TokenType::ParenRight
+                | TokenType::Comma
+                | TokenType::Dot
+                | TokenType::Minus
+                | TokenType::Plus
+                | TokenType::Semicolon
+                | TokenType::Star
+        )
+    }
+
     fn is_one_two_char(&self) -> bool {
-        match self {
-            TokenType::EqualEqual => true,
-            TokenType::BangEqual => true,
-            TokenType::LessEqual => true,
-            TokenType::GreaterEqual => true,
-            TokenType::Equal => true,
-            TokenType::Bang => true,
-            TokenType::Less => true,
-            TokenType::Greater => true,
-            TokenType::Division => true,
-
-            _ => false,
-        }
+        matches!(
+            self,
+            TokenType::EqualEqual
+                | TokenType::BangEqual
+                | TokenType::LessEqual
+                | TokenType::GreaterEqual
+                | TokenType::Equal
+                | TokenType::Bang
+                | TokenType::Less
+                | TokenType::Greater
+                | TokenType::Division
+        )
     }
 
     pub(crate) fn matches(&self, other: &Self) -> bool {
@@ -191,13 +275,13 @@ impl TokenType {
     }
 }
 
-impl Debug for TokenType {
+impl Debug for TokenType<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         fmt(self, f)
     }
 }
 
-impl Display for TokenType {
+impl Display for TokenType<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         fmt(self, f)
     }
@@ -233,7 +317,7 @@ fn fmt(t: &TokenType, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         }
         TokenType::EOF => "EOF",
         TokenType::Number(n) => {
-            tmp = format!("NUM: {num}", num = n.to_string());
+            tmp = format!("NUM: {num}", num = n);
             &tmp
         }
         TokenType::String(s) => {
@@ -261,9 +345,69 @@ fn fmt(t: &TokenType, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(f, "{s}")
 }
 
+///
+/// A token together with the exact text it was scanned from and the whitespace and comments which
+/// precede it. Concatenating the trivia and the text of all tokens of a source gives back the
+/// source.
+///
+#[derive(Debug, PartialEq)]
+pub struct LosslessToken<'src> {
+    pub leading_trivia: Vec<Trivia<'src>>,
+    pub token: Token<'src>,
+    /// The text of the token in the source, e.g., a string with its quotes; empty for EOF
+    pub text: &'src str,
+}
+
+///
+/// Source text which is not part of a token
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trivia<'src> {
+    pub kind: TriviaKind,
+    pub text: &'src str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    /// A run of spaces, tabs and line breaks
+    Whitespace,
+    /// A line comment, from the `//` up to the end of the line (excluding the line break)
+    Comment,
+}
+
+impl<'src> Trivia<'src> {
+    ///
+    /// Splits text consisting only of whitespace and line comments into its pieces
+    ///
+    pub(crate) fn split(mut text: &'src str) -> Vec<Self> {
+        let mut trivia = vec![];
+        while !text.is_empty() {
+            let (kind, len) = if text.starts_with("//") {
+                (TriviaKind::Comment, text.find('\n').unwrap_or(text.len()))
+            } else {
+                let len = text.find("//").unwrap_or(text.len());
+                (TriviaKind::Whitespace, len)
+            };
+            trivia.push(Trivia {
+                kind,
+                text: &text[..len],
+            });
+            text = &text[len..];
+        }
+        trivia
+    }
+
+    ///
+    /// The number of line breaks in the trivia
+    ///
+    pub fn line_breaks(&self) -> usize {
+        self.text.matches('\n').count()
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::domain::scanning::TokenType;
+    use crate::domain::scanning::{TokenType, Trivia, TriviaKind};
 
     #[test]
     fn matches_works() {
@@ -274,4 +418,20 @@ mod test {
         assert!(!TokenType::String("a".into()).matches(&TokenType::Identifier("a".into())));
         assert!(!TokenType::String("a".into()).matches(&TokenType::AND));
     }
+
+    #[test]
+    fn trivia_is_split_into_whitespace_and_comments() {
+        let trivia = Trivia::split("  // one\n\n// two\n ");
+        let pieces: Vec<_> = trivia.iter().map(|t| (t.kind, t.text)).collect();
+        assert_eq!(
+            vec![
+                (TriviaKind::Whitespace, "  "),
+                (TriviaKind::Comment, "// one"),
+                (TriviaKind::Whitespace, "\n\n"),
+                (TriviaKind::Comment, "// two"),
+                (TriviaKind::Whitespace, "\n "),
+            ],
+            pieces
+        );
+    }
 }
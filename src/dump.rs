@@ -0,0 +1,117 @@
+//! Debug dumps of the intermediate representations of a program: the token stream, the syntax tree
+//! as written and the syntax tree after desugaring, each either as text or as JSON. The syntax tree
+//! as written is the one of `rlox ast`, in the versioned schema of [crate::ast_json].
+
+use std::fmt::Write;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::{
+    ast_json,
+    parser::{parse, parse_without_desugaring},
+    scanner::scan_input,
+};
+
+///
+/// The representation of the program to dump
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpStage {
+    /// The tokens produced by the scanner, with their locations
+    Tokens,
+    /// The program as parsed, with for loops as written. As JSON, in the schema of [ast_json].
+    Ast,
+    /// The program as interpreted, with for loops desugared into while loops
+    Desugared,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum DumpFormat {
+    /// Tokens one per line, syntax trees as S-expressions
+    #[default]
+    Text,
+    Json,
+}
+
+///
+/// Dumps the given stage of the source in the given format
+///
+pub fn dump(
+    source: &str,
+    stage: DumpStage,
+    format: DumpFormat,
+) -> Result<String, Vec<anyhow::Error>> {
+    let tokens = scan_input(source)?;
+    match (stage, format) {
+        (DumpStage::Tokens, DumpFormat::Text) => {
+            let mut dump = String::new();
+            for token in &tokens {
+                let location = token.location();
+                writeln!(
+                    dump,
+                    "{line}:{column} {t_type}",
+                    line = location.line,
+                    column = location.column,
+                    t_type = token.t_type()
+                )
+                .expect("writing to a string cannot fail");
+            }
+            Ok(dump)
+        }
+        (DumpStage::Tokens, DumpFormat::Json) => to_json(&tokens),
+        (DumpStage::Ast, DumpFormat::Text) => Ok(parse_without_desugaring(&tokens)?.to_string()),
+        (DumpStage::Ast, DumpFormat::Json) => {
+            let mut json =
+                ast_json::to_json(&parse_without_desugaring(&tokens)?).map_err(|e| vec![e])?;
+            json.push('\n');
+            Ok(json)
+        }
+        (DumpStage::Desugared, DumpFormat::Text) => Ok(parse(&tokens)?.to_string()),
+        (DumpStage::Desugared, DumpFormat::Json) => to_json(&parse(&tokens)?),
+    }
+}
+
+fn to_json(value: &impl Serialize) -> Result<String, Vec<anyhow::Error>> {
+    let mut json = serde_json::to_string_pretty(value)
+        .context("error encoding the dump as JSON")
+        .map_err(|e| vec![e])?;
+    json.push('\n');
+    Ok(json)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{dump, DumpFormat, DumpStage};
+    use crate::ast_json;
+
+    #[test]
+    fn tokens_with_locations() {
+        let actual = dump("var a =\n  1;", DumpStage::Tokens, DumpFormat::Text).unwrap();
+        assert_eq!(
+            "1:1 KW: VAR\n1:5 ID: a\n1:7 =\n2:3 NUM: 1\n2:4 ;\n2:5 EOF\n",
+            actual
+        );
+    }
+
+    #[test]
+    fn json_is_valid() {
+        let src = "for {var i = 0;} {i < 2} {i = i + 1;} { print i; }";
+        for stage in [DumpStage::Tokens, DumpStage::Ast, DumpStage::Desugared] {
+            let json = dump(src, stage, DumpFormat::Json).unwrap();
+            serde_json::from_str::<serde_json::Value>(&json).expect("dump is valid JSON");
+        }
+    }
+
+    #[test]
+    fn ast_keeps_for_loops() {
+        let src = "for {var i = 0;} {i < 2} {i = i + 1;} { }";
+        let ast = dump(src, DumpStage::Ast, DumpFormat::Json).unwrap();
+        let desugared = dump(src, DumpStage::Desugared, DumpFormat::Json).unwrap();
+        assert!(ast.contains(r#""kind": "for""#), "{ast}");
+        assert!(ast_json::from_json(&ast).is_ok());
+        assert!(!desugared.contains("RawFor"));
+        assert!(desugared.contains("While"));
+    }
+}
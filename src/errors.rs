@@ -1,19 +1,537 @@
-use std::error::Error;
+//! The errors reported by the interpreter. Each error has a stable code (e.g., `L0101`), which
+//! tools can match on and which [explain] describes in more detail:
+//!
+//! | codes   | errors           | reported by                        |
+//! |---------|------------------|------------------------------------|
+//! | `L00xx` | [ScanError]      | the scanner                        |
+//! | `L01xx` | [ParseError]     | the parser                         |
+//! | `L02xx` | [ResolveError]   | the resolver of variables          |
+//! | `L03xx` | [CompileError]   | the byte code compiler             |
+//! | `L04xx` | [RuntimeError]   | the interpreter and the VM         |
+//!
+//! The interpreter returns the errors as [anyhow::Error]s; the typed errors are available via
+//! [anyhow::Error::downcast_ref].
 
-pub struct SyntaxError {
-    line: u32,
+use std::fmt::Display;
+
+pub use crate::interpreter::error::{
+    BinaryOperatorError, IdentifierNotDefinedError, InterruptedError, LimitExceededError,
+    OutputError, RuntimeError, TypeError, UnaryOperatorError, UninitializedVariableError,
+};
+use crate::{
+    diagnostic::{diagnostic_of, Diagnostic},
+    domain::{location::CodeSpan, scanning::TokenType},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanError {
+    IllegalCharacter { character: char, span: CodeSpan },
+    UnterminatedString { content: String, span: CodeSpan },
 }
 
-impl std::fmt::Display for SyntaxError {
+impl ScanError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::IllegalCharacter { .. } => "L0001",
+            Self::UnterminatedString { .. } => "L0002",
+        }
+    }
+
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Self::IllegalCharacter { span, .. } | Self::UnterminatedString { span, .. } => *span,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let label = match self {
+            Self::IllegalCharacter { .. } => "illegal character",
+            Self::UnterminatedString { .. } => "string is never closed",
+        };
+        Diagnostic::new(self.to_string())
+            .with_code(self.code())
+            .with_primary(self.span(), label)
+    }
+}
+
+impl Display for ScanError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Syntax error on line {l}.", l = self.line)
+        match self {
+            Self::IllegalCharacter { character, .. } => {
+                write!(f, "found illegal character: '{character}'")
+            }
+            Self::UnterminatedString { content, .. } => write!(
+                f,
+                "string not completed by closing '\"'; string start: {content}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnexpectedToken {
+        /// Descriptions of the tokens which would have been valid, e.g., `identifier` or `';'`
+        expected: Vec<String>,
+        found: TokenType<'static>,
+        /// What the parser was reading when it found the token
+        context: &'static str,
+        span: CodeSpan,
+        /// Set if the declaration starts with an identifier which looks like a misspelled keyword
+        misspelled_keyword: Option<MisspelledKeyword>,
+    },
+    UnclosedBlock {
+        /// The span of the opening brace
+        opening: CodeSpan,
+        /// The span of the end of the input
+        span: CodeSpan,
+    },
+    TooDeeplyNested {
+        /// The maximum nesting depth of blocks and expressions
+        limit: usize,
+        /// The span of the token which opens the level beyond the limit
+        span: CodeSpan,
+    },
+    ChainTooLong {
+        /// The maximum number of chained binary operators
+        limit: usize,
+        /// The span of the operator beyond the limit
+        span: CodeSpan,
+    },
+}
+
+///
+/// An identifier which is probably meant to be a keyword, e.g., `whlie`
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct MisspelledKeyword {
+    /// The keyword with the most similar name
+    pub keyword: &'static str,
+    /// The span of the identifier
+    pub span: CodeSpan,
+}
+
+impl ParseError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnexpectedToken { .. } => "L0101",
+            Self::UnclosedBlock { .. } => "L0102",
+            Self::TooDeeplyNested { .. } => "L0103",
+            Self::ChainTooLong { .. } => "L0104",
+        }
+    }
+
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Self::UnexpectedToken { span, .. }
+            | Self::UnclosedBlock { span, .. }
+            | Self::TooDeeplyNested { span, .. }
+            | Self::ChainTooLong { span, .. } => *span,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::new(self.to_string()).with_code(self.code());
+        match self {
+            Self::UnexpectedToken {
+                context,
+                span,
+                misspelled_keyword,
+                ..
+            } => {
+                let diagnostic = diagnostic.with_primary(*span, format!("expected {context}"));
+                match misspelled_keyword {
+                    Some(misspelled) => diagnostic
+                        .with_secondary(misspelled.span, "not a keyword")
+                        .with_help(format!(
+                            "did you mean the keyword '{}'?",
+                            misspelled.keyword
+                        )),
+                    None => diagnostic,
+                }
+            }
+            Self::UnclosedBlock { opening, span } => diagnostic
+                .with_primary(*span, "expected '}'")
+                .with_secondary(*opening, "block opened here"),
+            Self::TooDeeplyNested { span, .. } => {
+                diagnostic.with_primary(*span, "nested too deeply")
+            }
+            Self::ChainTooLong { span, .. } => {
+                diagnostic.with_primary(*span, "operator beyond the limit")
+            }
+        }
     }
 }
 
-impl std::fmt::Debug for SyntaxError {
+impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Syntax error on line {l}.", l = self.line)
+        match self {
+            Self::UnexpectedToken {
+                expected,
+                found,
+                context,
+                ..
+            } => {
+                match expected.as_slice() {
+                    [expected] => write!(f, "Expected {expected}")?,
+                    expected => write!(f, "Expected one of {}", expected.join(", "))?,
+                }
+                write!(f, " but got '{found:?}'; Context: '{context}'")
+            }
+            Self::UnclosedBlock { opening, .. } => write!(
+                f,
+                "Expected '}}' closing the block opened in line {line} but reached the end of the input",
+                line = opening.start.line
+            ),
+            Self::TooDeeplyNested { limit, .. } => write!(
+                f,
+                "Blocks and expressions are nested deeper than the maximum nesting depth of {limit}"
+            ),
+            Self::ChainTooLong { limit, .. } => write!(
+                f,
+                "Expression chains more than {limit} binary operators"
+            ),
+        }
     }
 }
 
-impl Error for SyntaxError {}
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolveError {
+    AlreadyDeclared {
+        name: String,
+        span: CodeSpan,
+    },
+    ReadInOwnInitializer {
+        name: String,
+        span: CodeSpan,
+    },
+    UsedBeforeDeclaration {
+        name: String,
+        span: CodeSpan,
+    },
+    ReadBeforeAssignment {
+        name: String,
+        span: CodeSpan,
+        /// The span of the name in the declaration without initializer
+        declaration: CodeSpan,
+    },
+}
+
+impl ResolveError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::AlreadyDeclared { .. } => "L0201",
+            Self::ReadInOwnInitializer { .. } => "L0202",
+            Self::UsedBeforeDeclaration { .. } => "L0203",
+            Self::ReadBeforeAssignment { .. } => "L0204",
+        }
+    }
+
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Self::AlreadyDeclared { span, .. }
+            | Self::ReadInOwnInitializer { span, .. }
+            | Self::UsedBeforeDeclaration { span, .. }
+            | Self::ReadBeforeAssignment { span, .. } => *span,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let label = match self {
+            Self::AlreadyDeclared { .. } => "redeclared here",
+            Self::ReadInOwnInitializer { .. } => "read before it is initialized",
+            Self::UsedBeforeDeclaration { .. } => "used before its declaration",
+            Self::ReadBeforeAssignment { .. } => "read before any assignment",
+        };
+        let diagnostic = Diagnostic::new(self.to_string())
+            .with_code(self.code())
+            .with_primary(self.span(), label);
+        match self {
+            Self::ReadBeforeAssignment { declaration, .. } => {
+                diagnostic.with_secondary(*declaration, "declared without a value")
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl Display for ResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AlreadyDeclared { name, .. } => {
+                write!(f, "variable '{name}' is already declared in the same block")
+            }
+            Self::ReadInOwnInitializer { name, .. } => {
+                write!(f, "variable '{name}' is read in its own initializer")
+            }
+            Self::UsedBeforeDeclaration { name, .. } => {
+                write!(f, "variable '{name}' is used before its declaration")
+            }
+            Self::ReadBeforeAssignment { name, .. } => {
+                write!(
+                    f,
+                    "variable '{name}' is read before a value is assigned to it"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompileError {
+    TooManyLocals { name: String, span: CodeSpan },
+    TooManyConstants { span: CodeSpan },
+    JumpTooLarge { span: CodeSpan },
+}
+
+impl CompileError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::TooManyLocals { .. } => "L0301",
+            Self::TooManyConstants { .. } => "L0302",
+            Self::JumpTooLarge { .. } => "L0303",
+        }
+    }
+
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Self::TooManyLocals { span, .. }
+            | Self::TooManyConstants { span }
+            | Self::JumpTooLarge { span } => *span,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(self.to_string())
+            .with_code(self.code())
+            .with_primary(self.span(), "")
+    }
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooManyLocals { name, .. } => write!(
+                f,
+                "too many local variables in scope (declaration of '{name}')"
+            ),
+            Self::TooManyConstants { .. } => write!(f, "too many constants in one program"),
+            Self::JumpTooLarge { .. } => {
+                write!(f, "body of branch or loop too large to jump over")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+///
+/// The code of the error, if it is one of the errors reported by the interpreter
+///
+pub fn error_code(error: &anyhow::Error) -> Option<&'static str> {
+    diagnostic_of(error)?.code()
+}
+
+///
+/// A longer description of the error with the given code
+///
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, explanation)| *explanation)
+}
+
+///
+/// All error codes, in ascending order
+///
+pub fn codes() -> impl Iterator<Item = &'static str> {
+    EXPLANATIONS.iter().map(|(code, _)| *code)
+}
+
+const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "L0001",
+        "Illegal character.
+
+The source contains a character which is not part of any token, e.g., `@` or `#`. Characters
+inside of strings and comments are not affected.",
+    ),
+    (
+        "L0002",
+        "Unterminated string.
+
+A string literal was opened with `\"` but the input ended before the closing `\"`. Strings may span
+several lines, so the missing quote may be far from the end of the input.",
+    ),
+    (
+        "L0101",
+        "Unexpected token.
+
+The parser found a token which cannot appear at this point, e.g., a missing semicolon after a
+statement, a missing `{` after the condition of `if` or `while`, or an operator without an
+operand. The error lists the tokens which would have been valid. The parser skips ahead to the
+next statement and reports further errors from there.",
+    ),
+    (
+        "L0102",
+        "Unclosed block.
+
+A block opened with `{` is not closed by a matching `}` before the end of the input.",
+    ),
+    (
+        "L0103",
+        "Nested too deeply.
+
+Blocks, groupings and unary operators are nested deeper than the parser accepts. The limit keeps
+the parser and the interpreter from exhausting the stack; it is 128 levels unless the run sets
+`--max-depth`. Split the code into several statements.
+
+Parsing stops at this error, as the rest of the input would be read inside the levels which were
+cut off.",
+    ),
+    (
+        "L0104",
+        "Operator chain too long.
+
+An expression chains more binary operators (e.g., `1 + 1 + ...`) than the parser accepts, counting
+the chains of the enclosing expressions as well. Each operator of a chain is evaluated after the
+ones on its left, so long chains exhaust the stack like deep nesting does. Split the expression
+into several statements, e.g., by accumulating the value in a variable.
+
+Parsing stops at this error.",
+    ),
+    (
+        "L0201",
+        "Variable already declared.
+
+A variable is declared twice in the same block. Redeclaring global variables is allowed; local
+variables may shadow variables of enclosing blocks, but not the ones of their own block.",
+    ),
+    (
+        "L0202",
+        "Variable read in its own initializer.
+
+A variable is used in the expression which initializes it, e.g., `var a = a;`. Use a different
+name to refer to a variable of an enclosing block. Redeclaring a global is not an error: its
+initializer reads the previous value, e.g., `var a = 1; var a = a + 1;`.",
+    ),
+    (
+        "L0203",
+        "Variable used before its declaration.
+
+A variable is used in a block before the declaration of the same name in that block, so the use
+would silently refer to a variable of an enclosing block. Move the declaration up or rename it.",
+    ),
+    (
+        "L0204",
+        "Variable read before assignment.
+
+A variable declared without an initializer, e.g., `var a;`, is read before a value is assigned to
+it on any path through the program. Initialize the variable in its declaration or assign it before
+the read. If the variable is assigned on some paths only, the read is checked when the program
+runs instead (see L0408).",
+    ),
+    (
+        "L0301",
+        "Too many local variables.
+
+The byte code VM supports at most 65536 local variables in scope at the same time.",
+    ),
+    (
+        "L0302",
+        "Too many constants.
+
+The byte code VM supports at most 65536 constants (literals and names of global variables) per
+program.",
+    ),
+    (
+        "L0303",
+        "Jump too large.
+
+The body of a branch or loop compiles to more byte code than a single jump of the VM can span
+(65535 bytes). Split the body into smaller parts.",
+    ),
+    (
+        "L0401",
+        "Binary operator not defined for the operand types.
+
+The operator cannot be applied to the types of its operands, e.g., `1 + true`. Arithmetic operators
+and comparisons require numbers; `+` also concatenates two strings.",
+    ),
+    (
+        "L0402",
+        "Unary operator not defined for the operand type.
+
+`-` requires a number and `!` requires a boolean.",
+    ),
+    (
+        "L0403",
+        "Identifier not defined.
+
+A variable is read or assigned without being declared with `var` first.",
+    ),
+    (
+        "L0404",
+        "Type error.
+
+A value of the wrong type was used, e.g., a number as the condition of `if` or `while`. Only
+booleans are truthy or falsy in this implementation of Lox.",
+    ),
+    (
+        "L0405",
+        "Limit exceeded.
+
+The run exceeded one of the configured limits (fuel, nesting depth or string length) and was
+stopped. The state of the interpreter stays usable.",
+    ),
+    (
+        "L0406",
+        "Execution interrupted.
+
+The run was interrupted, e.g., by pressing Ctrl-C in the prompt. The state of the interpreter stays
+usable.",
+    ),
+    (
+        "L0407",
+        "Output error.
+
+The output of a `print` statement could not be written to its destination, e.g., because stdout
+was closed. The run is stopped.",
+    ),
+    (
+        "L0408",
+        "Uninitialized variable.
+
+A variable declared without an initializer was read before a value was assigned to it, e.g.,
+because the assignment is in a branch which was not taken, or because the initializer of the
+variable failed. Reads which can never see a value are already reported before the program runs
+(see L0204).",
+    ),
+];
+
+#[cfg(test)]
+mod test {
+    use super::{codes, explain};
+
+    #[test]
+    fn codes_are_sorted_and_unique() {
+        let codes: Vec<_> = codes().collect();
+        let mut sorted = codes.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted, codes);
+    }
+
+    #[test]
+    fn explain_ignores_case() {
+        assert!(explain("l0001").unwrap().starts_with("Illegal character."));
+        assert!(explain("L9999").is_none());
+    }
+}
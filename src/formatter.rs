@@ -0,0 +1,324 @@
+//! Prints Lox code in its canonical layout: one declaration per line, four spaces of indentation
+//! per block, opening braces at the end of the line and single spaces around binary operators.
+//! The formatter works on the lossless tokens, so that comments stay where they were written:
+//! comments on a line of their own keep their own line, comments behind code stay behind it. One
+//! blank line between declarations is kept, more are collapsed.
+
+use crate::{
+    domain::scanning::{LosslessToken, TokenType, Trivia, TriviaKind},
+    parser, Scanner,
+};
+
+const INDENT: &str = "    ";
+
+///
+/// Formats the source; sources with syntax errors are not formatted, their errors are returned
+///
+pub fn format_source(source: &str) -> Result<String, Vec<anyhow::Error>> {
+    let mut tokens = vec![];
+    let mut errors = vec![];
+    for result in Scanner::new(source).lossless() {
+        match result {
+            Ok(token) => tokens.push(token),
+            Err(e) => errors.push(e.into()),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    // formatting relies on the structure of the code, which is only known for valid code
+    let plain: Vec<_> = tokens.iter().map(|t| t.token.clone()).collect();
+    parser::parse_without_desugaring(&plain)?;
+
+    let mut formatter = Formatter::default();
+    for token in &tokens {
+        formatter.token(token);
+    }
+    Ok(formatter.output)
+}
+
+///
+/// The kind of an open pair of braces
+///
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Brace {
+    Block,
+    /// The init, condition and update of a for loop, which are written on the line of the `for`
+    ForHeader,
+}
+
+#[derive(Default)]
+struct Formatter {
+    output: String,
+    /// The number of enclosing blocks
+    indent: usize,
+    braces: Vec<Brace>,
+    /// The number of brace pairs of the header of a for loop which are still to come
+    for_header_braces: usize,
+    /// The last token written, and whether it was a unary operator
+    previous: Option<(TokenType<'static>, bool)>,
+    /// A line break is due before the next token, unless that token continues the line (e.g., an
+    /// `else` after a closing brace)
+    line_break_due: bool,
+    /// The current line ends with a comment, so that nothing can follow on it
+    line_ends_in_comment: bool,
+}
+
+impl Formatter {
+    fn token(&mut self, token: &LosslessToken) {
+        let t_type = &token.token.t_type;
+        let blank_line = self.trivia(&token.leading_trivia);
+        if *t_type == TokenType::EOF {
+            if !self.output.is_empty() {
+                self.line_break();
+            }
+            return;
+        }
+
+        let closes_block =
+            *t_type == TokenType::BraceRight && self.braces.last() == Some(&Brace::Block);
+        if closes_block {
+            self.indent -= 1;
+        }
+        let after_opening_brace = matches!(self.previous, Some((TokenType::BraceLeft, _)));
+        if self.line_break_due {
+            let continues_line =
+                *t_type == TokenType::ELSE || (closes_block && after_opening_brace);
+            if continues_line && !self.line_ends_in_comment {
+                // `} else {` and empty blocks `{}` stay on one line
+                self.line_break_due = false;
+            } else {
+                self.line_break();
+                if blank_line && !closes_block && !after_opening_brace {
+                    self.output.push('\n');
+                }
+            }
+        }
+        if self.at_line_start() {
+            self.indentation();
+        } else if self.space_before(t_type) {
+            self.output.push(' ');
+        }
+        self.output.push_str(token.text);
+
+        let unary = self.is_unary(t_type);
+        self.after(t_type);
+        self.previous = Some((t_type.clone().into_owned(), unary));
+    }
+
+    ///
+    /// Writes the comments in the trivia; returns whether the trivia contain a blank line in front
+    /// of the token
+    ///
+    fn trivia(&mut self, trivia: &[Trivia]) -> bool {
+        // at the start of the source, everything counts as being on a line of its own
+        let mut line_breaks = usize::from(self.previous.is_none());
+        for piece in trivia {
+            match piece.kind {
+                TriviaKind::Whitespace => line_breaks += piece.line_breaks(),
+                TriviaKind::Comment if line_breaks == 0 => {
+                    // a comment behind code
+                    self.output.push(' ');
+                    self.output.push_str(piece.text.trim_end());
+                    self.line_break_due = true;
+                    self.line_ends_in_comment = true;
+                }
+                TriviaKind::Comment => {
+                    let after_opening_brace =
+                        matches!(self.previous, Some((TokenType::BraceLeft, _)));
+                    if !self.at_line_start() {
+                        self.line_break();
+                    }
+                    if line_breaks > 1 && !after_opening_brace && !self.output.is_empty() {
+                        self.output.push('\n');
+                    }
+                    self.indentation();
+                    self.output.push_str(piece.text.trim_end());
+                    self.line_break_due = true;
+                    self.line_ends_in_comment = true;
+                    line_breaks = 0;
+                }
+            }
+        }
+        line_breaks > 1
+    }
+
+    ///
+    /// Updates the state after the token was written
+    ///
+    fn after(&mut self, t_type: &TokenType) {
+        match t_type {
+            TokenType::FOR => self.for_header_braces = 3,
+            TokenType::BraceLeft if self.for_header_braces > 0 => {
+                self.braces.push(Brace::ForHeader);
+            }
+            TokenType::BraceLeft => {
+                self.braces.push(Brace::Block);
+                self.indent += 1;
+                self.line_break_due = true;
+            }
+            TokenType::BraceRight => {
+                if self.braces.pop() == Some(Brace::ForHeader) {
+                    self.for_header_braces -= 1;
+                } else {
+                    self.line_break_due = true;
+                }
+            }
+            TokenType::Semicolon if !self.in_for_header() => self.line_break_due = true,
+            _ => (),
+        }
+    }
+
+    fn in_for_header(&self) -> bool {
+        self.braces.last() == Some(&Brace::ForHeader)
+    }
+
+    fn space_before(&self, t_type: &TokenType) -> bool {
+        let Some((previous, previous_unary)) = &self.previous else {
+            return false;
+        };
+        if *previous_unary {
+            return false;
+        }
+        match (previous, t_type) {
+            (
+                _,
+                TokenType::Semicolon | TokenType::ParenRight | TokenType::Comma | TokenType::Dot,
+            ) => false,
+            (TokenType::ParenLeft | TokenType::Dot, _)
+            | (TokenType::BraceLeft, TokenType::BraceRight) => false,
+            // the parts of a for header are written without spaces inside their braces
+            (TokenType::BraceLeft, _) | (_, TokenType::BraceRight) => !self.in_for_header(),
+            _ => true,
+        }
+    }
+
+    ///
+    /// A minus is unary unless it follows an operand
+    ///
+    fn is_unary(&self, t_type: &TokenType) -> bool {
+        match t_type {
+            TokenType::Bang => true,
+            TokenType::Minus => !matches!(
+                self.previous,
+                Some((
+                    TokenType::Identifier(_)
+                        | TokenType::Number(_)
+                        | TokenType::String(_)
+                        | TokenType::TRUE
+                        | TokenType::FALSE
+                        | TokenType::NIL
+                        | TokenType::ParenRight,
+                    _
+                ))
+            ),
+            _ => false,
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.output.is_empty() || self.output.ends_with('\n')
+    }
+
+    fn line_break(&mut self) {
+        self.output.push('\n');
+        self.line_break_due = false;
+        self.line_ends_in_comment = false;
+    }
+
+    ///
+    /// Indents the line; lines which continue a declaration are indented one more level
+    ///
+    fn indentation(&mut self) {
+        let continuation = !matches!(
+            self.previous,
+            None | Some((
+                TokenType::Semicolon | TokenType::BraceLeft | TokenType::BraceRight,
+                _
+            ))
+        ) && !self.line_break_due;
+        let depth = self.indent + usize::from(continuation);
+        self.output.push_str(&INDENT.repeat(depth));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::format_source;
+
+    fn assert_formats(input: &str, expected: &str) {
+        let formatted = format_source(input).expect("formatting failed");
+        assert_eq!(expected, formatted);
+        let again = format_source(&formatted).expect("formatting failed");
+        assert_eq!(expected, again, "formatting is not idempotent");
+    }
+
+    #[test]
+    fn declarations_are_put_on_their_own_lines() {
+        assert_formats(
+            "var a=1;print a;a=-a*(2+3);",
+            "var a = 1;\nprint a;\na = -a * (2 + 3);\n",
+        );
+    }
+
+    #[test]
+    fn blocks_are_indented() {
+        assert_formats(
+            "if (a<=b) {print a;} else {{ print !b; }}",
+            "if (a <= b) {\n    print a;\n} else {\n    {\n        print !b;\n    }\n}\n",
+        );
+    }
+
+    #[test]
+    fn empty_blocks_stay_on_one_line() {
+        assert_formats("while (false)   {\n\n}", "while (false) {}\n");
+    }
+
+    #[test]
+    fn for_headers_stay_on_one_line() {
+        assert_formats(
+            "for { var i = 0; } { i < 3 } { i = i + 1; } { print i; }",
+            "for {var i = 0;} {i < 3} {i = i + 1;} {\n    print i;\n}\n",
+        );
+    }
+
+    #[test]
+    fn comments_stay_attached() {
+        assert_formats(
+            "// header\nvar a = 1;   // one\n{\n// inside\nprint a;}\n// end",
+            "// header\nvar a = 1; // one\n{\n    // inside\n    print a;\n}\n// end\n",
+        );
+    }
+
+    #[test]
+    fn single_blank_lines_are_kept() {
+        assert_formats(
+            "var a = 1;\n\n\n\nvar b = 2;\n{\n\nprint a;\n\n}",
+            "var a = 1;\n\nvar b = 2;\n{\n    print a;\n}\n",
+        );
+    }
+
+    #[test]
+    fn binary_and_unary_minus_are_told_apart() {
+        assert_formats("print 1 - -2 - (-a);", "print 1 - -2 - (-a);\n");
+    }
+
+    #[test]
+    fn lines_continued_after_comments_are_indented() {
+        assert_formats("var a = 1 + // first\n2;", "var a = 1 + // first\n    2;\n");
+    }
+
+    #[test]
+    fn nothing_is_appended_to_comments() {
+        assert_formats(
+            "if (a) { // empty\n} // then\nelse { print a; }",
+            "if (a) { // empty\n} // then\nelse {\n    print a;\n}\n",
+        );
+    }
+
+    #[test]
+    fn invalid_code_is_not_formatted() {
+        assert!(format_source("var a = ;").is_err());
+        assert!(format_source("var a = 1 @ 2;").is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! Static inference of the types of values: the types an expression may evaluate to are told
+//! from its literals and operators, and from the types of the variables it reads, as far as the
+//! caller knows them.
+
+use std::{collections::BTreeSet, fmt::Display};
+
+use crate::domain::grammar::{
+    Comparison, Equality, Expression, Factor, LogicAnd, LogicOr, Primary, Term, Unary, Variable,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum LoxType {
+    Number,
+    String,
+    Boolean,
+    Nil,
+}
+
+///
+/// The possible types of a value, as far as they can be told without running the program
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Types {
+    known: BTreeSet<LoxType>,
+    /// The value may also have a type which is not known
+    unknown: bool,
+}
+
+impl Types {
+    pub(crate) fn of(t: LoxType) -> Self {
+        Self {
+            known: BTreeSet::from([t]),
+            unknown: false,
+        }
+    }
+
+    pub(crate) fn unknown() -> Self {
+        Self {
+            known: BTreeSet::new(),
+            unknown: true,
+        }
+    }
+
+    pub(crate) fn union(mut self, other: Types) -> Self {
+        self.known.extend(other.known);
+        self.unknown |= other.unknown;
+        self
+    }
+
+    ///
+    /// Whether the value certainly does not have the type
+    ///
+    pub(crate) fn excludes(&self, t: LoxType) -> bool {
+        !self.unknown && !self.known.is_empty() && !self.known.contains(&t)
+    }
+
+    ///
+    /// Numbers are added, strings concatenated; other combinations fail at runtime
+    ///
+    fn plus(&self, other: &Types) -> Self {
+        let mut sum = Types {
+            known: BTreeSet::new(),
+            unknown: self.unknown || other.unknown,
+        };
+        for l in &self.known {
+            for r in &other.known {
+                match (l, r) {
+                    (LoxType::Number, LoxType::Number) => sum.known.insert(LoxType::Number),
+                    (LoxType::String, LoxType::String) => sum.known.insert(LoxType::String),
+                    _ => false,
+                };
+            }
+        }
+        sum
+    }
+}
+
+impl Display for Types {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.unknown || self.known.is_empty() {
+            return write!(f, "unknown");
+        }
+        let names: Vec<_> = self.known.iter().map(|t| format!("{t:?}")).collect();
+        write!(f, "{}", names.join(" | "))
+    }
+}
+
+///
+/// The types the expression may evaluate to; `variable` gives the types of a variable it reads
+///
+pub(crate) fn infer(expr: &Expression, variable: &impl Fn(&Variable) -> Types) -> Types {
+    Inference { variable }.expression(expr)
+}
+
+struct Inference<'a, F> {
+    variable: &'a F,
+}
+
+impl<F: Fn(&Variable) -> Types> Inference<'_, F> {
+    fn expression(&self, expr: &Expression) -> Types {
+        match expr {
+            Expression::LogicOr(l_or) => self.logic_or(l_or),
+        }
+    }
+
+    fn logic_or(&self, l_or: &LogicOr) -> Types {
+        match l_or {
+            LogicOr::LogicAnd(l_and) => self.logic_and(l_and),
+            LogicOr::Or { left, right, .. } => self.logic_or(left).union(self.logic_and(right)),
+        }
+    }
+
+    fn logic_and(&self, l_and: &LogicAnd) -> Types {
+        match l_and {
+            LogicAnd::Equality(eq) => self.equality(eq),
+            LogicAnd::And { left, right, .. } => self.logic_and(left).union(self.equality(right)),
+        }
+    }
+
+    fn equality(&self, eq: &Equality) -> Types {
+        match eq {
+            Equality::Comparison(comp) => self.comparison(comp),
+            Equality::EqualityCheck { .. } | Equality::InequalityCheck { .. } => {
+                Types::of(LoxType::Boolean)
+            }
+        }
+    }
+
+    fn comparison(&self, comp: &Comparison) -> Types {
+        match comp {
+            Comparison::Term(term) => self.term(term),
+            _ => Types::of(LoxType::Boolean),
+        }
+    }
+
+    fn term(&self, term: &Term) -> Types {
+        match term {
+            Term::Factor(factor) => self.factor(factor),
+            Term::Addition { left, right, .. } => self.term(left).plus(&self.factor(right)),
+            Term::Subtraction { .. } => Types::of(LoxType::Number),
+        }
+    }
+
+    fn factor(&self, factor: &Factor) -> Types {
+        match factor {
+            Factor::Unary(unary) => self.unary(unary),
+            Factor::Multiplication { .. } | Factor::Division { .. } => Types::of(LoxType::Number),
+        }
+    }
+
+    fn unary(&self, unary: &Unary) -> Types {
+        match unary {
+            Unary::Primary(primary) => self.primary(primary),
+            Unary::LogicalNegation { .. } => Types::of(LoxType::Boolean),
+            Unary::ArithmNegation { .. } => Types::of(LoxType::Number),
+        }
+    }
+
+    fn primary(&self, primary: &Primary) -> Types {
+        match primary {
+            Primary::Number(_) => Types::of(LoxType::Number),
+            Primary::String(_) => Types::of(LoxType::String),
+            Primary::True(_) | Primary::False(_) => Types::of(LoxType::Boolean),
+            Primary::Nil(_) => Types::of(LoxType::Nil),
+            Primary::GroupedExpression(expr) => self.expression(expr),
+            Primary::Identifier(var) => (self.variable)(var),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parser::parse;
+    use crate::{
+        domain::grammar::{Declaration, Statement},
+        scanner::scan_input,
+    };
+
+    use super::{infer, LoxType, Types};
+
+    fn types_of(expr: &str) -> Types {
+        let program = parse(&scan_input(&format!("print {expr};")).expect("scanning failed"))
+            .expect("parsing failed");
+        let Declaration::Statement(Statement::Print(expr), _) = &program[0] else {
+            panic!("not a print statement");
+        };
+        infer(expr, &|var| match var.name.value() {
+            "n" => Types::of(LoxType::Number),
+            _ => Types::unknown(),
+        })
+    }
+
+    #[test]
+    fn types_follow_the_operators() {
+        assert_eq!("Number", types_of("-(n + 2) * 3").to_string());
+        assert_eq!("String", types_of("\"a\" + \"b\"").to_string());
+        assert_eq!("Boolean", types_of("1 < n == !nil").to_string());
+        assert_eq!("Number | Nil", types_of("n or nil").to_string());
+        assert_eq!("unknown", types_of("n + x").to_string());
+    }
+
+    #[test]
+    fn excluded_types_are_certain() {
+        assert!(types_of("n + 1").excludes(LoxType::Boolean));
+        assert!(!types_of("n or true").excludes(LoxType::Boolean));
+        assert!(!types_of("x").excludes(LoxType::Boolean));
+    }
+}
@@ -1,25 +1,36 @@
 use std::fmt::Display;
 
-use anyhow::Result;
-
 use crate::{
     domain::{grammar::Program, location::CodeSpan},
     Interpreter,
 };
 
-use self::{error::InterpreterError, statements::InterpretedStatement};
+use self::{error::RuntimeError, statements::InterpretedStatement};
 
+mod budget;
+pub mod debug;
 pub mod environment;
 pub mod error;
+mod heap;
+mod policy;
 
+pub(crate) use budget::Budget;
+pub use budget::{InterruptHandle, Limit, Limits};
+pub use debug::{Debugger, Pause};
 pub use environment::*;
+pub(crate) use heap::Heap;
+pub use heap::{Gc, GcStats};
+pub(crate) use policy::run_declarations;
+pub use policy::{ErrorPolicy, OnError, RunStats};
 
 mod expressions;
+pub(crate) mod operations;
 mod statements;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Value {
     pub v_type: ValueType,
+    /// `None` only for the value of variables which have not been assigned yet
     span: Option<CodeSpan>,
 }
 
@@ -31,22 +42,46 @@ impl Value {
         }
     }
 
-    pub fn nil() -> Self {
+    ///
+    /// The value of a variable declared without initializer; reading it before a value is assigned
+    /// is an error
+    ///
+    pub(crate) fn uninitialized() -> Self {
         Self {
             v_type: ValueType::Nil,
             span: None,
         }
     }
 
-    pub fn span(&self) -> CodeSpan {
-        self.span.unwrap()
+    pub(crate) fn is_initialized(&self) -> bool {
+        self.span.is_some()
+    }
+
+    ///
+    /// The span of the code the value was created or read at. Only for values the program computes
+    /// or reads, which are always initialized.
+    ///
+    pub(crate) fn span(&self) -> CodeSpan {
+        self.span
+            .expect("the span of the value of an uninitialized variable")
+    }
+
+    ///
+    /// The value as read at the given span, e.g., where a variable is used instead of where its
+    /// value was created, so that errors point at the code which uses it
+    ///
+    pub(crate) fn at(self, span: CodeSpan) -> Self {
+        Self {
+            span: Some(span),
+            ..self
+        }
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ValueType {
     Number(f64),
-    String(String),
+    String(Gc<String>),
     Boolean(bool),
     Nil,
 }
@@ -63,8 +98,11 @@ impl ValueType {
 }
 
 impl ValueType {
+    ///
+    /// Creates a string value which is not tracked by the heap of an interpreter
+    ///
     pub fn string(s: impl Into<String>) -> Self {
-        ValueType::String(s.into())
+        ValueType::String(Gc::new(s.into()))
     }
 }
 
@@ -93,29 +131,42 @@ impl Display for ValueType {
 
 impl Interpreter {
     ///
-    /// Interprets the given program. If the last statement is an expression, the value of that
-    /// expression is returned.
+    /// Interprets the top-level declarations of the given program as the error policy allows. The
+    /// error which unwound a run stopped by the debugger is dropped.
     ///
-    pub(crate) fn interpret(
-        &mut self,
-        program: Program,
-    ) -> Result<Option<Value>, Vec<InterpreterError>> {
+    pub(crate) fn interpret(&mut self, program: Program) -> (RunStats, Vec<RuntimeError>) {
         let environment = &mut self.environment;
-        let mut errors = vec![];
-        for decl in program.into_iter() {
-            match decl.interpret_statement(environment) {
-                Ok(()) => (),
-                Err(e) => errors.push(e),
-            }
-        }
-        if errors.is_empty() {
-            Ok(environment.get_tmp_value().cloned())
-        } else {
-            Err(errors)
+        let (mut stats, mut errors) = run_declarations(program.len(), self.error_policy, |idx| {
+            program[idx].interpret_statement(environment)
+        });
+        if environment.budget().stopped() {
+            errors.pop();
+            stats.declarations_failed -= 1;
         }
+        (stats, errors)
     }
 
     pub fn environment(&self) -> &Environment {
         &self.environment
     }
+
+    ///
+    /// A handle which can be used from other threads to stop the current run of the interpreter;
+    /// the variables defined so far stay available
+    ///
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.environment.budget().interrupt_handle()
+    }
+
+    pub fn gc_stats(&self) -> GcStats {
+        self.environment.gc_stats()
+    }
+
+    ///
+    /// Releases all heap objects which are no longer reachable from the variables or the temporary
+    /// value
+    ///
+    pub fn collect_garbage(&mut self) {
+        self.environment.collect_garbage(&[]);
+    }
 }
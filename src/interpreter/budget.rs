@@ -0,0 +1,234 @@
+//! Limits on the resources a single run of the interpreter may use, so that untrusted programs
+//! cannot hang or exhaust the host, and the interruption of runs from other threads.
+
+use std::{
+    cell::Cell,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::domain::location::CodeSpan;
+
+use super::error::RuntimeError;
+
+///
+/// The limits for one call of [crate::Interpreter::interpret_src_str]; `None` means unlimited.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The number of steps a run may take. The tree-walking interpreter charges one step per
+    /// statement and per expression, the VM one step per instruction.
+    pub fuel: Option<u64>,
+    /// The maximum nesting of blocks, groupings and unary operators, 128 if unset. Deeper code is
+    /// rejected by the parser on both backends; the tree-walking interpreter checks the depth again
+    /// while running, for programs which were not parsed from source.
+    pub max_depth: Option<usize>,
+    /// The maximum length of strings created while running, in bytes
+    pub max_string_len: Option<usize>,
+}
+
+///
+/// The limit which stopped the execution
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Fuel(u64),
+    Depth(usize),
+    StringLength(usize),
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::Fuel(fuel) => write!(f, "fuel of {fuel} steps"),
+            Limit::Depth(depth) => write!(f, "maximum nesting depth of {depth}"),
+            Limit::StringLength(len) => write!(f, "maximum string length of {len} bytes"),
+        }
+    }
+}
+
+///
+/// A handle to interrupt the runs of an interpreter, e.g., from a signal handler. It can be cloned
+/// and sent to other threads; an interrupt stops the current run at the next loop iteration or
+/// block statement.
+///
+#[derive(Debug, Clone, Default)]
+pub struct InterruptHandle(Arc<AtomicBool>);
+
+impl InterruptHandle {
+    pub fn interrupt(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+///
+/// Keeps track of the resources used by the current run. Interior mutability is used, as
+/// expressions are interpreted with a shared reference to the environment.
+///
+#[derive(Debug, Default)]
+pub(crate) struct Budget {
+    limits: Limits,
+    fuel_used: Cell<u64>,
+    depth: Cell<usize>,
+    interrupt: InterruptHandle,
+    /// Whether a debugger stopped the run; unlike an interrupt, this ends the run without an error
+    stopped: Cell<bool>,
+}
+
+impl Budget {
+    pub(crate) fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    pub(crate) fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    pub(crate) fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    ///
+    /// Prepares the budget for a new run; interrupts requested before the run are discarded
+    ///
+    pub(crate) fn reset(&self) {
+        self.fuel_used.set(0);
+        self.depth.set(0);
+        self.interrupt.0.store(false, Ordering::Relaxed);
+        self.stopped.set(false);
+    }
+
+    pub(crate) fn stop(&self) {
+        self.stopped.set(true);
+    }
+
+    pub(crate) fn stopped(&self) -> bool {
+        self.stopped.get()
+    }
+
+    pub(crate) fn check_interrupt(
+        &self,
+        span: impl FnOnce() -> CodeSpan,
+    ) -> Result<(), RuntimeError> {
+        if self.interrupt.0.load(Ordering::Relaxed) {
+            return Err(RuntimeError::interrupted(span()));
+        }
+        Ok(())
+    }
+
+    ///
+    /// Charges one step and checks the current nesting depth. The span is only computed if a limit
+    /// is exceeded.
+    ///
+    pub(crate) fn charge(&self, span: impl FnOnce() -> CodeSpan) -> Result<(), RuntimeError> {
+        let fuel_used = self.fuel_used.get() + 1;
+        self.fuel_used.set(fuel_used);
+        if let Some(fuel) = self.limits.fuel {
+            if fuel_used > fuel {
+                return Err(RuntimeError::limit_exceeded(Limit::Fuel(fuel), span()));
+            }
+        }
+        if let Some(max_depth) = self.limits.max_depth {
+            if self.depth.get() > max_depth {
+                return Err(RuntimeError::limit_exceeded(
+                    Limit::Depth(max_depth),
+                    span(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Enters a nested block or expression; has to be paired with [Budget::leave], also when the
+    /// nested code fails
+    ///
+    pub(crate) fn enter(&self) {
+        self.depth.set(self.depth.get() + 1);
+    }
+
+    pub(crate) fn leave(&self) {
+        self.depth.set(self.depth.get() - 1);
+    }
+
+    pub(crate) fn check_string_len(
+        &self,
+        len: usize,
+        span: impl FnOnce() -> CodeSpan,
+    ) -> Result<(), RuntimeError> {
+        match self.limits.max_string_len {
+            Some(max) if len > max => Err(RuntimeError::limit_exceeded(
+                Limit::StringLength(max),
+                span(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::location::CodeSpan;
+
+    use super::{Budget, Limits};
+
+    #[test]
+    fn fuel_runs_out() {
+        let mut budget = Budget::default();
+        budget.set_limits(Limits {
+            fuel: Some(2),
+            ..Default::default()
+        });
+        assert!(budget.charge(CodeSpan::default).is_ok());
+        assert!(budget.charge(CodeSpan::default).is_ok());
+        assert!(budget.charge(CodeSpan::default).is_err());
+
+        budget.reset();
+        assert!(budget.charge(CodeSpan::default).is_ok());
+    }
+
+    #[test]
+    fn depth_is_restored() {
+        let mut budget = Budget::default();
+        budget.set_limits(Limits {
+            max_depth: Some(1),
+            ..Default::default()
+        });
+        budget.enter();
+        assert!(budget.charge(CodeSpan::default).is_ok());
+        budget.enter();
+        assert!(budget.charge(CodeSpan::default).is_err());
+        budget.leave();
+        budget.leave();
+        assert!(budget.charge(CodeSpan::default).is_ok());
+    }
+
+    #[test]
+    fn interrupts_are_cleared_by_reset() {
+        let budget = Budget::default();
+        let handle = budget.interrupt_handle();
+        assert!(budget.check_interrupt(CodeSpan::default).is_ok());
+
+        std::thread::spawn(move || handle.interrupt())
+            .join()
+            .unwrap();
+        assert!(budget.check_interrupt(CodeSpan::default).is_err());
+
+        budget.reset();
+        assert!(budget.check_interrupt(CodeSpan::default).is_ok());
+    }
+
+    #[test]
+    fn unlimited_by_default() {
+        let budget = Budget::default();
+        for _ in 0..1000 {
+            assert!(budget.charge(CodeSpan::default).is_ok());
+        }
+        assert!(budget
+            .check_string_len(usize::MAX, CodeSpan::default)
+            .is_ok());
+    }
+}
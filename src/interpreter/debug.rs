@@ -0,0 +1,87 @@
+//! The hook through which a debugger follows a run of the tree walker.
+
+use anyhow::anyhow;
+
+use crate::{
+    domain::{
+        grammar::{Declaration, Statement},
+        location::CodeSpan,
+    },
+    Value,
+};
+
+use super::{expressions::InterpretedExpression, Environment, Scope};
+
+///
+/// Follows a run of the tree walker, see [crate::Interpreter::with_debugger]. The run waits while
+/// the debugger handles a pause, e.g., while it asks the user what to do next.
+///
+pub trait Debugger {
+    ///
+    /// Called before each declaration is run, except for blocks: the declarations in a block are
+    /// paused at one by one instead
+    ///
+    fn before_declaration(&mut self, pause: &Pause);
+}
+
+impl std::fmt::Debug for dyn Debugger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Debugger")
+    }
+}
+
+///
+/// The state of a run which is paused before a declaration
+///
+pub struct Pause<'a> {
+    environment: &'a Environment,
+    span: CodeSpan,
+}
+
+impl<'a> Pause<'a> {
+    pub(crate) fn new(environment: &'a Environment, span: CodeSpan) -> Self {
+        Self { environment, span }
+    }
+
+    ///
+    /// The span of the declaration which is run next
+    ///
+    pub fn span(&self) -> CodeSpan {
+        self.span
+    }
+
+    ///
+    /// The number of blocks the declaration is nested in; 0 for top-level declarations
+    ///
+    pub fn depth(&self) -> usize {
+        self.environment.depth()
+    }
+
+    ///
+    /// The variables visible at the pause, from the globals to the innermost block
+    ///
+    pub fn scopes(&self) -> Vec<Scope> {
+        self.environment.scopes()
+    }
+
+    ///
+    /// Evaluates an expression in the scope of the paused declaration; its variables are looked up
+    /// by name, from the innermost block outwards. The spans of errors refer to the expression.
+    ///
+    pub fn evaluate(&self, expression: &str) -> Result<Value, Vec<anyhow::Error>> {
+        let program = crate::parse(format!("{expression};").as_str())?;
+        let [Declaration::Statement(Statement::Expression(expr), _)] = program.0.as_slice() else {
+            return Err(vec![anyhow!("'{expression}' is not an expression")]);
+        };
+        expr.interpret_expression(self.environment)
+            .map_err(|e| vec![e.into()])
+    }
+
+    ///
+    /// Stops the run once the debugger returns; the run ends without an error, and the remaining
+    /// declarations are not run
+    ///
+    pub fn stop(&self) {
+        self.environment.budget().stop();
+    }
+}
@@ -2,17 +2,63 @@ use std::collections::HashMap;
 
 use anyhow::{bail, Result};
 
-use crate::Value;
+use crate::{
+    domain::{grammar::Binding, location::CodeSpan},
+    Output, Value,
+};
+
+use super::{
+    debug::{Debugger, Pause},
+    error::RuntimeError,
+    Budget, GcStats, Heap, Limits,
+};
 
 ///
 /// The state of the interpreter:
 ///
-/// - The current values of the global variables
+/// - The current values of the global variables, looked up by name
+/// - The values of the variables declared in the currently entered blocks. They are kept on a
+///   single stack and addressed by the slots the resolver assigned to them.
+/// - The heap holding the compound values; the variables and the temporary value are the roots
+///   of its collections
+/// - The debugger following the run, if any
 ///
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Environment {
-    scope: Option<Scope>,
+    globals: HashMap<String, Value>,
+    locals: Vec<Value>,
+    frames: Vec<Frame>,
     tmp_value: Option<Value>,
+    heap: Heap,
+    budget: Budget,
+    /// The destination of `print` statements
+    output: Output,
+    debugger: Option<Box<dyn Debugger>>,
+}
+
+///
+/// The variables declared in one scope, as shown by a debugger
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    /// The number of blocks the scope is nested in; 0 for the globals
+    pub level: usize,
+    /// The variables in the order of their declaration (by name for the globals), with no value
+    /// while they are not initialized
+    pub variables: Vec<(String, Option<Value>)>,
+}
+
+///
+/// The part of the local stack which belongs to one block
+///
+#[derive(Debug)]
+struct Frame {
+    /// The position of the first variable of the block on the local stack
+    start: usize,
+    /// The names of the variables declared via [Environment::declare_var], with their index in the
+    /// block; variables declared by the interpreter are only addressed by their slot, unless a
+    /// debugger is attached
+    names: Vec<(String, usize)>,
 }
 
 impl Environment {
@@ -24,116 +70,260 @@ impl Environment {
         self.tmp_value.as_ref()
     }
 
+    ///
+    /// Declares the variable in the innermost scope, so that it can be accessed by name. A global
+    /// which is already declared keeps its value, which the initializer of its redeclaration reads.
+    ///
     pub fn declare_var(&mut self, iden: impl Into<String>) {
-        self.scope_mut().declare_var(iden);
+        let iden = iden.into();
+        match self.frames.last_mut() {
+            Some(frame) => {
+                frame.names.push((iden, self.locals.len() - frame.start));
+                self.locals.push(Value::uninitialized());
+            }
+            None => {
+                self.globals
+                    .entry(iden)
+                    .or_insert_with(Value::uninitialized);
+            }
+        }
     }
 
     pub fn set_var_value(&mut self, iden: impl Into<String>, val: Value) -> Result<()> {
-        self.scope_mut().set_var_value(iden, val)
+        let iden = iden.into();
+        let Some(var) = self.named_var_mut(&iden) else {
+            bail!("variable not declared")
+        };
+        *var = val;
+        Ok(())
     }
 
     pub fn get_var_value(&self, iden: &str) -> Option<&Value> {
-        self.scope().get_var_value(iden)
+        for frame in self.frames.iter().rev() {
+            if let Some((_, index)) = frame.names.iter().rev().find(|(name, _)| name == iden) {
+                return self.locals.get(frame.start + index);
+            }
+        }
+        self.globals.get(iden)
     }
 
     ///
-    /// Creates a new inner scope in the current scope
+    /// Declares a variable in the innermost scope and returns its binding. Within blocks, the
+    /// variable takes the next free slot; the resolver assigns the same slots to the uses of the
+    /// variable. Its name is only recorded for a debugger to look it up. As for
+    /// [Environment::declare_var], a redeclared global keeps its value.
     ///
-    pub fn new_inner_scope(&mut self) {
-        let outer = self.scope.take().expect("scope must be set");
-        self.scope = Some(Scope::new_inner(outer));
+    pub(crate) fn declare_bound_var(&mut self, iden: &str) -> Binding {
+        let named = self.debugger.is_some();
+        match self.frames.last_mut() {
+            Some(frame) => {
+                let index = self.locals.len() - frame.start;
+                if named {
+                    frame.names.push((iden.to_owned(), index));
+                }
+                self.locals.push(Value::uninitialized());
+                Binding::Local { depth: 0, index }
+            }
+            None => {
+                if !self.globals.contains_key(iden) {
+                    self.globals.insert(iden.to_owned(), Value::uninitialized());
+                }
+                Binding::Global
+            }
+        }
+    }
+
+    pub(crate) fn get_bound_var_value(&self, iden: &str, binding: Binding) -> Option<&Value> {
+        match binding {
+            Binding::Global => self.globals.get(iden),
+            Binding::Local { depth, index } => self.locals.get(self.local_slot(depth, index)?),
+        }
+    }
+
+    pub(crate) fn set_bound_var_value(
+        &mut self,
+        iden: &str,
+        binding: Binding,
+        val: Value,
+    ) -> Result<()> {
+        let var = match binding {
+            Binding::Global => self.globals.get_mut(iden),
+            Binding::Local { depth, index } => match self.local_slot(depth, index) {
+                Some(slot) => self.locals.get_mut(slot),
+                None => None,
+            },
+        };
+        let Some(var) = var else {
+            bail!("variable not declared")
+        };
+        *var = val;
+        Ok(())
     }
 
     ///
-    /// Tears down the current innermost scope
+    /// The names of the variables declared in the global scope
     ///
-    pub fn teardown_inner_scope(&mut self) {
-        let inner = self.scope.take().expect("scope must be set");
-        self.scope = Some(inner.collapse());
+    pub(crate) fn global_names(&self) -> impl Iterator<Item = &str> {
+        self.globals.keys().map(String::as_str)
     }
 
-    fn scope(&self) -> &Scope {
-        self.scope.as_ref().expect("scope should always be set")
+    ///
+    /// The names of the variables which can be accessed by name, from the innermost scope outwards
+    ///
+    pub(crate) fn visible_names(&self) -> impl Iterator<Item = &str> {
+        self.frames
+            .iter()
+            .rev()
+            .flat_map(|frame| frame.names.iter().map(|(name, _)| name.as_str()))
+            .chain(self.global_names())
     }
 
-    fn scope_mut(&mut self) -> &mut Scope {
-        self.scope.as_mut().expect("scope should always be set")
+    ///
+    /// The variables of all entered scopes, from the globals to the innermost block
+    ///
+    pub fn scopes(&self) -> Vec<Scope> {
+        let mut globals: Vec<_> = self
+            .globals
+            .iter()
+            .map(|(name, value)| (name.clone(), initialized(value)))
+            .collect();
+        globals.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let blocks = self.frames.iter().enumerate().map(|(level, frame)| Scope {
+            level: level + 1,
+            variables: frame
+                .names
+                .iter()
+                .map(|(name, index)| (name.clone(), initialized(&self.locals[frame.start + index])))
+                .collect(),
+        });
+        std::iter::once(Scope {
+            level: 0,
+            variables: globals,
+        })
+        .chain(blocks)
+        .collect()
     }
-}
 
-impl Default for Environment {
-    fn default() -> Self {
-        Self {
-            scope: Some(Scope::default()),
-            tmp_value: None,
-        }
+    ///
+    /// The number of entered blocks
+    ///
+    pub(crate) fn depth(&self) -> usize {
+        self.frames.len()
     }
-}
 
-#[derive(Debug)]
-struct Scope {
-    outer: Option<Box<Self>>,
-    variables: HashMap<String, Value>,
-}
+    pub(crate) fn set_debugger(&mut self, debugger: Box<dyn Debugger>) {
+        self.debugger = Some(debugger);
+    }
 
-impl Scope {
-    fn new_global() -> Self {
-        Self {
-            outer: None,
-            variables: HashMap::default(),
+    ///
+    /// Hands the run to the debugger before the declaration with the given span is run; fails if
+    /// the debugger stopped the run, which unwinds it, or if the run was interrupted meanwhile
+    ///
+    pub(crate) fn pause_point(&mut self, span: CodeSpan) -> Result<(), RuntimeError> {
+        let Some(mut debugger) = self.debugger.take() else {
+            return Ok(());
+        };
+        debugger.before_declaration(&Pause::new(self, span));
+        self.debugger = Some(debugger);
+        if self.budget.stopped() {
+            return Err(RuntimeError::interrupted(span));
         }
+        self.budget.check_interrupt(|| span)
     }
 
-    fn new_inner(outer: Self) -> Self {
-        Self {
-            outer: Some(Box::new(outer)),
-            variables: HashMap::default(),
-        }
+    pub(crate) fn heap(&self) -> &Heap {
+        &self.heap
     }
 
-    fn collapse(self) -> Self {
-        *self.outer.expect("cannot collapse global scope")
+    pub(crate) fn set_output(&mut self, output: Output) {
+        self.output = output;
     }
 
-    fn declare_var(&mut self, iden: impl Into<String>) {
-        let key = iden.into();
-        self.variables.insert(key, Value::nil());
+    pub(crate) fn print(&mut self, value: &Value) -> std::io::Result<()> {
+        self.output.write_line(&value.to_string())
     }
 
-    fn set_var_value(&mut self, iden: impl Into<String>, val: Value) -> Result<()> {
-        let key = iden.into();
-        let Some(var_scope) = self.variable_scope(&key) else {
-            bail!("variable not declared")
-        };
-        var_scope.variables.insert(key, val);
-        Ok(())
+    pub(crate) fn budget(&self) -> &Budget {
+        &self.budget
     }
 
-    fn variable_scope(&mut self, iden: &str) -> Option<&mut Self> {
-        match self.variables.get_mut(iden) {
-            Some(_) => Some(self),
-            None => match &mut self.outer {
-                Some(outer) => outer.variable_scope(iden),
-                None => None,
-            },
+    pub(crate) fn set_limits(&mut self, limits: Limits) {
+        self.budget.set_limits(limits);
+    }
+
+    pub(crate) fn set_gc_stress(&mut self, stress: bool) {
+        self.heap.set_stress(stress);
+    }
+
+    pub fn gc_stats(&self) -> GcStats {
+        self.heap.stats()
+    }
+
+    ///
+    /// Runs a collection if one was requested by an allocation. Must only be called where all live
+    /// values are stored in the environment or in `extra_roots` (e.g., the stack of the VM).
+    ///
+    pub(crate) fn safepoint(&self, extra_roots: &[Value]) {
+        if self.heap.collection_requested() {
+            self.collect_garbage(extra_roots);
         }
     }
 
-    fn get_var_value(&self, iden: &str) -> Option<&Value> {
-        match self.variables.get(iden) {
-            Some(v) => Some(v),
-            None => match &self.outer {
-                Some(outer) => outer.get_var_value(iden),
-                None => None,
-            },
+    pub(crate) fn collect_garbage(&self, extra_roots: &[Value]) {
+        let roots = self
+            .globals
+            .values()
+            .chain(&self.locals)
+            .chain(&self.tmp_value)
+            .chain(extra_roots);
+        self.heap.collect(roots);
+    }
+
+    ///
+    /// Creates a new inner scope in the current scope
+    ///
+    pub fn new_inner_scope(&mut self) {
+        self.frames.push(Frame {
+            start: self.locals.len(),
+            names: vec![],
+        });
+    }
+
+    ///
+    /// Tears down the current innermost scope
+    ///
+    pub fn teardown_inner_scope(&mut self) {
+        let frame = self.frames.pop().expect("cannot collapse global scope");
+        self.locals.truncate(frame.start);
+    }
+
+    ///
+    /// The position on the local stack of the variable with the given index in the block `depth`
+    /// levels above the innermost one
+    ///
+    fn local_slot(&self, depth: usize, index: usize) -> Option<usize> {
+        let frame_idx = self.frames.len().checked_sub(depth + 1)?;
+        let slot = self.frames[frame_idx].start + index;
+        let frame_end = self
+            .frames
+            .get(frame_idx + 1)
+            .map(|frame| frame.start)
+            .unwrap_or(self.locals.len());
+        (slot < frame_end).then_some(slot)
+    }
+
+    fn named_var_mut(&mut self, iden: &str) -> Option<&mut Value> {
+        for frame in self.frames.iter().rev() {
+            if let Some((_, index)) = frame.names.iter().rev().find(|(name, _)| name == iden) {
+                return self.locals.get_mut(frame.start + index);
+            }
         }
+        self.globals.get_mut(iden)
     }
 }
 
-impl Default for Scope {
-    fn default() -> Self {
-        Self::new_global()
-    }
+fn initialized(value: &Value) -> Option<Value> {
+    value.is_initialized().then(|| value.clone())
 }
 
 #[cfg(test)]
@@ -252,4 +442,71 @@ mod test {
             ValueType::Number(1.0)
         );
     }
+
+    #[test]
+    fn bound_vars_are_addressed_by_slot() {
+        let mut env = Environment::default();
+        assert_eq!(Binding::Global, env.declare_bound_var("a"));
+        env.new_inner_scope();
+        assert_eq!(
+            Binding::Local { depth: 0, index: 0 },
+            env.declare_bound_var("b")
+        );
+        env.new_inner_scope();
+        assert_eq!(
+            Binding::Local { depth: 0, index: 0 },
+            env.declare_bound_var("c")
+        );
+
+        let outer_b = Binding::Local { depth: 1, index: 0 };
+        env.set_bound_var_value(
+            "b",
+            outer_b,
+            Value::new(ValueType::Number(2.0), CodeSpan::default()),
+        )
+        .unwrap();
+        assert_eq!(
+            env.get_bound_var_value("b", outer_b).unwrap().v_type,
+            ValueType::Number(2.0)
+        );
+        assert_eq!(
+            env.get_bound_var_value("a", Binding::Global)
+                .unwrap()
+                .v_type,
+            ValueType::Nil
+        );
+        // bound locals are not visible by name
+        assert!(env.get_var_value("b").is_none());
+
+        env.teardown_inner_scope();
+        let inner_c = Binding::Local { depth: 0, index: 1 };
+        assert!(env.get_bound_var_value("c", inner_c).is_none());
+        assert!(env
+            .set_bound_var_value("c", inner_c, Value::uninitialized())
+            .is_err());
+    }
+
+    #[test]
+    fn scopes_list_the_variables_from_the_globals_inwards() {
+        let mut env = Environment::default();
+        env.declare_var("b");
+        env.declare_var("a");
+        let one = Value::new(ValueType::Number(1.0), CodeSpan::default());
+        env.set_var_value("a", one.clone()).unwrap();
+        env.new_inner_scope();
+        env.declare_var("c");
+
+        let scopes = env.scopes();
+        let expected = vec![
+            Scope {
+                level: 0,
+                variables: vec![("a".to_owned(), Some(one)), ("b".to_owned(), None)],
+            },
+            Scope {
+                level: 1,
+                variables: vec![("c".to_owned(), None)],
+            },
+        ];
+        assert_eq!(expected, scopes);
+    }
 }
@@ -1,67 +1,185 @@
 use std::fmt::Display;
 
 use crate::{
+    diagnostic::Diagnostic,
     domain::{grammar::StringLiteral, location::CodeSpan},
-    Value, ValueType,
+    suggestion, Environment, Value, ValueType,
 };
 
+use super::Limit;
+
+///
+/// An error which stops the execution of a top-level declaration, or of the whole run if it is
+/// [fatal](RuntimeError::is_fatal)
+///
 #[derive(Debug)]
-pub enum InterpreterError {
+#[allow(clippy::enum_variant_names)]
+pub enum RuntimeError {
     BinaryOperatorError(BinaryOperatorError),
     UnaryOperatorError(UnaryOperatorError),
     IdentifierNotDefinedError(IdentifierNotDefinedError),
     TypeError(TypeError),
+    LimitExceededError(LimitExceededError),
+    InterruptedError(InterruptedError),
+    OutputError(OutputError),
+    UninitializedVariableError(UninitializedVariableError),
 }
 
-impl InterpreterError {
-    pub fn binary_operator(
-        msg: String,
+impl RuntimeError {
+    pub(crate) fn binary_operator(
+        operator: &str,
         span_operator: CodeSpan,
-        span_left: CodeSpan,
-        span_right: CodeSpan,
+        left: &Value,
+        right: &Value,
     ) -> Self {
         Self::BinaryOperatorError(BinaryOperatorError {
-            msg,
+            operator: operator.to_owned(),
+            left: left.v_type.variant_name(),
+            right: right.v_type.variant_name(),
             span_operator,
-            span_left,
-            span_right,
+            span_left: left.span(),
+            span_right: right.span(),
         })
     }
 
-    pub fn type_error(expected: ValueType, val: Value, context: &'static str) -> Self {
+    pub(crate) fn type_error(expected: ValueType, val: Value, context: &'static str) -> Self {
         let expected = expected.variant_name();
-        let actual = val.v_type.variant_name();
+        let found = val.v_type.variant_name();
         let span = val.span();
         Self::TypeError(TypeError {
             expected,
-            actual,
+            found,
             span,
             context,
         })
     }
 
-    pub fn unary_operator(msg: String, span_operator: CodeSpan, span_operand: CodeSpan) -> Self {
+    pub(crate) fn unary_operator(operator: &str, span_operator: CodeSpan, operand: &Value) -> Self {
         Self::UnaryOperatorError(UnaryOperatorError {
-            msg,
+            operator: operator.to_owned(),
+            operand: operand.v_type.variant_name(),
             span_operator,
-            span_operand,
+            span_operand: operand.span(),
+        })
+    }
+
+    ///
+    /// The error for an undefined identifier, suggesting the given name found by the resolver or
+    /// else the most similar of the names visible in the environment
+    ///
+    pub(crate) fn identifier_not_defined(
+        iden: StringLiteral,
+        suggestion: Option<&str>,
+        env: &Environment,
+    ) -> Self {
+        let suggestion = suggestion
+            .or_else(|| suggestion::closest(&iden.value, env.visible_names()))
+            .map(str::to_owned);
+        Self::IdentifierNotDefinedError(IdentifierNotDefinedError {
+            name: iden.value,
+            span: iden.span,
+            suggestion,
+        })
+    }
+
+    pub(crate) fn uninitialized_variable(iden: &StringLiteral) -> Self {
+        Self::UninitializedVariableError(UninitializedVariableError {
+            name: iden.value.clone(),
+            span: iden.span,
+        })
+    }
+
+    pub(crate) fn limit_exceeded(limit: Limit, span: CodeSpan) -> Self {
+        Self::LimitExceededError(LimitExceededError { limit, span })
+    }
+
+    pub(crate) fn interrupted(span: CodeSpan) -> Self {
+        Self::InterruptedError(InterruptedError { span })
+    }
+
+    pub(crate) fn output(error: std::io::Error, span: CodeSpan) -> Self {
+        Self::OutputError(OutputError {
+            msg: error.to_string(),
+            span,
         })
     }
 
-    pub fn identifier_not_defined(iden: StringLiteral) -> Self {
-        Self::IdentifierNotDefinedError(IdentifierNotDefinedError { iden })
+    ///
+    /// Whether the error stops the whole run instead of only the current top-level declaration
+    ///
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            Self::LimitExceededError(_) | Self::InterruptedError(_) | Self::OutputError(_)
+        )
     }
 
-    pub fn msg(self, src_str: &str) -> String {
+    ///
+    /// The stable code of the error, see [crate::errors::explain]
+    ///
+    pub fn code(&self) -> &'static str {
         match self {
-            Self::BinaryOperatorError(e) => e.msg(src_str),
-            Self::UnaryOperatorError(e) => e.msg(src_str),
-            Self::IdentifierNotDefinedError(e) => e.msg(),
-            Self::TypeError(e) => e.msg(),
+            Self::BinaryOperatorError(_) => "L0401",
+            Self::UnaryOperatorError(_) => "L0402",
+            Self::IdentifierNotDefinedError(_) => "L0403",
+            Self::TypeError(_) => "L0404",
+            Self::LimitExceededError(_) => "L0405",
+            Self::InterruptedError(_) => "L0406",
+            Self::OutputError(_) => "L0407",
+            Self::UninitializedVariableError(_) => "L0408",
         }
     }
 
-    pub fn unwrap_bool(val: Value, context: &'static str) -> Result<bool, Self> {
+    ///
+    /// The span of the code which caused the error
+    ///
+    pub fn span(&self) -> CodeSpan {
+        match self {
+            Self::BinaryOperatorError(e) => e.span_operator,
+            Self::UnaryOperatorError(e) => e.span_operand,
+            Self::IdentifierNotDefinedError(e) => e.span,
+            Self::TypeError(e) => e.span,
+            Self::LimitExceededError(e) => e.span,
+            Self::InterruptedError(e) => e.span,
+            Self::OutputError(e) => e.span,
+            Self::UninitializedVariableError(e) => e.span,
+        }
+    }
+
+    ///
+    /// The message of the error together with the spans of the code it refers to
+    ///
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::new(self.to_string()).with_code(self.code());
+        match self {
+            Self::BinaryOperatorError(e) => diagnostic
+                .with_primary(e.span_operator, "operator")
+                .with_secondary(e.span_left, format!("left operand: {}", e.left))
+                .with_secondary(e.span_right, format!("right operand: {}", e.right)),
+            Self::UnaryOperatorError(e) => diagnostic
+                .with_primary(e.span_operand, format!("operand: {}", e.operand))
+                .with_secondary(e.span_operator, "operator"),
+            Self::IdentifierNotDefinedError(e) => {
+                let diagnostic = diagnostic.with_primary(e.span, "not defined");
+                match &e.suggestion {
+                    Some(name) => diagnostic.with_help(format!("did you mean '{name}'?")),
+                    None => diagnostic,
+                }
+            }
+            Self::TypeError(e) => {
+                let label = format!("expected '{}', found '{}'", e.expected, e.found);
+                diagnostic.with_primary(e.span, label)
+            }
+            Self::LimitExceededError(e) => diagnostic.with_primary(e.span, "limit exceeded here"),
+            Self::InterruptedError(e) => diagnostic.with_primary(e.span, "interrupted here"),
+            Self::OutputError(e) => diagnostic.with_primary(e.span, "value to print"),
+            Self::UninitializedVariableError(e) => {
+                diagnostic.with_primary(e.span, "no value assigned yet")
+            }
+        }
+    }
+
+    pub(crate) fn unwrap_bool(val: Value, context: &'static str) -> Result<bool, Self> {
         match val.v_type {
             ValueType::Boolean(b) => Ok(b),
             _ => Err(Self::type_error(ValueType::Boolean(true), val, context)),
@@ -69,135 +187,173 @@ impl InterpreterError {
     }
 }
 
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BinaryOperatorError(e) => write!(f, "{e}"),
+            Self::UnaryOperatorError(e) => write!(f, "{e}"),
+            Self::IdentifierNotDefinedError(e) => write!(f, "{e}"),
+            Self::TypeError(e) => write!(f, "{e}"),
+            Self::LimitExceededError(e) => write!(f, "{e}"),
+            Self::InterruptedError(e) => write!(f, "{e}"),
+            Self::OutputError(e) => write!(f, "{e}"),
+            Self::UninitializedVariableError(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
 #[derive(Debug)]
 pub struct TypeError {
+    /// The name of the expected type
     pub expected: &'static str,
-    pub actual: &'static str,
+    /// The name of the type which was found instead
+    pub found: &'static str,
     pub context: &'static str,
     pub span: CodeSpan,
 }
 
-impl TypeError {
-    fn msg(self) -> String {
-        format!(
-            "type error in line {line} - expected: '{expected}'; found: '{actual}'; context: {context}",
+impl Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "type error - expected: '{expected}'; found: '{found}'; context: {context}",
             expected = self.expected,
-            actual = self.actual,
+            found = self.found,
             context = self.context,
-            line = self.span.start.line
         )
     }
 }
 
 #[derive(Debug)]
-pub struct IdentifierNotDefinedError {
-    pub iden: StringLiteral,
+pub struct LimitExceededError {
+    pub limit: Limit,
+    pub span: CodeSpan,
 }
 
-impl IdentifierNotDefinedError {
-    fn msg(self) -> String {
-        format!(
-            "identifier '{iden}' (used in line {l}) not defined",
-            iden = self.iden,
-            l = self.iden.span.start.line
+impl Display for LimitExceededError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "execution stopped: exceeded the {limit}",
+            limit = self.limit
         )
     }
 }
 
 #[derive(Debug)]
-pub struct BinaryOperatorError {
-    pub msg: String,
-    pub span_operator: CodeSpan,
-    pub span_left: CodeSpan,
-    pub span_right: CodeSpan,
+pub struct InterruptedError {
+    pub span: CodeSpan,
 }
 
-impl BinaryOperatorError {
-    fn msg(self, src_str: &str) -> String {
-        let mut msg = self.msg;
-        msg.push_str("\n");
+impl Display for InterruptedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "execution interrupted")
+    }
+}
 
-        msg.push_str("Left operand source:\n");
-        dbg!(&self.span_left.start.pos);
-        let left_str = &src_str[self.span_left.start.pos..self.span_left.end.pos];
-        msg.push_str(left_str);
-        msg.push_str("\n");
+#[derive(Debug)]
+pub struct OutputError {
+    pub msg: String,
+    pub span: CodeSpan,
+}
 
-        msg.push_str("operator source:\n");
-        let oper_str = &src_str[self.span_operator.start.pos..self.span_operator.end.pos];
-        msg.push_str(oper_str);
-        msg.push_str("\n");
+impl Display for OutputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "failed writing the output of the print statement: {msg}",
+            msg = self.msg
+        )
+    }
+}
 
-        msg.push_str("right operand source:\n");
-        let right_str = &src_str[self.span_right.start.pos..self.span_right.end.pos];
-        msg.push_str(right_str);
+#[derive(Debug)]
+pub struct IdentifierNotDefinedError {
+    pub name: String,
+    pub span: CodeSpan,
+    /// The visible variable with the most similar name, if there is one
+    pub suggestion: Option<String>,
+}
 
-        msg
+impl Display for IdentifierNotDefinedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "identifier '{name}' not defined", name = self.name)
     }
 }
 
 #[derive(Debug)]
-pub struct UnaryOperatorError {
-    pub msg: String,
-    pub span_operator: CodeSpan,
-    pub span_operand: CodeSpan,
+pub struct UninitializedVariableError {
+    pub name: String,
+    pub span: CodeSpan,
 }
 
-impl UnaryOperatorError {
-    fn msg(self, src_str: &str) -> String {
-        let mut msg = self.msg;
-        msg.push_str("\n");
-
-        msg.push_str("operator source:\n");
-        let oper_str = &src_str[self.span_operator.start.pos..self.span_operator.end.pos];
-        msg.push_str(oper_str);
+impl Display for UninitializedVariableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "variable '{name}' is read before a value is assigned to it",
+            name = self.name
+        )
+    }
+}
 
-        msg.push_str("operand source:\n");
-        let operand_str = &src_str[self.span_operand.start.pos..self.span_operand.end.pos];
-        msg.push_str(operand_str);
+#[derive(Debug)]
+pub struct BinaryOperatorError {
+    pub operator: String,
+    /// The type name of the left operand
+    pub left: &'static str,
+    /// The type name of the right operand
+    pub right: &'static str,
+    pub span_operator: CodeSpan,
+    pub span_left: CodeSpan,
+    pub span_right: CodeSpan,
+}
 
-        msg
+impl Display for BinaryOperatorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "operator {oper} not defined for types {left} and {right}",
+            oper = self.operator,
+            left = self.left,
+            right = self.right,
+        )
     }
 }
 
-impl Display for InterpreterError {
-    // TODO: Should have a proper display for different variants
+#[derive(Debug)]
+pub struct UnaryOperatorError {
+    pub operator: String,
+    /// The type name of the operand
+    pub operand: &'static str,
+    pub span_operator: CodeSpan,
+    pub span_operand: CodeSpan,
+}
+
+impl Display for UnaryOperatorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{message}", message = "interpreter_error")
+        write!(
+            f,
+            "operator {oper} not defined for type {operand}",
+            oper = self.operator,
+            operand = self.operand,
+        )
     }
 }
 
-impl std::error::Error for InterpreterError {}
-
 #[macro_export]
 macro_rules! operator_error {
     // binary operators
-    ($left:ident, $right:ident, $oper_name: expr) => {
-        let msg = format!(
-            "operator {oper} not defined for types {left} and {right}",
-            oper = $oper_name,
-            left = $left.v_type,
-            right = $right.v_type,
-        );
-
-        let err = InterpreterError::binary_operator(
-            msg,
-            CodeSpan::in_between($left.span(), $right.span()),
-            $left.span(),
-            $right.span(),
-        );
-        return Err(err);
+    ($left:ident, $right:ident, $oper_name: expr, $oper_span: expr) => {
+        return Err(RuntimeError::binary_operator(
+            $oper_name, $oper_span, &$left, &$right,
+        ));
     };
 
     // unary operators
-    ($val:ident, $oper_name: expr) => {
-        let msg = format!(
-            "operator {oper} not defined for type {val}",
-            oper = $oper_name,
-            val = $val.v_type,
-        );
-
-        let err = InterpreterError::unary_operator(msg, $val.span(), $val.span());
-        return Err(err);
+    ($val:ident, $oper_name: expr, $oper_span: expr) => {
+        return Err(RuntimeError::unary_operator($oper_name, $oper_span, &$val));
     };
 }
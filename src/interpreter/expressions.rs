@@ -1,6 +1,6 @@
 use crate::{Environment, Value};
 
-use super::error::InterpreterError;
+use super::error::RuntimeError;
 
 mod comparison;
 mod equality;
@@ -12,5 +12,5 @@ mod term;
 mod unary;
 
 pub(crate) trait InterpretedExpression {
-    fn interpret_expression(&self, env: &Environment) -> Result<Value, InterpreterError>;
+    fn interpret_expression(&self, env: &Environment) -> Result<Value, RuntimeError>;
 }
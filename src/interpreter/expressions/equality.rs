@@ -3,64 +3,41 @@ use crate::{
         grammar::{Comparison, Equality},
         location::CodeSpan,
     },
-    interpreter::error::InterpreterError,
-    operator_error, Environment, Value, ValueType,
+    interpreter::{
+        error::RuntimeError,
+        operations::{self, EqualityOperator},
+    },
+    Environment, Value,
 };
 
 use super::InterpretedExpression;
 
 impl InterpretedExpression for Equality {
-    fn interpret_expression(&self, state: &Environment) -> Result<Value, InterpreterError> {
+    fn interpret_expression(&self, state: &Environment) -> Result<Value, RuntimeError> {
         match self {
             Equality::Comparison(c) => c.interpret_expression(state),
-            Equality::EqualityCheck { left, right } => {
-                operation(left, right, Operator::Equal, state)
-            }
-            Equality::InequalityCheck { left, right } => {
-                operation(left, right, Operator::NotEqual, state)
-            }
+            Equality::EqualityCheck {
+                left,
+                operator,
+                right,
+            } => operation(left, *operator, right, EqualityOperator::Equal, state),
+            Equality::InequalityCheck {
+                left,
+                operator,
+                right,
+            } => operation(left, *operator, right, EqualityOperator::NotEqual, state),
         }
     }
 }
 
-enum Operator {
-    Equal,
-    NotEqual,
-}
-
 fn operation(
-    left: &Box<Equality>,
+    left: &Equality,
+    operator_span: CodeSpan,
     right: &Comparison,
-    operator: Operator,
+    operator: EqualityOperator,
     state: &Environment,
-) -> Result<Value, InterpreterError> {
+) -> Result<Value, RuntimeError> {
     let left_val = left.interpret_expression(state)?;
     let right_val = right.interpret_expression(state)?;
-
-    let b = match (&left_val.v_type, &right_val.v_type) {
-        (ValueType::Number(l), ValueType::Number(r)) => match operator {
-            Operator::Equal => l == r,
-            Operator::NotEqual => l != r,
-        },
-        (ValueType::Boolean(l), ValueType::Boolean(r)) => match operator {
-            Operator::Equal => l == r,
-            Operator::NotEqual => l != r,
-        },
-        (ValueType::String(l), ValueType::String(r)) => match operator {
-            Operator::Equal => l == r,
-            Operator::NotEqual => l != r,
-        },
-        (_, _) => {
-            let oper_str = match operator {
-                Operator::Equal => "==",
-                Operator::NotEqual => "!=",
-            };
-            operator_error!(left_val, right_val, oper_str);
-        }
-    };
-    let value = Value::new(
-        ValueType::Boolean(b),
-        CodeSpan::merged(left_val.span(), right_val.span()),
-    );
-    Ok(value)
+    operations::check_equality(left_val, right_val, operator, operator_span)
 }
@@ -1,15 +1,17 @@
 use anyhow::Result;
 
-use crate::{
-    domain::grammar::Expression, interpreter::error::InterpreterError, Environment, Value,
-};
+use crate::{domain::grammar::Expression, interpreter::error::RuntimeError, Environment, Value};
 
 use super::InterpretedExpression;
 
 impl InterpretedExpression for Expression {
-    fn interpret_expression(&self, state: &Environment) -> Result<Value, InterpreterError> {
-        match self {
+    fn interpret_expression(&self, state: &Environment) -> Result<Value, RuntimeError> {
+        let budget = state.budget();
+        budget.enter();
+        let result = budget.charge(|| self.span()).and_then(|()| match self {
             Expression::LogicOr(l_or) => l_or.interpret_expression(state),
-        }
+        });
+        budget.leave();
+        result
     }
 }
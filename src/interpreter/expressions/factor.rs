@@ -1,42 +1,32 @@
 use crate::{
-    domain::{grammar::Factor, location::CodeSpan},
-    interpreter::error::InterpreterError,
-    operator_error, Environment, Value, ValueType,
+    domain::grammar::Factor,
+    interpreter::{error::RuntimeError, operations},
+    Environment, Value,
 };
 
 use super::InterpretedExpression;
 
 impl InterpretedExpression for Factor {
-    fn interpret_expression(&self, state: &Environment) -> Result<Value, InterpreterError> {
+    fn interpret_expression(&self, state: &Environment) -> Result<Value, RuntimeError> {
         match self {
             Factor::Unary(u) => u.interpret_expression(state),
-            Factor::Multiplication { left, right } => {
+            Factor::Multiplication {
+                left,
+                operator,
+                right,
+            } => {
                 let left_val = left.interpret_expression(state)?;
                 let right_val = right.interpret_expression(state)?;
-
-                match (&left_val.v_type, &right_val.v_type) {
-                    (ValueType::Number(l), ValueType::Number(r)) => Ok(Value::new(
-                        ValueType::Number(l * r),
-                        CodeSpan::merged(left_val.span(), right_val.span()),
-                    )),
-                    (_, _) => {
-                        operator_error!(left_val, right_val, "*");
-                    }
-                }
+                operations::multiply(left_val, right_val, *operator)
             }
-            Factor::Division { left, right } => {
+            Factor::Division {
+                left,
+                operator,
+                right,
+            } => {
                 let left_val = left.interpret_expression(state)?;
                 let right_val = right.interpret_expression(state)?;
-
-                match (&left_val.v_type, &right_val.v_type) {
-                    (ValueType::Number(l), ValueType::Number(r)) => Ok(Value::new(
-                        ValueType::Number(l / r),
-                        CodeSpan::merged(left_val.span(), right_val.span()),
-                    )),
-                    (_, _) => {
-                        operator_error!(left_val, right_val, "/");
-                    }
-                }
+                operations::divide(left_val, right_val, *operator)
             }
         }
     }
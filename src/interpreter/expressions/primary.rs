@@ -2,29 +2,45 @@ use anyhow::Result;
 
 use crate::{
     domain::grammar::{NumLiteral, Primary, StringLiteral},
-    interpreter::error::InterpreterError,
+    interpreter::error::RuntimeError,
     Environment, Value, ValueType,
 };
 
 use super::InterpretedExpression;
 
 impl InterpretedExpression for Primary {
-    fn interpret_expression(&self, state: &Environment) -> Result<Value, InterpreterError> {
+    fn interpret_expression(&self, state: &Environment) -> Result<Value, RuntimeError> {
         let val = match self {
             Primary::Number(NumLiteral { value, span }) => {
                 Value::new(ValueType::Number(*value), *span)
             }
-            Primary::String(StringLiteral { value, span }) => {
-                Value::new(ValueType::string(value), *span)
-            }
+            Primary::String(StringLiteral { value, span }) => Value::new(
+                ValueType::String(state.heap().alloc_string(value.clone())),
+                *span,
+            ),
             Primary::True(span) => Value::new(ValueType::Boolean(true), *span),
             Primary::False(span) => Value::new(ValueType::Boolean(false), *span),
             Primary::Nil(span) => Value::new(ValueType::Nil, *span),
             Primary::GroupedExpression(expr) => expr.interpret_expression(state)?,
-            Primary::Identifier(iden) => match state.get_var_value(iden.as_ref()) {
-                Some(v) => v.clone(),
-                None => return Err(InterpreterError::identifier_not_defined(iden.clone())),
-            },
+            Primary::Identifier(var) => {
+                let value = match var.binding {
+                    Some(binding) => state.get_bound_var_value(var.as_ref(), binding),
+                    None => state.get_var_value(var.as_ref()),
+                };
+                match value {
+                    Some(v) if !v.is_initialized() => {
+                        return Err(RuntimeError::uninitialized_variable(&var.name))
+                    }
+                    Some(v) => v.clone().at(var.name.span),
+                    None => {
+                        return Err(RuntimeError::identifier_not_defined(
+                            var.name.clone(),
+                            var.suggestion.as_deref(),
+                            state,
+                        ))
+                    }
+                }
+            }
         };
         Ok(val)
     }
@@ -1,46 +1,32 @@
 use crate::{
-    domain::{grammar::Term, location::CodeSpan},
-    interpreter::error::InterpreterError,
-    operator_error, Environment, Value, ValueType,
+    domain::grammar::Term,
+    interpreter::{error::RuntimeError, operations},
+    Environment, Value,
 };
 
 use super::InterpretedExpression;
 
 impl InterpretedExpression for Term {
-    fn interpret_expression(&self, state: &Environment) -> Result<Value, InterpreterError> {
+    fn interpret_expression(&self, state: &Environment) -> Result<Value, RuntimeError> {
         match self {
             Term::Factor(f) => f.interpret_expression(state),
-            Term::Addition { left, right } => {
+            Term::Addition {
+                left,
+                operator,
+                right,
+            } => {
                 let l_val = left.interpret_expression(state)?;
                 let r_val = right.interpret_expression(state)?;
-
-                match (&l_val.v_type, &r_val.v_type) {
-                    (ValueType::Number(l), ValueType::Number(r)) => Ok(Value::new(
-                        ValueType::Number(l + r),
-                        CodeSpan::merged(l_val.span(), r_val.span()),
-                    )),
-                    (ValueType::String(l), ValueType::String(r)) => Ok(Value::new(
-                        ValueType::String(format!("{}{}", l, r)),
-                        CodeSpan::merged(l_val.span(), r_val.span()),
-                    )),
-                    (_, _) => {
-                        operator_error!(l_val, r_val, "+");
-                    }
-                }
+                operations::add(l_val, r_val, *operator, state)
             }
-            Term::Subtraction { left, right } => {
+            Term::Subtraction {
+                left,
+                operator,
+                right,
+            } => {
                 let left_val = left.interpret_expression(state)?;
                 let right_val = right.interpret_expression(state)?;
-
-                match (&left_val.v_type, &right_val.v_type) {
-                    (ValueType::Number(l), ValueType::Number(r)) => Ok(Value::new(
-                        ValueType::Number(l - r),
-                        CodeSpan::merged(left_val.span(), right_val.span()),
-                    )),
-                    (_, _) => {
-                        operator_error!(left_val, right_val, "-");
-                    }
-                }
+                operations::subtract(left_val, right_val, *operator)
             }
         }
     }
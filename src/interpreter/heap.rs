@@ -0,0 +1,287 @@
+//! The managed heap for compound values. Objects are reference counted, so that handles can be
+//! copied cheaply and are never left dangling; the heap additionally keeps track of every object it
+//! allocated and releases the ones which are no longer reachable from the roots in a mark-and-sweep
+//! collection. Dropping the heap's reference is enough to free acyclic objects; objects which can
+//! hold other handles release them when they are swept, which breaks reference cycles.
+//!
+//! Collections only run at safe points (between statements for the tree-walking interpreter, between
+//! instructions for the VM), where all live values are reachable from the environment. Allocations
+//! merely request a collection once the allocated bytes exceed a threshold, or always in stress mode.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt::{Debug, Display},
+    ops::Deref,
+    rc::Rc,
+};
+
+use crate::{Value, ValueType};
+
+/// Allocated bytes after which the first collection is requested
+const INITIAL_THRESHOLD: usize = 1024 * 1024;
+/// After a collection, the next one is requested once the live bytes have grown by this factor
+const GROWTH_FACTOR: usize = 2;
+
+///
+/// A handle to an object on the managed heap. Handles created outside of an interpreter (e.g., via
+/// [ValueType::string]) are not tracked by any heap and live as long as they are referenced.
+///
+pub struct Gc<T>(Rc<GcBox<T>>);
+
+struct GcBox<T> {
+    /// The number of the last collection which found the object reachable
+    marked_in: Cell<u64>,
+    value: T,
+}
+
+impl<T> Gc<T> {
+    pub fn new(value: T) -> Self {
+        Self(Rc::new(GcBox {
+            marked_in: Cell::new(0),
+            value,
+        }))
+    }
+}
+
+impl<T> Clone for Gc<T> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<T> Deref for Gc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Gc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.value == other.0.value
+    }
+}
+
+impl<T: Debug> Debug for Gc<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.value.fmt(f)
+    }
+}
+
+impl<T: Display> Display for Gc<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.value.fmt(f)
+    }
+}
+
+///
+/// The type-erased view of the objects on the heap used by the collector
+///
+trait HeapObject {
+    fn marked_in(&self) -> &Cell<u64>;
+
+    ///
+    /// Adds the handles held by the object to the work list of the collector
+    ///
+    fn trace(&self, _grey: &mut Vec<Rc<dyn HeapObject>>) {}
+
+    ///
+    /// Drops the handles held by the object once it was found unreachable
+    ///
+    fn release(&self) {}
+
+    fn size(&self) -> usize;
+}
+
+impl HeapObject for GcBox<String> {
+    fn marked_in(&self) -> &Cell<u64> {
+        &self.marked_in
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.value.capacity()
+    }
+}
+
+///
+/// Statistics about the allocations and collections of the heap
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GcStats {
+    /// The number of objects allocated so far
+    pub allocations: usize,
+    /// The number of collections run so far
+    pub collections: usize,
+    /// The number of objects the collections stopped tracking so far because the roots did not
+    /// reach them
+    pub released: usize,
+    /// The number of released objects which were reclaimed, i.e., which nothing referred to
+    /// anymore; a released object which is still referenced (e.g., by a value the host kept) is
+    /// reclaimed once that reference is dropped, which is not counted
+    pub freed: usize,
+    /// The number of objects currently tracked by the heap
+    pub live_objects: usize,
+    /// The size of the objects currently tracked by the heap, in bytes
+    pub live_bytes: usize,
+}
+
+pub(crate) struct Heap {
+    objects: RefCell<Vec<Rc<dyn HeapObject>>>,
+    stats: Cell<GcStats>,
+    /// The live bytes at which the next collection is requested
+    next_collection: Cell<usize>,
+    collection_requested: Cell<bool>,
+    /// When set, every allocation requests a collection, which runs at the next safe point
+    stress: bool,
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self {
+            objects: RefCell::default(),
+            stats: Cell::default(),
+            next_collection: Cell::new(INITIAL_THRESHOLD),
+            collection_requested: Cell::new(false),
+            stress: false,
+        }
+    }
+}
+
+impl Debug for Heap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Heap")
+            .field("stats", &self.stats.get())
+            .field("stress", &self.stress)
+            .finish()
+    }
+}
+
+impl Heap {
+    pub(crate) fn set_stress(&mut self, stress: bool) {
+        self.stress = stress;
+    }
+
+    pub(crate) fn stats(&self) -> GcStats {
+        self.stats.get()
+    }
+
+    pub(crate) fn alloc_string(&self, value: String) -> Gc<String> {
+        let gc = Gc::new(value);
+        let size = gc.0.size();
+        self.objects.borrow_mut().push(gc.0.clone());
+
+        let mut stats = self.stats.get();
+        stats.allocations += 1;
+        stats.live_objects += 1;
+        stats.live_bytes += size;
+        self.stats.set(stats);
+        if self.stress || stats.live_bytes > self.next_collection.get() {
+            self.collection_requested.set(true);
+        }
+        gc
+    }
+
+    pub(crate) fn collection_requested(&self) -> bool {
+        self.collection_requested.get()
+    }
+
+    ///
+    /// Runs a full collection; all objects which are not reachable from the given roots are
+    /// released
+    ///
+    pub(crate) fn collect<'a>(&self, roots: impl IntoIterator<Item = &'a Value>) {
+        let mut stats = self.stats.get();
+        stats.collections += 1;
+        let epoch = stats.collections as u64;
+
+        let mut grey: Vec<Rc<dyn HeapObject>> = vec![];
+        for root in roots {
+            if let ValueType::String(gc) = &root.v_type {
+                grey.push(gc.0.clone());
+            }
+        }
+        while let Some(object) = grey.pop() {
+            if object.marked_in().replace(epoch) != epoch {
+                object.trace(&mut grey);
+            }
+        }
+
+        self.objects.borrow_mut().retain(|object| {
+            if object.marked_in().get() == epoch {
+                return true;
+            }
+            object.release();
+            stats.released += 1;
+            if Rc::strong_count(object) == 1 {
+                stats.freed += 1;
+            }
+            stats.live_objects -= 1;
+            stats.live_bytes -= object.size();
+            false
+        });
+
+        self.stats.set(stats);
+        self.next_collection
+            .set(INITIAL_THRESHOLD.max(stats.live_bytes * GROWTH_FACTOR));
+        self.collection_requested.set(false);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{domain::location::CodeSpan, Value, ValueType};
+
+    use super::Heap;
+
+    fn string_value(heap: &Heap, s: &str) -> Value {
+        Value::new(
+            ValueType::String(heap.alloc_string(s.to_owned())),
+            CodeSpan::default(),
+        )
+    }
+
+    #[test]
+    fn unreachable_objects_are_released() {
+        let heap = Heap::default();
+        let kept = string_value(&heap, "kept");
+        string_value(&heap, "dropped");
+
+        heap.collect([&kept]);
+
+        let stats = heap.stats();
+        assert_eq!(2, stats.allocations);
+        assert_eq!(1, stats.collections);
+        assert_eq!(1, stats.released);
+        assert_eq!(1, stats.freed);
+        assert_eq!(1, stats.live_objects);
+        assert_eq!(1, heap.objects.borrow().len());
+    }
+
+    #[test]
+    fn released_objects_stay_valid_while_referenced() {
+        let heap = Heap::default();
+        let value = string_value(&heap, "still here");
+
+        heap.collect([]);
+
+        let stats = heap.stats();
+        assert_eq!(0, stats.live_objects);
+        assert_eq!((1, 0), (stats.released, stats.freed));
+        assert_eq!(ValueType::string("still here"), value.v_type);
+    }
+
+    #[test]
+    fn stress_mode_requests_collection_on_every_allocation() {
+        let mut heap = Heap::default();
+        string_value(&heap, "a");
+        assert!(!heap.collection_requested());
+
+        heap.set_stress(true);
+        string_value(&heap, "b");
+        assert!(heap.collection_requested());
+        heap.collect([]);
+        assert!(!heap.collection_requested());
+        assert_eq!(0, heap.stats().live_bytes);
+    }
+}
@@ -0,0 +1,235 @@
+//! Semantics of the operators of the language, shared by the tree-walking interpreter and the VM.
+
+use crate::{domain::location::CodeSpan, operator_error, Value, ValueType};
+
+use super::{error::RuntimeError, Environment};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ComparisonOperator {
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EqualityOperator {
+    Equal,
+    NotEqual,
+}
+
+///
+/// Adds numbers or concatenates strings; new strings are allocated on the heap of the environment
+///
+pub(crate) fn add(
+    l_val: Value,
+    r_val: Value,
+    operator: CodeSpan,
+    env: &Environment,
+) -> Result<Value, RuntimeError> {
+    match (&l_val.v_type, &r_val.v_type) {
+        (ValueType::Number(l), ValueType::Number(r)) => Ok(Value::new(
+            ValueType::Number(l + r),
+            CodeSpan::merged(l_val.span(), r_val.span()),
+        )),
+        (ValueType::String(l), ValueType::String(r)) => {
+            let span = CodeSpan::merged(l_val.span(), r_val.span());
+            env.budget().check_string_len(l.len() + r.len(), || span)?;
+            Ok(Value::new(
+                ValueType::String(env.heap().alloc_string(format!("{}{}", l, r))),
+                span,
+            ))
+        }
+        (_, _) => {
+            operator_error!(l_val, r_val, "+", operator);
+        }
+    }
+}
+
+pub(crate) fn subtract(
+    left_val: Value,
+    right_val: Value,
+    operator: CodeSpan,
+) -> Result<Value, RuntimeError> {
+    match (&left_val.v_type, &right_val.v_type) {
+        (ValueType::Number(l), ValueType::Number(r)) => Ok(Value::new(
+            ValueType::Number(l - r),
+            CodeSpan::merged(left_val.span(), right_val.span()),
+        )),
+        (_, _) => {
+            operator_error!(left_val, right_val, "-", operator);
+        }
+    }
+}
+
+pub(crate) fn multiply(
+    left_val: Value,
+    right_val: Value,
+    operator: CodeSpan,
+) -> Result<Value, RuntimeError> {
+    match (&left_val.v_type, &right_val.v_type) {
+        (ValueType::Number(l), ValueType::Number(r)) => Ok(Value::new(
+            ValueType::Number(l * r),
+            CodeSpan::merged(left_val.span(), right_val.span()),
+        )),
+        (_, _) => {
+            operator_error!(left_val, right_val, "*", operator);
+        }
+    }
+}
+
+pub(crate) fn divide(
+    left_val: Value,
+    right_val: Value,
+    operator: CodeSpan,
+) -> Result<Value, RuntimeError> {
+    match (&left_val.v_type, &right_val.v_type) {
+        (ValueType::Number(l), ValueType::Number(r)) => Ok(Value::new(
+            ValueType::Number(l / r),
+            CodeSpan::merged(left_val.span(), right_val.span()),
+        )),
+        (_, _) => {
+            operator_error!(left_val, right_val, "/", operator);
+        }
+    }
+}
+
+pub(crate) fn compare(
+    left_val: Value,
+    right_val: Value,
+    operator: ComparisonOperator,
+    operator_span: CodeSpan,
+) -> Result<Value, RuntimeError> {
+    let b = match (&left_val.v_type, &right_val.v_type) {
+        (ValueType::Number(l), ValueType::Number(r)) => match operator {
+            ComparisonOperator::Greater => l > r,
+            ComparisonOperator::GreaterEqual => l >= r,
+            ComparisonOperator::Less => l < r,
+            ComparisonOperator::LessEqual => l <= r,
+        },
+        (_, _) => {
+            let oper_str = match operator {
+                ComparisonOperator::Greater => ">",
+                ComparisonOperator::GreaterEqual => ">=",
+                ComparisonOperator::Less => "<",
+                ComparisonOperator::LessEqual => "<=",
+            };
+            operator_error!(left_val, right_val, oper_str, operator_span);
+        }
+    };
+    let value = Value::new(
+        ValueType::Boolean(b),
+        CodeSpan::merged(left_val.span(), right_val.span()),
+    );
+    Ok(value)
+}
+
+pub(crate) fn check_equality(
+    left_val: Value,
+    right_val: Value,
+    operator: EqualityOperator,
+    operator_span: CodeSpan,
+) -> Result<Value, RuntimeError> {
+    let b = match (&left_val.v_type, &right_val.v_type) {
+        (ValueType::Number(l), ValueType::Number(r)) => match operator {
+            EqualityOperator::Equal => l == r,
+            EqualityOperator::NotEqual => l != r,
+        },
+        (ValueType::Boolean(l), ValueType::Boolean(r)) => match operator {
+            EqualityOperator::Equal => l == r,
+            EqualityOperator::NotEqual => l != r,
+        },
+        (ValueType::String(l), ValueType::String(r)) => match operator {
+            EqualityOperator::Equal => l == r,
+            EqualityOperator::NotEqual => l != r,
+        },
+        (_, _) => {
+            let oper_str = match operator {
+                EqualityOperator::Equal => "==",
+                EqualityOperator::NotEqual => "!=",
+            };
+            operator_error!(left_val, right_val, oper_str, operator_span);
+        }
+    };
+    let value = Value::new(
+        ValueType::Boolean(b),
+        CodeSpan::merged(left_val.span(), right_val.span()),
+    );
+    Ok(value)
+}
+
+///
+/// Combines the operands of an `or` whose left operand did not short-circuit
+///
+pub(crate) fn or(
+    left_val: Value,
+    right_val: Value,
+    operator: CodeSpan,
+) -> Result<Value, RuntimeError> {
+    match (&left_val.v_type, &right_val.v_type) {
+        (ValueType::Boolean(l), ValueType::Boolean(r)) => Ok(Value::new(
+            ValueType::Boolean(*l || *r),
+            CodeSpan::merged(left_val.span(), right_val.span()),
+        )),
+        (_, _) => {
+            operator_error!(left_val, right_val, "||", operator);
+        }
+    }
+}
+
+///
+/// Combines the operands of an `and` whose left operand did not short-circuit
+///
+pub(crate) fn and(
+    left_val: Value,
+    right_val: Value,
+    operator: CodeSpan,
+) -> Result<Value, RuntimeError> {
+    match (&left_val.v_type, &right_val.v_type) {
+        (ValueType::Boolean(l), ValueType::Boolean(r)) => Ok(Value::new(
+            ValueType::Boolean(*l && *r),
+            CodeSpan::merged(left_val.span(), right_val.span()),
+        )),
+        (_, _) => {
+            operator_error!(left_val, right_val, "&&", operator);
+        }
+    }
+}
+
+///
+/// Whether the left operand of an `or` already decides the result
+///
+pub(crate) fn or_short_circuits(left_val: &Value) -> bool {
+    matches!(left_val.v_type, ValueType::Boolean(true))
+}
+
+///
+/// Whether the left operand of an `and` already decides the result
+///
+pub(crate) fn and_short_circuits(left_val: &Value) -> bool {
+    matches!(left_val.v_type, ValueType::Boolean(false))
+}
+
+pub(crate) fn not(val: Value, operator: CodeSpan) -> Result<Value, RuntimeError> {
+    match &val.v_type {
+        ValueType::Boolean(b) => Ok(Value::new(
+            ValueType::Boolean(!b),
+            CodeSpan::merged(operator, val.span()),
+        )),
+        _ => {
+            operator_error!(val, "!", operator);
+        }
+    }
+}
+
+pub(crate) fn negate(val: Value, operator: CodeSpan) -> Result<Value, RuntimeError> {
+    match &val.v_type {
+        ValueType::Number(n) => Ok(Value::new(
+            ValueType::Number(-n),
+            CodeSpan::merged(operator, val.span()),
+        )),
+        _ => {
+            operator_error!(val, "-", operator);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! What happens when a top-level declaration fails, and the bookkeeping of the declarations run.
+
+use super::error::RuntimeError;
+
+///
+/// Whether the run goes on after a top-level declaration failed with a runtime error
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum OnError {
+    /// Stop the run at the first failed declaration
+    Stop,
+    /// Run the remaining declarations; a variable whose initializer failed is declared without a
+    /// value (a redeclared global keeps its previous one), so reading it is an error
+    #[default]
+    Continue,
+}
+
+///
+/// The handling of runtime errors in one call of [crate::Interpreter::interpret_src_str]. Fatal
+/// errors (e.g., an exceeded limit) stop the run regardless of the policy.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorPolicy {
+    pub on_error: OnError,
+    /// The number of errors after which the run stops even if it would continue; `None` means
+    /// unlimited
+    pub max_errors: Option<usize>,
+}
+
+impl ErrorPolicy {
+    fn stops_after(&self, errors: usize) -> bool {
+        self.on_error == OnError::Stop || self.max_errors.is_some_and(|max| errors >= max)
+    }
+}
+
+///
+/// The number of top-level declarations of the last run, how many of them were run, and how many
+/// of those failed. The declaration which stopped the run counts as run.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RunStats {
+    pub declarations: usize,
+    pub declarations_run: usize,
+    pub declarations_failed: usize,
+}
+
+///
+/// Runs the top-level declarations one after the other as the policy allows; `run` executes the
+/// declaration with the given index
+///
+pub(crate) fn run_declarations(
+    declarations: usize,
+    policy: ErrorPolicy,
+    mut run: impl FnMut(usize) -> Result<(), RuntimeError>,
+) -> (RunStats, Vec<RuntimeError>) {
+    let mut stats = RunStats {
+        declarations,
+        ..Default::default()
+    };
+    let mut errors = vec![];
+    for idx in 0..declarations {
+        stats.declarations_run += 1;
+        if let Err(e) = run(idx) {
+            stats.declarations_failed += 1;
+            let fatal = e.is_fatal();
+            errors.push(e);
+            if fatal || policy.stops_after(errors.len()) {
+                break;
+            }
+        }
+    }
+    (stats, errors)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::{grammar::StringLiteral, location::CodeSpan},
+        interpreter::error::RuntimeError,
+        Environment,
+    };
+
+    use super::{run_declarations, ErrorPolicy, OnError};
+
+    /// Fails with a fatal error for the declarations with odd indices
+    fn failing_odd(idx: usize) -> Result<(), RuntimeError> {
+        match idx % 2 {
+            0 => Ok(()),
+            _ => Err(RuntimeError::interrupted(CodeSpan::default())),
+        }
+    }
+
+    fn failing_odd_non_fatal(idx: usize) -> Result<(), RuntimeError> {
+        failing_odd(idx).map_err(|_| {
+            let name = StringLiteral {
+                value: "a".to_owned(),
+                span: CodeSpan::default(),
+            };
+            RuntimeError::identifier_not_defined(name, None, &Environment::default())
+        })
+    }
+
+    #[test]
+    fn continues_by_default() {
+        let (stats, errors) = run_declarations(5, ErrorPolicy::default(), failing_odd_non_fatal);
+        assert_eq!(5, stats.declarations_run);
+        assert_eq!(2, stats.declarations_failed);
+        assert_eq!(2, errors.len());
+    }
+
+    #[test]
+    fn stops_at_the_first_error() {
+        let policy = ErrorPolicy {
+            on_error: OnError::Stop,
+            ..Default::default()
+        };
+        let (stats, errors) = run_declarations(5, policy, failing_odd_non_fatal);
+        assert_eq!(2, stats.declarations_run);
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn stops_after_the_maximum_number_of_errors() {
+        let policy = ErrorPolicy {
+            max_errors: Some(2),
+            ..Default::default()
+        };
+        let (stats, errors) = run_declarations(7, policy, failing_odd_non_fatal);
+        assert_eq!(4, stats.declarations_run);
+        assert_eq!(2, errors.len());
+    }
+
+    #[test]
+    fn fatal_errors_always_stop() {
+        let (stats, errors) = run_declarations(5, ErrorPolicy::default(), failing_odd);
+        assert_eq!(2, stats.declarations_run);
+        assert_eq!(1, errors.len());
+    }
+}
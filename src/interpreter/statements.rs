@@ -1,4 +1,4 @@
-use super::{error::InterpreterError, Environment};
+use super::{error::RuntimeError, Environment};
 
 mod block;
 mod declaration;
@@ -10,5 +10,5 @@ mod var_declaration;
 mod while_loop;
 
 pub(crate) trait InterpretedStatement {
-    fn interpret_statement(&self, environment: &mut Environment) -> Result<(), InterpreterError>;
+    fn interpret_statement(&self, environment: &mut Environment) -> Result<(), RuntimeError>;
 }
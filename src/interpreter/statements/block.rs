@@ -1,11 +1,13 @@
-use crate::{domain::grammar::Block, interpreter::error::InterpreterError, Environment};
+use crate::{domain::grammar::Block, interpreter::error::RuntimeError, Environment};
 
 use super::InterpretedStatement;
 
 impl InterpretedStatement for Block {
-    fn interpret_statement(&self, environment: &mut Environment) -> Result<(), InterpreterError> {
+    fn interpret_statement(&self, environment: &mut Environment) -> Result<(), RuntimeError> {
         environment.new_inner_scope();
+        environment.budget().enter();
         let inner_result = self.interpret_statements_in_inner_scope(environment);
+        environment.budget().leave();
         environment.teardown_inner_scope();
         inner_result
     }
@@ -15,8 +17,9 @@ impl Block {
     fn interpret_statements_in_inner_scope(
         &self,
         env: &mut Environment,
-    ) -> Result<(), InterpreterError> {
+    ) -> Result<(), RuntimeError> {
         for decl in self.as_ref() {
+            env.budget().check_interrupt(|| decl.span())?;
             decl.interpret_statement(env)?;
         }
         Ok(())
@@ -1,16 +1,25 @@
 use crate::{
     domain::grammar::Declaration,
-    interpreter::{error::InterpreterError, Environment},
+    interpreter::{error::RuntimeError, Environment},
 };
 
 use super::InterpretedStatement;
 
 impl InterpretedStatement for Declaration {
-    fn interpret_statement(&self, env: &mut Environment) -> Result<(), InterpreterError> {
-        match self {
-            Declaration::Declaration(var_decl) => var_decl.interpret_statement(env),
-            Declaration::Statement(s) => s.interpret_statement(env),
+    fn interpret_statement(&self, env: &mut Environment) -> Result<(), RuntimeError> {
+        let result = match self {
+            Declaration::Declaration(var_decl) => env
+                .pause_point(var_decl.span())
+                .and_then(|()| env.budget().charge(|| var_decl.span()))
+                .and_then(|()| var_decl.interpret_statement(env)),
+            Declaration::Statement(s, span) => env
+                .pause_point(*span)
+                .and_then(|()| env.budget().charge(|| *span))
+                .and_then(|()| s.interpret_statement(env)),
             Declaration::Block(block) => block.interpret_statement(env),
-        }
+        };
+        // between two declarations, all live values are stored in the environment
+        env.safepoint(&[]);
+        result
     }
 }
@@ -1,9 +1,9 @@
-use crate::{domain::grammar::DesugeredFor, interpreter::error::InterpreterError, Environment};
+use crate::{domain::grammar::DesugeredFor, interpreter::error::RuntimeError, Environment};
 
 use super::InterpretedStatement;
 
 impl InterpretedStatement for DesugeredFor {
-    fn interpret_statement(&self, env: &mut Environment) -> Result<(), InterpreterError> {
+    fn interpret_statement(&self, env: &mut Environment) -> Result<(), RuntimeError> {
         self.for_block.interpret_statement(env)?;
         Ok(())
     }
@@ -1,12 +1,12 @@
 use crate::{
     domain::grammar::Statement,
-    interpreter::{error::InterpreterError, expressions::InterpretedExpression, Environment},
+    interpreter::{error::RuntimeError, expressions::InterpretedExpression, Environment},
 };
 
 use super::InterpretedStatement;
 
 impl InterpretedStatement for Statement {
-    fn interpret_statement(&self, env: &mut Environment) -> Result<(), InterpreterError> {
+    fn interpret_statement(&self, env: &mut Environment) -> Result<(), RuntimeError> {
         match self {
             Statement::Expression(e) => {
                 let value = e.interpret_expression(env)?;
@@ -15,20 +15,31 @@ impl InterpretedStatement for Statement {
             }
             Statement::Print(e) => {
                 let value = e.interpret_expression(env)?;
-                println!("{}", value);
-                Ok(())
+                env.print(&value)
+                    .map_err(|error| RuntimeError::output(error, value.span()))
             }
-            Statement::Assignment(iden, expr) => {
-                let value = expr.interpret_expression(&env)?;
-                match env.set_var_value(iden.as_ref(), value) {
+            Statement::Assignment(var, expr) => {
+                let value = expr.interpret_expression(env)?;
+                let result = match var.binding {
+                    Some(binding) => env.set_bound_var_value(var.as_ref(), binding, value),
+                    None => env.set_var_value(var.as_ref(), value),
+                };
+                match result {
                     Ok(()) => Ok(()),
-                    Err(_) => Err(InterpreterError::identifier_not_defined(iden.clone())),
+                    Err(_) => Err(RuntimeError::identifier_not_defined(
+                        var.name.clone(),
+                        var.suggestion.as_deref(),
+                        env,
+                    )),
                 }
             }
             Statement::IfThen(if_then) => if_then.interpret_statement(env),
             Statement::IfThenElse(if_then_else) => if_then_else.interpret_statement(env),
             Statement::While(while_loop) => while_loop.interpret_statement(env),
             Statement::For(desugered_for) => desugered_for.interpret_statement(env),
+            Statement::RawFor(_) => {
+                unreachable!("for loops are desugared before the program is resolved")
+            }
         }
     }
 }
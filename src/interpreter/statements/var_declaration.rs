@@ -1,28 +1,25 @@
 use crate::{
     domain::grammar::VarDeclaration,
-    interpreter::{error::InterpreterError, expressions::InterpretedExpression},
-    Environment,
+    interpreter::{error::RuntimeError, expressions::InterpretedExpression},
+    Environment, Value,
 };
 
 use super::InterpretedStatement;
 
 impl InterpretedStatement for VarDeclaration {
-    fn interpret_statement(&self, state: &mut Environment) -> Result<(), InterpreterError> {
+    fn interpret_statement(&self, state: &mut Environment) -> Result<(), RuntimeError> {
         let iden = match self {
             VarDeclaration::Declare(i) => i,
             VarDeclaration::DeclareAndAssign(i, _) => i,
         };
-        state.declare_var(iden.as_ref());
-
-        match self {
-            VarDeclaration::DeclareAndAssign(_, e) => {
-                let val = e.interpret_expression(state)?;
-                state
-                    .set_var_value(iden.as_ref(), val)
-                    .expect("variable was just declared");
-            }
-            VarDeclaration::Declare(_) => {}
-        }
+        let binding = state.declare_bound_var(iden.as_ref());
+        let val = match self {
+            VarDeclaration::DeclareAndAssign(_, e) => e.interpret_expression(state)?,
+            VarDeclaration::Declare(_) => Value::uninitialized(),
+        };
+        state
+            .set_bound_var_value(iden.as_ref(), binding, val)
+            .expect("variable was just declared");
         Ok(())
     }
 }
@@ -1,17 +1,18 @@
 use crate::{
     domain::grammar::While,
-    interpreter::{error::InterpreterError, expressions::InterpretedExpression},
+    interpreter::{error::RuntimeError, expressions::InterpretedExpression},
     Environment,
 };
 
 use super::InterpretedStatement;
 
 impl InterpretedStatement for While {
-    fn interpret_statement(&self, env: &mut Environment) -> Result<(), InterpreterError> {
+    fn interpret_statement(&self, env: &mut Environment) -> Result<(), RuntimeError> {
         let mut cond_bool = self.get_cond_bool(env)?;
         let while_block = &self.block;
 
         while cond_bool {
+            env.budget().check_interrupt(|| self.condition.span())?;
             while_block.interpret_statement(env)?;
             cond_bool = self.get_cond_bool(env)?;
         }
@@ -20,10 +21,10 @@ impl InterpretedStatement for While {
 }
 
 impl While {
-    fn get_cond_bool(&self, env: &Environment) -> Result<bool, InterpreterError> {
+    fn get_cond_bool(&self, env: &Environment) -> Result<bool, RuntimeError> {
         let condition = &self.condition;
         let condition_val = condition.interpret_expression(env)?;
-        let cond_bool = InterpreterError::unwrap_bool(condition_val, "while condition")?;
+        let cond_bool = RuntimeError::unwrap_bool(condition_val, "while condition")?;
         Ok(cond_bool)
     }
 }
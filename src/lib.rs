@@ -1,55 +1,412 @@
+// `RuntimeError` carries the spans needed for error reporting; it is returned by value on
+// purpose, as it is only constructed on the (cold) error path.
+#![allow(clippy::result_large_err)]
+
+//! An interpreter for the Lox language.
+//!
+//! The stages of the interpreter can be used on their own: [scan] turns a source string into
+//! tokens, [parse] turns a source string or tokens into a [Program], and [Interpreter::run] runs
+//! the program. [Interpreter::interpret_src_str] does all of these at once.
+//!
+//! ```
+//! use rlox::{domain::grammar::Declaration, Interpreter};
+//!
+//! let program = rlox::parse("var a = 1; print a + 1;").unwrap();
+//! assert!(matches!(program[0], Declaration::Declaration(_)));
+//!
+//! let mut interpreter = Interpreter::default();
+//! interpreter.run(program).unwrap();
+//! ```
+//!
+//! The command line interface (the `rlox` binary with its interactive prompt) is behind the `cli`
+//! feature, which is enabled by default.
+
 use anyhow::{Context, Result};
-use domain::grammar::{Declaration, Expression, Program, Statement};
-use std::fmt::Write;
+use domain::{
+    grammar::{Declaration, Expression, Program, Statement},
+    scanning::Token,
+};
+use std::{fmt::Write, path::PathBuf};
 
+pub mod ast_json;
 pub mod domain;
 pub mod errors;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 
+#[cfg(feature = "cli")]
 mod arguments;
+mod cache;
+mod debugger;
+mod diagnostic;
+mod dump;
+mod formatter;
+mod inference;
 mod interpreter;
+mod lint;
+mod optimizer;
+mod output;
 mod parser;
+mod resolver;
 mod scanner;
+mod suggestion;
+mod vm;
 
+#[cfg(feature = "cli")]
 pub use arguments::*;
-use dialoguer::{theme::ColorfulTheme, Confirm, Input};
+pub use debugger::ConsoleDebugger;
+pub use diagnostic::{format_errors, render_error, use_color, Diagnostic, ErrorFormat, Severity};
+pub use dump::{dump, DumpFormat, DumpStage};
+pub use formatter::format_source;
 pub use interpreter::Environment;
-pub use interpreter::{Value, ValueType};
+pub use interpreter::{
+    Debugger, ErrorPolicy, Gc, GcStats, InterruptHandle, Limit, Limits, OnError, Pause, RunStats,
+    Scope, Value, ValueType,
+};
+pub use lint::{lint_source, Lint, LintConfig, Rule};
+pub use optimizer::OptLevel;
+pub use output::{Output, OutputBuffer};
+pub use scanner::Scanner;
+
+use crate::{cache::ProgramCache, diagnostic::format_diagnostics, scanner::scan_input};
+
+///
+/// Splits the source into tokens, ending with the EOF token. All scan errors are returned, not
+/// only the first one. Use [Scanner] to scan lazily.
+///
+pub fn scan(source: &str) -> Result<Vec<Token<'_>>, Vec<anyhow::Error>> {
+    scan_input(source)
+}
+
+///
+/// Parses a source string or its tokens into a program; see [ParseInput]. For loops are desugared
+/// into while loops, and the variables are not resolved yet.
+///
+pub fn parse(input: impl ParseInput) -> Result<Program, Vec<anyhow::Error>> {
+    input.into_program()
+}
+
+///
+/// The input of [parse]: a source string, which is scanned first, or the tokens from [scan] or
+/// [Scanner]
+///
+pub trait ParseInput {
+    fn into_program(self) -> Result<Program, Vec<anyhow::Error>>;
+}
+
+impl ParseInput for &str {
+    fn into_program(self) -> Result<Program, Vec<anyhow::Error>> {
+        parser::parse(&scan_input(self)?)
+    }
+}
+
+impl ParseInput for &[Token<'_>] {
+    fn into_program(self) -> Result<Program, Vec<anyhow::Error>> {
+        parser::parse(self)
+    }
+}
 
-use crate::{domain::location::CodeSpan, scanner::scan_input};
+impl ParseInput for Vec<Token<'_>> {
+    fn into_program(self) -> Result<Program, Vec<anyhow::Error>> {
+        parser::parse(&self)
+    }
+}
+
+///
+/// Returned when errors have already been reported in a machine-readable format, so that only the
+/// exit code of the process is left to set
+///
+#[derive(Debug)]
+pub struct ErrorsReported {
+    pub count: usize,
+}
+
+impl std::fmt::Display for ErrorsReported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{count} error(s) reported", count = self.count)
+    }
+}
 
-pub fn interpret_lox_file(path: &str) -> Result<()> {
+impl std::error::Error for ErrorsReported {}
+
+pub fn interpret_lox_file(
+    path: &str,
+    interpreter: Interpreter,
+    error_format: ErrorFormat,
+) -> Result<()> {
     let lox_str = std::fs::read_to_string(path)
         .with_context(|| format!("error reading in file at '{path}'"))?;
-    let mut interpreter = Interpreter::default();
-    match interpreter.interpret_src_str(&lox_str) {
+    let mut interpreter = for_report(interpreter, error_format);
+    let result = interpreter.interpret_src_str(&lox_str);
+    if let Some(stats) = interpreter.run_stats() {
+        let failed = match stats.declarations_failed {
+            0 => String::new(),
+            failed => format!(", {failed} of which failed"),
+        };
+        interpreter.diagnostic(&format!(
+            "ran {run} of {total} top-level declarations{failed}",
+            run = stats.declarations_run,
+            total = stats.declarations
+        ));
+    }
+    match result {
         Ok(Some(v)) => {
-            println!("file interpreted; evaluation result: {v}");
-            Ok(())
+            interpreter.diagnostic(&format!("file interpreted; evaluation result: {v}"));
+            report_errors(vec![], &lox_str, path, error_format)
         }
         Ok(None) => {
-            println!("file interpreted; no evaluation result");
+            interpreter.diagnostic("file interpreted; no evaluation result");
+            report_errors(vec![], &lox_str, path, error_format)
+        }
+        Err(errors) => report_errors(errors, &lox_str, path, error_format),
+    }
+}
+
+///
+/// Runs the file on the tree walker under a [ConsoleDebugger] which talks to the user via stdin and
+/// stdout; the runtime errors are reported as for [interpret_lox_file]
+///
+pub fn debug_lox_file(path: &str, interpreter: Interpreter) -> Result<()> {
+    let lox_str = std::fs::read_to_string(path)
+        .with_context(|| format!("error reading in file at '{path}'"))?;
+    let debugger = ConsoleDebugger::new(std::io::stdin().lock(), std::io::stdout(), &lox_str, path);
+    let mut interpreter = interpreter
+        .with_backend(Backend::TreeWalker)
+        .quiet()
+        .with_debugger(debugger);
+    let errors = interpreter
+        .interpret_src_str(&lox_str)
+        .err()
+        .unwrap_or_default();
+    report_errors(errors, &lox_str, path, ErrorFormat::Human)
+}
+
+///
+/// Reports the errors in the file without running it
+///
+pub fn check_lox_file(
+    path: &str,
+    interpreter: Interpreter,
+    error_format: ErrorFormat,
+) -> Result<()> {
+    let lox_str = std::fs::read_to_string(path)
+        .with_context(|| format!("error reading in file at '{path}'"))?;
+    let mut interpreter = for_report(interpreter, error_format);
+    let errors = interpreter.check(&lox_str).err().unwrap_or_default();
+    if errors.is_empty() {
+        interpreter.diagnostic(&format!("no errors found in '{path}'"));
+    }
+    report_errors(errors, &lox_str, path, error_format)
+}
+
+///
+/// Silences the free-text diagnostics of the interpreter if the errors are reported in a
+/// machine-readable format, so that stderr only carries the report
+///
+fn for_report(interpreter: Interpreter, error_format: ErrorFormat) -> Interpreter {
+    match error_format {
+        ErrorFormat::Human => interpreter,
+        ErrorFormat::Json | ErrorFormat::Sarif => interpreter.quiet(),
+    }
+}
+
+///
+/// Reports the errors on stderr in the given format. The machine-readable formats are written
+/// even if there are no errors, as an empty report is a meaningful result for tools.
+///
+fn report_errors(
+    errors: Vec<anyhow::Error>,
+    source: &str,
+    path: &str,
+    format: ErrorFormat,
+) -> Result<()> {
+    if format == ErrorFormat::Human {
+        if errors.is_empty() {
+            return Ok(());
+        }
+        let color = use_color(&std::io::stderr());
+        return Err(summarize_errors(errors, source, path, color)?);
+    }
+
+    eprint!("{}", format_errors(&errors, source, path, format));
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ErrorsReported {
+            count: errors.len(),
+        }
+        .into())
+    }
+}
+
+///
+/// Prints the given stage of the program in the file to stdout. The syntax tree as parsed is
+/// printed by [print_ast_of_lox_file].
+///
+pub fn dump_lox_file(path: &str, stage: DumpStage, format: DumpFormat) -> Result<()> {
+    if stage == DumpStage::Ast {
+        return print_ast_of_lox_file(path, format);
+    }
+    let lox_str = std::fs::read_to_string(path)
+        .with_context(|| format!("error reading in file at '{path}'"))?;
+    let color = use_color(&std::io::stderr());
+    match dump(&lox_str, stage, format) {
+        Ok(dump) => {
+            print!("{dump}");
             Ok(())
         }
-        Err(errors) => Err(summarize_errors(errors)?),
+        Err(errors) => Err(summarize_errors(errors, &lox_str, path, color)?),
+    }
+}
+
+///
+/// Prints the syntax tree of the program in the file, with for loops as written, to stdout. The
+/// JSON format is the one of [ast_json].
+///
+pub fn print_ast_of_lox_file(path: &str, format: DumpFormat) -> Result<()> {
+    let lox_str = std::fs::read_to_string(path)
+        .with_context(|| format!("error reading in file at '{path}'"))?;
+    let color = use_color(&std::io::stderr());
+    let program =
+        match scan_input(&lox_str).and_then(|tokens| parser::parse_without_desugaring(&tokens)) {
+            Ok(program) => program,
+            Err(errors) => return Err(summarize_errors(errors, &lox_str, path, color)?),
+        };
+    match format {
+        DumpFormat::Text => print!("{program}"),
+        DumpFormat::Json => println!("{}", ast_json::to_json(&program)?),
+    }
+    Ok(())
+}
+
+///
+/// Formats the files in place. With `check`, the files are left as they are and the paths of those
+/// which would change are printed instead. Files with syntax errors are reported and skipped; the
+/// others are still formatted.
+///
+pub fn format_lox_files(paths: &[String], check: bool) -> Result<()> {
+    let color = use_color(&std::io::stderr());
+    let mut failed = 0;
+    for path in paths {
+        let lox_str = std::fs::read_to_string(path)
+            .with_context(|| format!("error reading in file at '{path}'"))?;
+        let formatted = match format_source(&lox_str) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                eprintln!("{}", summarize_errors(errors, &lox_str, path, color)?);
+                failed += 1;
+                continue;
+            }
+        };
+        if formatted == lox_str {
+            continue;
+        }
+        if check {
+            println!("{path} is not formatted");
+            failed += 1;
+        } else {
+            std::fs::write(path, formatted)
+                .with_context(|| format!("error writing the formatted file at '{path}'"))?;
+        }
     }
+    if failed > 0 {
+        return Err(ErrorsReported { count: failed }.into());
+    }
+    Ok(())
 }
 
+///
+/// Lints the files without running them and reports the lints on stdout in the given format. The
+/// errors of files which cannot be linted are reported on stderr; both fail the command.
+///
+pub fn lint_lox_files(paths: &[String], config: &LintConfig, format: ErrorFormat) -> Result<()> {
+    let color = use_color(&std::io::stdout());
+    let mut found = 0;
+    for path in paths {
+        let lox_str = std::fs::read_to_string(path)
+            .with_context(|| format!("error reading in file at '{path}'"))?;
+        let lints = match lint_source(&lox_str, config) {
+            Ok(lints) => lints,
+            Err(errors) => {
+                found += errors.len();
+                if format == ErrorFormat::Human {
+                    let color = use_color(&std::io::stderr());
+                    eprintln!("{}", summarize_errors(errors, &lox_str, path, color)?);
+                } else {
+                    eprint!("{}", format_errors(&errors, &lox_str, path, format));
+                }
+                continue;
+            }
+        };
+        found += lints.len();
+        let diagnostics: Vec<_> = lints.iter().map(Lint::diagnostic).collect();
+        if format == ErrorFormat::Human {
+            for diagnostic in diagnostics {
+                println!("{}", diagnostic.render(&lox_str, path, color));
+            }
+        } else {
+            print!(
+                "{}",
+                format_diagnostics(&diagnostics, &lox_str, path, format)
+            );
+        }
+    }
+    if found > 0 {
+        return Err(ErrorsReported { count: found }.into());
+    }
+    Ok(())
+}
+
+///
+/// Prints the description of the error with the given code
+///
+pub fn explain_error_code(code: &str) -> Result<()> {
+    let Some(explanation) = errors::explain(code) else {
+        let codes: Vec<_> = errors::codes().collect();
+        anyhow::bail!(
+            "unknown error code '{code}'; the known codes are: {codes}",
+            codes = codes.join(", ")
+        );
+    };
+    println!("{code}: {explanation}", code = code.to_uppercase());
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
 const COMMAND_EXIT: &str = "exit";
+/// The file name reported in errors for the code entered in the prompt
+#[cfg(feature = "cli")]
+const PROMPT_FILE_NAME: &str = "<prompt>";
+
+#[cfg(feature = "cli")]
+pub fn run_prompt(mut interpreter: Interpreter) -> Result<()> {
+    use dialoguer::{theme::ColorfulTheme, Confirm, Input};
 
-pub fn run_prompt() -> Result<()> {
     let prompt_theme = ColorfulTheme::default();
     let prompt =
         format!("Enter the next line of lox code. Type '{COMMAND_EXIT}' to terminate the prompt.");
-    let mut interpreter = Interpreter::default();
 
-    let mut last_value = Value::new(ValueType::Nil, CodeSpan::default());
+    // Ctrl-C only aborts the current input, the variables defined so far are kept
+    let interrupt_handle = interpreter.interrupt_handle();
+    ctrlc::set_handler(move || interrupt_handle.interrupt())
+        .context("failed installing the Ctrl-C handler")?;
+
+    let mut last_value = Value::new(ValueType::Nil, domain::location::CodeSpan::default());
     loop {
-        let input: String = Input::with_theme(&prompt_theme)
+        let input: String = match Input::with_theme(&prompt_theme)
             .with_prompt(&prompt)
             .interact_text()
-            .context("failed reading in user prompt input")?;
+        {
+            Ok(input) => input,
+            // Ctrl-C while no input is running discards the line entered so far
+            Err(dialoguer::Error::IO(e)) if e.kind() == std::io::ErrorKind::Interrupted => {
+                println!();
+                continue;
+            }
+            Err(e) => return Err(e).context("failed reading in user prompt input"),
+        };
 
-        if &input == COMMAND_EXIT
+        if input == COMMAND_EXIT
             && Confirm::with_theme(&prompt_theme)
                 .with_prompt("Do you want to terminate the interpreter?")
                 .interact()?
@@ -66,7 +423,8 @@ pub fn run_prompt() -> Result<()> {
                 println!("no evaluation result");
             }
             Err(errors) => {
-                let err_summary = summarize_errors(errors)?;
+                let color = use_color(&std::io::stdout());
+                let err_summary = summarize_errors(errors, &input, PROMPT_FILE_NAME, color)?;
                 println!("{err_summary}");
                 // return Err(err_summary);
             }
@@ -77,12 +435,155 @@ pub fn run_prompt() -> Result<()> {
     Ok(())
 }
 
-#[derive(Default)]
 pub struct Interpreter {
     environment: Environment,
+    backend: Backend,
+    disassemble: bool,
+    opt_level: OptLevel,
+    error_policy: ErrorPolicy,
+    /// The statistics of the last run
+    run_stats: Option<RunStats>,
+    cache: Option<ProgramCache>,
+    /// Receives everything the interpreter reports about its work, as opposed to the output of
+    /// the program
+    diagnostics: Output,
+}
+
+impl Default for Interpreter {
+    ///
+    /// An interpreter which prints to stdout and discards its diagnostic messages
+    ///
+    fn default() -> Self {
+        Self {
+            environment: Environment::default(),
+            backend: Backend::default(),
+            disassemble: false,
+            opt_level: OptLevel::default(),
+            error_policy: ErrorPolicy::default(),
+            run_stats: None,
+            cache: None,
+            diagnostics: Output::null(),
+        }
+    }
+}
+
+///
+/// The way in which programs are executed
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Backend {
+    /// Walks the syntax tree directly
+    #[default]
+    TreeWalker,
+    /// Compiles the program to byte code which is run on a stack machine
+    Vm,
 }
 
 impl Interpreter {
+    pub fn with_backend(self, backend: Backend) -> Self {
+        Self { backend, ..self }
+    }
+
+    ///
+    /// With [OptLevel::O1], constant expressions are folded and dead branches removed before the
+    /// program is run
+    ///
+    pub fn with_opt_level(self, opt_level: OptLevel) -> Self {
+        Self { opt_level, ..self }
+    }
+
+    ///
+    /// Caches the parsed programs in the given directory, so that scanning and parsing is skipped
+    /// when the same source is interpreted again
+    ///
+    pub fn with_cache_dir(self, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cache: Some(ProgramCache::new(dir)),
+            ..self
+        }
+    }
+
+    ///
+    /// Limits the resources each call of [Interpreter::interpret_src_str] may use. A run which
+    /// exceeds a limit stops with an error pointing at the code where it happened; the state of the
+    /// interpreter stays usable.
+    ///
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.environment.set_limits(limits);
+        self
+    }
+
+    ///
+    /// Decides whether a run goes on after a top-level declaration failed. By default, all
+    /// declarations are run, as suits the prompt; the command line stops files at the first error
+    /// unless `--on-error` or `--max-errors` is given.
+    ///
+    pub fn with_error_policy(self, error_policy: ErrorPolicy) -> Self {
+        Self {
+            error_policy,
+            ..self
+        }
+    }
+
+    ///
+    /// Sets the destination of the output of `print` statements
+    ///
+    pub fn with_output(mut self, output: impl Into<Output>) -> Self {
+        self.environment.set_output(output.into());
+        self
+    }
+
+    ///
+    /// Sets the destination of the diagnostic messages (e.g., the interpreted source, the syntax
+    /// tree or the byte code), which are discarded by default. The command line interface sends
+    /// them to stderr, apart from the output of the program.
+    ///
+    pub fn with_diagnostics(self, diagnostics: impl Into<Output>) -> Self {
+        Self {
+            diagnostics: diagnostics.into(),
+            ..self
+        }
+    }
+
+    ///
+    /// Discards all diagnostic messages, so that only the output of the program is emitted
+    ///
+    pub fn quiet(self) -> Self {
+        self.with_diagnostics(Output::null())
+    }
+
+    ///
+    /// When set, the garbage collector runs at the first safe point after every allocation, i.e.,
+    /// before the next declaration on the tree walker and before the next instruction on the VM.
+    /// As the heap shares its objects with the values, a collection only drops the references of
+    /// the heap; an object is freed once no value refers to it anymore ([GcStats] counts both).
+    ///
+    pub fn with_gc_stress(mut self, stress: bool) -> Self {
+        self.environment.set_gc_stress(stress);
+        self
+    }
+
+    ///
+    /// Hands each run to the debugger before every declaration, except for blocks, whose
+    /// declarations are paused at one by one; a `for` loop is paused at before its init and again
+    /// at its condition. Only the tree walker is followed; the VM runs programs without pausing.
+    ///
+    pub fn with_debugger(mut self, debugger: impl Debugger + 'static) -> Self {
+        self.environment.set_debugger(Box::new(debugger));
+        self
+    }
+
+    ///
+    /// When set, the byte code of every program is printed before it is run on the VM
+    ///
+    pub fn with_disassembly(self, disassemble: bool) -> Self {
+        Self {
+            disassemble,
+            ..self
+        }
+    }
+
     ///
     /// Interprets the given source string while mutating the current state of the interpreter
     ///
@@ -90,25 +591,107 @@ impl Interpreter {
         &mut self,
         source_str: &str,
     ) -> Result<Option<Value>, Vec<anyhow::Error>> {
-        println!("interpreting the following: '{source_str}'");
-        let tokens = scan_input(source_str)?;
-        let program = parser::parse(tokens)?;
+        self.diagnostic(&format!("interpreting the following: '{source_str}'"));
+        self.run_stats = None;
+        let program = self.parse(source_str)?;
+        self.run(program)
+    }
+
+    ///
+    /// Runs a program from [parse] while mutating the current state of the interpreter. The
+    /// variables of the program are resolved against the globals defined so far. For loops kept as
+    /// written (e.g., in a program deserialized from `rlox ast --format json`) are desugared
+    /// first.
+    ///
+    pub fn run(&mut self, mut program: Program) -> Result<Option<Value>, Vec<anyhow::Error>> {
+        self.run_stats = None;
+        parser::desugar(&mut program);
+        resolver::resolve(&mut program, self.environment.global_names())?;
+        if self.opt_level == OptLevel::O1 {
+            optimizer::optimize(&mut program, self.environment.budget().limits());
+        }
 
         if let Some(expr) = single_expression(&program) {
-            print_expr_ast(expr);
+            self.diagnostic(&format!("here is the AST we got: \n{expr}"));
         }
 
-        match self.interpret(program) {
-            Ok(value) => Ok(value),
-            Err(errors) => {
-                let mut interpreter_errors = vec![];
-                for error in errors {
-                    let e = anyhow::anyhow!(error.msg(source_str));
-                    interpreter_errors.push(e);
+        self.environment.budget().reset();
+        let (run_stats, errors) = match self.backend {
+            Backend::TreeWalker => self.interpret(program),
+            Backend::Vm => {
+                let chunk = vm::compile(&program)?;
+                if self.disassemble {
+                    self.diagnostic(&chunk.to_string());
                 }
-                Err(interpreter_errors)
+                vm::run(&chunk, &mut self.environment, self.error_policy)
             }
+        };
+        self.run_stats = Some(run_stats);
+
+        if errors.is_empty() {
+            Ok(self.environment.get_tmp_value().cloned())
+        } else {
+            Err(errors.into_iter().map(anyhow::Error::new).collect())
+        }
+    }
+
+    ///
+    /// How many of the top-level declarations were run by the last call of
+    /// [Interpreter::interpret_src_str] or [Interpreter::run]; `None` if the program was not run
+    /// because of errors found before, e.g., a syntax error
+    ///
+    pub fn run_stats(&self) -> Option<RunStats> {
+        self.run_stats
+    }
+}
+
+impl Interpreter {
+    ///
+    /// Reports the errors in the source which are found without running it, i.e., by the scanner,
+    /// the parser, the resolver and, for the VM backend, the compiler. The state of the
+    /// interpreter is not changed.
+    ///
+    pub fn check(&mut self, source_str: &str) -> Result<(), Vec<anyhow::Error>> {
+        let mut program = self.parse(source_str)?;
+        resolver::resolve(&mut program, self.environment.global_names())?;
+        if self.backend == Backend::Vm {
+            vm::compile(&program)?;
         }
+        Ok(())
+    }
+}
+
+impl Interpreter {
+    ///
+    /// Parses the source, going through the cache if one is configured. Invalid cache entries are
+    /// reported and replaced; failing to write the cache does not fail the interpretation. Code
+    /// nested deeper than the `max_depth` limit is rejected while parsing.
+    ///
+    fn parse(&mut self, source_str: &str) -> Result<Program, Vec<anyhow::Error>> {
+        let max_depth = self.environment.budget().limits().max_depth;
+        let parse = |source_str| parser::parse_with_max_depth(&scan_input(source_str)?, max_depth);
+        let Some(cache) = self.cache.clone() else {
+            return parse(source_str);
+        };
+        // a program is only valid for the depth it was parsed with
+        let max_depth = max_depth.unwrap_or(parser::MAX_DEPTH);
+        match cache.load(source_str, max_depth) {
+            Ok(Some(program)) => return Ok(program),
+            Ok(None) => (),
+            Err(e) => self.diagnostic(&format!("warning: {e:#}; regenerating it")),
+        }
+        let program = parse(source_str)?;
+        if let Err(e) = cache.store(source_str, max_depth, &program) {
+            self.diagnostic(&format!("warning: {e:#}"));
+        }
+        Ok(program)
+    }
+
+    ///
+    /// Reports a diagnostic message; failing to do so does not affect the interpretation
+    ///
+    fn diagnostic(&mut self, msg: &str) {
+        let _ = self.diagnostics.write_line(msg);
     }
 }
 
@@ -118,20 +701,24 @@ fn single_expression(program: &Program) -> Option<Expression> {
     }
 
     match &program[0] {
-        Declaration::Statement(Statement::Expression(e)) => Some(e.clone()),
+        Declaration::Statement(Statement::Expression(e), _) => Some(e.clone()),
         _ => None,
     }
 }
 
-fn print_expr_ast(expr: Expression) {
-    println!("here is the AST we got: ");
-    println!("{}", expr);
-}
-
-fn summarize_errors(errors: Vec<anyhow::Error>) -> Result<anyhow::Error> {
+///
+/// Renders the errors with the snippets of the source they refer to
+///
+fn summarize_errors(
+    errors: Vec<anyhow::Error>,
+    source: &str,
+    file_name: &str,
+    color: bool,
+) -> Result<anyhow::Error> {
     let mut msg = String::new();
     for e in errors {
-        writeln!(msg, "{e}").context("error writing summary error")?;
+        let rendered = render_error(&e, source, file_name, color);
+        writeln!(msg, "{rendered}").context("error writing summary error")?;
     }
     Ok(anyhow::anyhow!("Input error:\n\n{msg}"))
 }
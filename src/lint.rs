@@ -0,0 +1,539 @@
+//! Static checks for code which is valid, but likely wrong. The linter only scans, parses and
+//! resolves the source; it never runs it.
+//!
+//! Rules can be allowed for all files via [LintConfig], or in a file by a comment naming them,
+//! e.g., `// lint: allow(unused-variable, empty-block)`.
+
+use std::collections::HashSet;
+
+use crate::{
+    diagnostic::Severity,
+    domain::{
+        grammar::{
+            visit::{walk_if_then, walk_while, Visitor},
+            Declaration, Expression, IfThen, VarDeclaration, Variable, While,
+        },
+        location::CodeSpan,
+        scanning::{LosslessToken, TokenType, TriviaKind},
+    },
+    inference::LoxType,
+    parser,
+    resolver::{self, ReferenceKind, Symbols, TypeTracker},
+    Diagnostic, Scanner,
+};
+
+/// The start of a comment which configures the linter for the file
+const DIRECTIVE: &str = "lint:";
+
+///
+/// A check of the linter
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Rule {
+    /// A variable which is declared but never read
+    UnusedVariable,
+    /// A variable in a block with the name of a variable of an enclosing block
+    Shadowing,
+    /// An `if` or `while` condition which does not depend on any variable
+    ConstantCondition,
+    /// A condition which cannot be a Boolean, the only type conditions accept
+    NonBooleanCondition,
+    /// A loop whose condition reads variables none of which is assigned in its body
+    UnmodifiedLoopCondition,
+    /// A block without declarations or comments
+    EmptyBlock,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 6] = [
+        Rule::UnusedVariable,
+        Rule::Shadowing,
+        Rule::ConstantCondition,
+        Rule::NonBooleanCondition,
+        Rule::UnmodifiedLoopCondition,
+        Rule::EmptyBlock,
+    ];
+
+    ///
+    /// The name of the rule in reports and in the comments which allow it
+    ///
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::UnusedVariable => "unused-variable",
+            Rule::Shadowing => "shadowing",
+            Rule::ConstantCondition => "constant-condition",
+            Rule::NonBooleanCondition => "non-boolean-condition",
+            Rule::UnmodifiedLoopCondition => "unmodified-loop-condition",
+            Rule::EmptyBlock => "empty-block",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+///
+/// The rules which are not checked; all rules are checked by default
+///
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    allowed: HashSet<Rule>,
+}
+
+impl LintConfig {
+    pub fn allow(mut self, rule: Rule) -> Self {
+        self.allowed.insert(rule);
+        self
+    }
+
+    pub fn is_allowed(&self, rule: Rule) -> bool {
+        self.allowed.contains(&rule)
+    }
+}
+
+///
+/// A finding of the linter
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    rule: Rule,
+    message: String,
+    span: CodeSpan,
+    /// Related code, e.g., the declaration a variable shadows
+    related: Option<(CodeSpan, String)>,
+}
+
+impl Lint {
+    fn new(rule: Rule, message: impl Into<String>, span: CodeSpan) -> Self {
+        Self {
+            rule,
+            message: message.into(),
+            span,
+            related: None,
+        }
+    }
+
+    fn with_related(self, span: CodeSpan, text: impl Into<String>) -> Self {
+        Self {
+            related: Some((span, text.into())),
+            ..self
+        }
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> CodeSpan {
+        self.span
+    }
+
+    ///
+    /// The lint as a warning, with the name of its rule as the code
+    ///
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::new(&self.message)
+            .with_code(self.rule.name())
+            .with_severity(Severity::Warning)
+            .with_primary(self.span, "");
+        let diagnostic = match &self.related {
+            Some((span, text)) => diagnostic.with_secondary(*span, text),
+            None => diagnostic,
+        };
+        diagnostic.with_help(format!(
+            "add `// {DIRECTIVE} allow({rule})` to the file to allow this",
+            rule = self.rule
+        ))
+    }
+}
+
+///
+/// Checks the source with the rules which are not allowed, ordered by their position. Sources with
+/// errors, including comments allowing unknown rules, are not checked; their errors are returned.
+///
+pub fn lint_source(source: &str, config: &LintConfig) -> Result<Vec<Lint>, Vec<anyhow::Error>> {
+    let mut tokens = vec![];
+    let mut errors = vec![];
+    for result in Scanner::new(source).lossless() {
+        match result {
+            Ok(token) => tokens.push(token),
+            Err(e) => errors.push(e.into()),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let allowed_in_file = allowed_rules(&tokens)?;
+
+    let plain: Vec<_> = tokens.iter().map(|t| t.token.clone()).collect();
+    let mut program = parser::parse(&plain)?;
+    let (symbols, errors) = resolver::resolve_symbols(&mut program);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut checker = Checker::new(&symbols);
+    checker.visit_program(&program);
+    let mut lints = checker.finish();
+    lints.extend(empty_blocks(&tokens));
+    lints.retain(|lint| !config.is_allowed(lint.rule) && !allowed_in_file.contains(&lint.rule));
+    lints.sort_by_key(|lint| lint.span.start.pos);
+    Ok(lints)
+}
+
+///
+/// The rules allowed by `// lint: allow(...)` comments
+///
+fn allowed_rules(tokens: &[LosslessToken]) -> Result<HashSet<Rule>, Vec<anyhow::Error>> {
+    let mut allowed = HashSet::new();
+    let mut errors = vec![];
+    let comments = tokens
+        .iter()
+        .flat_map(|token| &token.leading_trivia)
+        .filter(|trivia| trivia.kind == TriviaKind::Comment);
+    for comment in comments {
+        let text = comment.text.trim_start_matches('/').trim();
+        let Some(directive) = text.strip_prefix(DIRECTIVE).map(str::trim) else {
+            continue;
+        };
+        let Some(names) = directive
+            .strip_prefix("allow(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        else {
+            errors.push(anyhow::anyhow!(
+                "invalid lint comment '{text}', expected '{DIRECTIVE} allow(<rule>, ...)'"
+            ));
+            continue;
+        };
+        for name in names.split(',').map(str::trim) {
+            match Rule::from_name(name) {
+                Some(rule) => {
+                    allowed.insert(rule);
+                }
+                None => {
+                    let rules: Vec<_> = Rule::ALL.iter().map(Rule::name).collect();
+                    errors.push(anyhow::anyhow!(
+                        "unknown lint rule '{name}'; the rules are: {rules}",
+                        rules = rules.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(allowed)
+    } else {
+        Err(errors)
+    }
+}
+
+///
+/// Finds the blocks without declarations or comments; the init and the update of a for loop may
+/// be empty
+///
+fn empty_blocks(tokens: &[LosslessToken]) -> Vec<Lint> {
+    let mut lints = vec![];
+    // for each open brace, whether it belongs to the header of a for loop
+    let mut braces = vec![];
+    // the number of brace pairs of the header of a for loop which are still to come
+    let mut for_header_braces = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.token.t_type {
+            TokenType::FOR => for_header_braces = 3,
+            TokenType::BraceLeft if for_header_braces > 0 => braces.push(true),
+            TokenType::BraceLeft => {
+                braces.push(false);
+                let Some(next) = tokens.get(i + 1) else {
+                    continue;
+                };
+                let commented = next
+                    .leading_trivia
+                    .iter()
+                    .any(|trivia| trivia.kind == TriviaKind::Comment);
+                if next.token.t_type == TokenType::BraceRight && !commented {
+                    let span = CodeSpan {
+                        start: token.token.location(),
+                        end: next.token.location().shifted(1),
+                    };
+                    lints.push(Lint::new(Rule::EmptyBlock, "empty block", span));
+                }
+            }
+            TokenType::BraceRight => {
+                for_header_braces -= usize::from(braces.pop() == Some(true));
+            }
+            _ => (),
+        }
+    }
+    lints
+}
+
+///
+/// Applies the rules which need the syntax tree, on the declarations and the references the
+/// resolver found
+///
+struct Checker<'s> {
+    symbols: &'s Symbols,
+    types: TypeTracker<'s>,
+    lints: Vec<Lint>,
+}
+
+impl<'s> Checker<'s> {
+    fn new(symbols: &'s Symbols) -> Self {
+        Self {
+            symbols,
+            types: TypeTracker::new(symbols),
+            lints: vec![],
+        }
+    }
+
+    fn finish(self) -> Vec<Lint> {
+        let symbols = self.symbols;
+        let unused = symbols
+            .declarations
+            .iter()
+            .enumerate()
+            .filter(|(id, declared)| {
+                !declared.name.starts_with('_')
+                    && symbols
+                        .references_of(*id)
+                        .all(|reference| reference.kind != ReferenceKind::Read)
+            })
+            .map(|(_, declared)| {
+                Lint::new(
+                    Rule::UnusedVariable,
+                    format!("variable `{}` is never read", declared.name),
+                    declared.span,
+                )
+            });
+        let shadowing = symbols.declarations.iter().filter_map(|declared| {
+            let shadowed = &symbols.declarations[declared.shadows?];
+            let lint = Lint::new(
+                Rule::Shadowing,
+                format!(
+                    "variable `{}` shadows a variable of an enclosing block",
+                    declared.name
+                ),
+                declared.span,
+            )
+            .with_related(shadowed.span, "shadowed declaration");
+            Some(lint)
+        });
+        self.lints
+            .into_iter()
+            .chain(unused)
+            .chain(shadowing)
+            .collect()
+    }
+
+    ///
+    /// Conditions have to be Booleans; the types known so far are the ones the condition may have
+    /// when it is evaluated first
+    ///
+    fn check_condition(&mut self, condition: &Expression, statement: &str) {
+        if self.types.infer(condition).excludes(LoxType::Boolean) {
+            self.lints.push(Lint::new(
+                Rule::NonBooleanCondition,
+                format!("the condition of this `{statement}` is never a Boolean"),
+                condition.span(),
+            ));
+        } else if variables_read(condition).is_empty() {
+            self.lints.push(Lint::new(
+                Rule::ConstantCondition,
+                format!("the condition of this `{statement}` is constant"),
+                condition.span(),
+            ));
+        }
+    }
+
+    ///
+    /// Loops run forever or not at all if the body assigns none of the variables the condition
+    /// reads
+    ///
+    fn check_loop(&mut self, while_loop: &While) {
+        let read = self.declarations(variables_read(&while_loop.condition));
+        let assigned = self.declarations(variables_assigned(&while_loop.block));
+        if read.is_empty() || !read.is_disjoint(&assigned) {
+            return;
+        }
+        let mut names: Vec<_> = read
+            .iter()
+            .map(|id| format!("`{}`", self.symbols.declarations[*id].name))
+            .collect();
+        names.sort_unstable();
+        self.lints.push(Lint::new(
+            Rule::UnmodifiedLoopCondition,
+            format!(
+                "the loop condition reads {names}, which the loop body never assigns",
+                names = names.join(", ")
+            ),
+            while_loop.condition.span(),
+        ));
+    }
+
+    ///
+    /// The declarations the names refer to
+    ///
+    fn declarations(&self, names: Vec<CodeSpan>) -> HashSet<usize> {
+        names
+            .into_iter()
+            .filter_map(|name| self.symbols.declaration_of(name))
+            .collect()
+    }
+}
+
+impl<'ast> Visitor<'ast> for Checker<'_> {
+    fn visit_var_declaration(&mut self, var_decl: &'ast VarDeclaration) {
+        self.types.declare(var_decl);
+    }
+
+    fn visit_assignment(&mut self, var: &'ast Variable, value: &'ast Expression) {
+        self.types.assign(var, value);
+    }
+
+    fn visit_if_then(&mut self, if_then: &'ast IfThen) {
+        self.check_condition(&if_then.condition, "if");
+        walk_if_then(self, if_then);
+    }
+
+    fn visit_while(&mut self, while_loop: &'ast While) {
+        self.check_condition(&while_loop.condition, "while");
+        self.check_loop(while_loop);
+        walk_while(self, while_loop);
+    }
+}
+
+///
+/// The spans of the names of the variables the expression reads
+///
+fn variables_read(expr: &Expression) -> Vec<CodeSpan> {
+    #[derive(Default)]
+    struct Reads(Vec<CodeSpan>);
+
+    impl<'ast> Visitor<'ast> for Reads {
+        fn visit_variable(&mut self, var: &'ast Variable) {
+            self.0.push(var.name.span);
+        }
+    }
+
+    let mut reads = Reads::default();
+    reads.visit_expression(expr);
+    reads.0
+}
+
+///
+/// The spans of the names of the variables assigned anywhere in the declaration
+///
+fn variables_assigned(decl: &Declaration) -> Vec<CodeSpan> {
+    #[derive(Default)]
+    struct Assignments(Vec<CodeSpan>);
+
+    impl<'ast> Visitor<'ast> for Assignments {
+        fn visit_assignment(&mut self, var: &'ast Variable, _value: &'ast Expression) {
+            self.0.push(var.name.span);
+        }
+    }
+
+    let mut assignments = Assignments::default();
+    assignments.visit_declaration(decl);
+    assignments.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::{lint_source, LintConfig, Rule};
+
+    fn rules_of(source: &str) -> Vec<Rule> {
+        lint_source(source, &LintConfig::default())
+            .expect("linting failed")
+            .iter()
+            .map(|lint| lint.rule())
+            .collect()
+    }
+
+    #[test]
+    fn unused_variables_are_found() {
+        assert_eq!(
+            vec![Rule::UnusedVariable],
+            rules_of("var a = 1; var b = 2; b = 3; print a;")
+        );
+        assert!(rules_of("var _ignored = 1;").is_empty());
+    }
+
+    #[test]
+    fn shadowing_is_found() {
+        let lints = lint_source(
+            "var a = 1; { var a = 2; print a; } print a;",
+            &LintConfig::default(),
+        )
+        .expect("linting failed");
+        assert_eq!(1, lints.len());
+        assert_eq!(Rule::Shadowing, lints[0].rule());
+        assert_eq!(17, lints[0].span().start.pos);
+        // declaring a global variable again does not shadow it
+        assert!(rules_of("var a = 1; print a; var a = 2; print a;").is_empty());
+    }
+
+    #[test]
+    fn constant_and_non_boolean_conditions_are_found() {
+        assert_eq!(
+            vec![Rule::ConstantCondition],
+            rules_of("if (1 < 2) { print 1; }")
+        );
+        assert_eq!(
+            vec![Rule::NonBooleanCondition],
+            rules_of("if (1) { print 1; }")
+        );
+        let source = "var s = \"x\"; if (s) { print s; } var b = s == \"x\"; if (b) { print b; }";
+        assert_eq!(vec![Rule::NonBooleanCondition], rules_of(source));
+    }
+
+    #[test]
+    fn unmodified_loop_conditions_are_found() {
+        let source = "var n = 3; while (n > 0) { print n; }";
+        assert_eq!(vec![Rule::UnmodifiedLoopCondition], rules_of(source));
+        assert!(rules_of("var n = 3; while (n > 0) { n = n - 1; }").is_empty());
+        let source = "for {var i = 0;} {i < 3} {i = i + 1;} { print i; }";
+        assert!(rules_of(source).is_empty());
+        // the body assigns a variable of its own, which shadows the one of the condition
+        let source = "var n = 3; while (n > 0) { var n = 1; n = n - 1; print n; }";
+        assert_eq!(
+            vec![Rule::UnmodifiedLoopCondition, Rule::Shadowing],
+            rules_of(source)
+        );
+    }
+
+    #[test]
+    fn empty_blocks_are_found() {
+        assert_eq!(vec![Rule::EmptyBlock], rules_of("{}"));
+        assert!(rules_of("{ // nothing yet\n}").is_empty());
+        let source = "for {} {false} {} { print 1; }";
+        assert_eq!(vec![Rule::ConstantCondition], rules_of(source));
+    }
+
+    #[test]
+    fn rules_can_be_allowed() {
+        let config = LintConfig::default().allow(Rule::EmptyBlock);
+        assert!(lint_source("{}", &config).unwrap().is_empty());
+        let source = "// lint: allow(empty-block, unused-variable)\nvar a; {}";
+        assert!(rules_of(source).is_empty());
+    }
+
+    #[test]
+    fn unknown_rules_are_errors() {
+        assert!(lint_source("// lint: allow(everything)\n", &LintConfig::default()).is_err());
+        assert!(lint_source("// lint: deny(shadowing)\n", &LintConfig::default()).is_err());
+        assert!(lint_source("var a = ;", &LintConfig::default()).is_err());
+    }
+}
@@ -0,0 +1,442 @@
+//! A language server for Lox, speaking the Language Server Protocol. It publishes the errors the
+//! scanner, the parser, the resolver and the compiler find whenever a document changes, and
+//! answers requests for hovers, definitions, references, renames, completions and semantic
+//! tokens.
+//!
+//! [serve] runs the server on any connection, e.g., an in-memory one to script a client in tests;
+//! [run_stdio] runs it on stdin and stdout, as editors start it.
+
+mod analysis;
+mod position;
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as LspNotification, PublishDiagnostics,
+    },
+    request::{
+        Completion, GotoDefinition, HoverRequest, References, Rename, Request as LspRequest,
+        SemanticTokensFullRequest,
+    },
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    DiagnosticSeverity, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind, NumberOrString,
+    OneOf, PublishDiagnosticsParams, ReferenceParams, RenameParams, SemanticToken,
+    SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensFullOptions,
+    SemanticTokensLegend, SemanticTokensOptions, SemanticTokensParams, SemanticTokensResult,
+    SemanticTokensServerCapabilities, ServerCapabilities, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit,
+};
+
+use crate::{
+    domain::scanning::{Token, TokenType},
+    Scanner, Severity,
+};
+use analysis::Analysis;
+use position::LineIndex;
+
+///
+/// The token types of the semantic tokens, in the order of [analysis::SemanticKind]
+///
+const TOKEN_TYPES: [SemanticTokenType; 6] = [
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::STRING,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::COMMENT,
+];
+
+///
+/// Runs the server on stdin and stdout until the client shuts it down
+///
+pub fn run_stdio() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(connection)?;
+    io_threads.join()?;
+    Ok(())
+}
+
+///
+/// Runs the server on the connection: answers the initialization of the client, then handles its
+/// messages until it shuts the server down
+///
+pub fn serve(connection: Connection) -> Result<()> {
+    connection.initialize(serde_json::to_value(capabilities())?)?;
+    let mut server = Server::default();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                connection
+                    .sender
+                    .send(Message::Response(server.request(request)))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(diagnostics) = server.notification(notification) {
+                    connection.sender.send(Message::Notification(diagnostics))?;
+                }
+            }
+            Message::Response(_) => (),
+        }
+    }
+    Ok(())
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        rename_provider: Some(OneOf::Left(true)),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: SemanticTokensLegend {
+                    token_types: TOKEN_TYPES.to_vec(),
+                    token_modifiers: vec![SemanticTokenModifier::DECLARATION],
+                },
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    }
+}
+
+///
+/// An open document, analysed whenever it changes
+///
+struct Document {
+    text: String,
+    version: i32,
+    lines: LineIndex,
+    analysis: Analysis,
+}
+
+impl Document {
+    fn new(text: String, version: i32) -> Self {
+        Self {
+            lines: LineIndex::new(&text),
+            analysis: Analysis::of(&text),
+            text,
+            version,
+        }
+    }
+
+    fn offset(&self, position: lsp_types::Position) -> usize {
+        self.lines.offset(&self.text, position)
+    }
+
+    fn range(&self, span: crate::domain::location::CodeSpan) -> lsp_types::Range {
+        self.lines.range(&self.text, span)
+    }
+
+    fn diagnostics(&self) -> Vec<lsp_types::Diagnostic> {
+        self.analysis
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let range = diagnostic
+                    .span()
+                    .map_or_else(Default::default, |span| self.range(span));
+                let message = match diagnostic.help() {
+                    Some(help) => format!("{}\nhelp: {help}", diagnostic.message()),
+                    None => diagnostic.message().to_owned(),
+                };
+                lsp_types::Diagnostic {
+                    range,
+                    severity: Some(match diagnostic.severity() {
+                        Severity::Error => DiagnosticSeverity::ERROR,
+                        Severity::Warning => DiagnosticSeverity::WARNING,
+                    }),
+                    code: diagnostic
+                        .code()
+                        .map(|code| NumberOrString::String(code.to_owned())),
+                    source: Some("rlox".to_owned()),
+                    message,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Url, Document>,
+}
+
+impl Server {
+    ///
+    /// Handles a notification; returns the diagnostics to publish if a document changed
+    ///
+    fn notification(&mut self, notification: Notification) -> Option<Notification> {
+        let (uri, diagnostics, version) = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = notification
+                    .extract::<<DidOpenTextDocument as LspNotification>::Params>(
+                        DidOpenTextDocument::METHOD,
+                    )
+                    .ok()?;
+                let document = params.text_document;
+                let opened = Document::new(document.text, document.version);
+                let diagnostics = opened.diagnostics();
+                self.documents.insert(document.uri.clone(), opened);
+                (document.uri, diagnostics, Some(document.version))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = notification
+                    .extract::<<DidChangeTextDocument as LspNotification>::Params>(
+                        DidChangeTextDocument::METHOD,
+                    )
+                    .ok()?;
+                // the documents are synchronized in full, so the last change holds the whole text
+                let text = params.content_changes.into_iter().last()?.text;
+                let changed = Document::new(text, params.text_document.version);
+                let diagnostics = changed.diagnostics();
+                let uri = params.text_document.uri;
+                self.documents.insert(uri.clone(), changed);
+                (uri, diagnostics, Some(params.text_document.version))
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = notification
+                    .extract::<<DidCloseTextDocument as LspNotification>::Params>(
+                        DidCloseTextDocument::METHOD,
+                    )
+                    .ok()?;
+                let uri = params.text_document.uri;
+                // the errors of a closed document are not shown anymore
+                let version = self.documents.remove(&uri).map(|document| document.version);
+                (uri, vec![], version)
+            }
+            _ => return None,
+        };
+        Some(Notification::new(
+            PublishDiagnostics::METHOD.to_owned(),
+            PublishDiagnosticsParams::new(uri, diagnostics, version),
+        ))
+    }
+
+    fn request(&self, request: Request) -> Response {
+        match request.method.as_str() {
+            HoverRequest::METHOD => self.respond::<HoverRequest>(request, Self::hover),
+            GotoDefinition::METHOD => self.respond::<GotoDefinition>(request, Self::definition),
+            References::METHOD => self.respond::<References>(request, Self::references),
+            Completion::METHOD => self.respond::<Completion>(request, Self::completion),
+            Rename::METHOD => self.try_respond::<Rename>(request, Self::rename),
+            SemanticTokensFullRequest::METHOD => {
+                self.respond::<SemanticTokensFullRequest>(request, Self::semantic_tokens)
+            }
+            method => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request '{method}'"),
+            ),
+        }
+    }
+
+    fn respond<R: LspRequest>(
+        &self,
+        request: Request,
+        handler: impl Fn(&Self, R::Params) -> R::Result,
+    ) -> Response {
+        self.try_respond::<R>(request, |server, params| Ok(handler(server, params)))
+    }
+
+    ///
+    /// Answers a request whose handler may fail, e.g., because the client asked for an invalid
+    /// change
+    ///
+    fn try_respond<R: LspRequest>(
+        &self,
+        request: Request,
+        handler: impl Fn(&Self, R::Params) -> Result<R::Result, String>,
+    ) -> Response {
+        match serde_json::from_value::<R::Params>(request.params) {
+            Ok(params) => match handler(self, params) {
+                Ok(result) => Response::new_ok(request.id, result),
+                Err(message) => {
+                    Response::new_err(request.id, ErrorCode::RequestFailed as i32, message)
+                }
+            },
+            Err(e) => Response::new_err(
+                request.id,
+                ErrorCode::InvalidParams as i32,
+                format!("invalid parameters of '{}': {e}", R::METHOD),
+            ),
+        }
+    }
+
+    ///
+    /// The document and the declaration of the variable at the position
+    ///
+    fn declaration_at(&self, params: &TextDocumentPositionParams) -> Option<(&Document, usize)> {
+        let document = self.documents.get(&params.text_document.uri)?;
+        let declaration = document
+            .analysis
+            .declaration_at(document.offset(params.position))?;
+        Some((document, declaration))
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let (document, declaration) = self.declaration_at(&params.text_document_position_params)?;
+        let var = &document.analysis.declarations[declaration];
+        let value = format!(
+            "```lox\n{source}\n```\n\ntype: `{types}`",
+            source = var.source,
+            types = var.types
+        );
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let position = params.text_document_position_params;
+        let (document, declaration) = self.declaration_at(&position)?;
+        let span = document.analysis.declarations[declaration].span;
+        Some(GotoDefinitionResponse::Scalar(Location::new(
+            position.text_document.uri,
+            document.range(span),
+        )))
+    }
+
+    fn references(&self, params: ReferenceParams) -> Option<Vec<Location>> {
+        let position = params.text_document_position;
+        let (document, declaration) = self.declaration_at(&position)?;
+        let include_declaration = params.context.include_declaration;
+        let declared =
+            include_declaration.then(|| document.analysis.declarations[declaration].span);
+        let locations = declared
+            .into_iter()
+            .chain(
+                document
+                    .analysis
+                    .references_of(declaration, include_declaration),
+            )
+            .map(|span| Location::new(position.text_document.uri.clone(), document.range(span)))
+            .collect();
+        Some(locations)
+    }
+
+    ///
+    /// Renames the variable at the position in its declaration and in all its uses; the new name
+    /// has to be an identifier which is not a keyword
+    ///
+    fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>, String> {
+        let position = params.text_document_position;
+        let Some((document, declaration)) = self.declaration_at(&position) else {
+            return Ok(None);
+        };
+        if !is_identifier(&params.new_name) {
+            return Err(format!(
+                "'{}' is not a valid variable name",
+                params.new_name
+            ));
+        }
+        let declared = document.analysis.declarations[declaration].span;
+        let edits = std::iter::once(declared)
+            .chain(document.analysis.references_of(declaration, true))
+            .map(|span| TextEdit::new(document.range(span), params.new_name.clone()))
+            .collect();
+        let changes = HashMap::from([(position.text_document.uri, edits)]);
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
+
+    ///
+    /// The keywords and the variables declared in front of the position, in the blocks around it
+    ///
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let position = params.text_document_position;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let offset = document.offset(position.position);
+
+        let mut variables: Vec<_> = document
+            .analysis
+            .declarations
+            .iter()
+            .filter(|var| var.span.end.pos <= offset && offset <= var.scope_end)
+            .collect();
+        // a name declared more than once is offered with its last declaration
+        variables.reverse();
+        let mut seen = HashSet::new();
+        let variables = variables
+            .into_iter()
+            .filter(|var| seen.insert(var.name.as_str()))
+            .map(|var| CompletionItem {
+                label: var.name.clone(),
+                kind: Some(CompletionItemKind::VARIABLE),
+                detail: Some(var.types.to_string()),
+                ..Default::default()
+            });
+        let keywords = Token::KEYWORDS.iter().map(|keyword| CompletionItem {
+            label: keyword.to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            ..Default::default()
+        });
+        Some(CompletionResponse::Array(
+            variables.chain(keywords).collect(),
+        ))
+    }
+
+    fn semantic_tokens(&self, params: SemanticTokensParams) -> Option<SemanticTokensResult> {
+        let document = self.documents.get(&params.text_document.uri)?;
+        let mut data = vec![];
+        let mut previous = lsp_types::Position::default();
+        for token in &document.analysis.semantic_tokens {
+            // tokens may not span lines in the protocol, so multi-line strings are split
+            let mut start = token.start;
+            for line in document.text[token.start..token.end].split_inclusive('\n') {
+                let text = line.trim_end_matches(['\n', '\r']);
+                let position = document.lines.position(&document.text, start);
+                start += line.len();
+                if text.is_empty() {
+                    continue;
+                }
+                let delta_line = position.line - previous.line;
+                let delta_start = if delta_line == 0 {
+                    position.character - previous.character
+                } else {
+                    position.character
+                };
+                data.push(SemanticToken {
+                    delta_line,
+                    delta_start,
+                    length: text.encode_utf16().count() as u32,
+                    token_type: token.kind as u32,
+                    token_modifiers_bitset: u32::from(token.declaration),
+                });
+                previous = position;
+            }
+        }
+        Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: None,
+            data,
+        }))
+    }
+}
+
+///
+/// Whether the name scans as a single identifier, i.e., is a valid name for a variable
+///
+fn is_identifier(name: &str) -> bool {
+    let tokens: Vec<_> = Scanner::new(name)
+        .map(|token| token.map(|token| token.t_type))
+        .collect();
+    matches!(
+        tokens.as_slice(),
+        [Ok(TokenType::Identifier(iden)), Ok(TokenType::EOF)] if iden == name
+    )
+}
@@ -0,0 +1,392 @@
+use std::collections::HashSet;
+
+use crate::{
+    diagnostic::diagnostic_of,
+    domain::{
+        location::CodeSpan,
+        scanning::{Token, TokenType, TriviaKind},
+    },
+    inference::Types,
+    parser,
+    resolver::{self, ReferenceKind, Symbols},
+    vm, Diagnostic, Scanner,
+};
+
+///
+/// What the server knows about a document: its errors, its variables and the tokens to highlight
+///
+#[derive(Default)]
+pub(crate) struct Analysis {
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    /// The declared variables, in the order of [Symbols::declarations]
+    pub(crate) declarations: Vec<VarInfo>,
+    /// The declarations and the uses of the variables, as the resolver binds them
+    pub(crate) symbols: Symbols,
+    pub(crate) semantic_tokens: Vec<SemanticToken>,
+}
+
+///
+/// A declared variable
+///
+pub(crate) struct VarInfo {
+    pub(crate) name: String,
+    /// The span of the name in the declaration
+    pub(crate) span: CodeSpan,
+    /// The source of the declaration, from `var` to the semicolon
+    pub(crate) source: String,
+    /// The byte offset at which the block the variable is declared in ends
+    pub(crate) scope_end: usize,
+    /// The types of the values the variable is declared or assigned with
+    pub(crate) types: Types,
+}
+
+///
+/// A token to highlight, given by its byte offsets in the source
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SemanticToken {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) kind: SemanticKind,
+    /// The name of a variable in its declaration
+    pub(crate) declaration: bool,
+}
+
+///
+/// The kinds of semantic tokens, in the order of the legend the server announces
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SemanticKind {
+    Keyword,
+    Variable,
+    Number,
+    String,
+    Operator,
+    Comment,
+}
+
+impl SemanticKind {
+    fn of(t_type: &TokenType) -> Option<Self> {
+        let kind = match t_type {
+            TokenType::Identifier(_) => Self::Variable,
+            TokenType::Number(_) => Self::Number,
+            TokenType::String(_) => Self::String,
+            TokenType::Minus
+            | TokenType::Plus
+            | TokenType::Star
+            | TokenType::Division
+            | TokenType::EqualEqual
+            | TokenType::BangEqual
+            | TokenType::LessEqual
+            | TokenType::GreaterEqual
+            | TokenType::Equal
+            | TokenType::Bang
+            | TokenType::Less
+            | TokenType::Greater => Self::Operator,
+            TokenType::PRINT
+            | TokenType::AND
+            | TokenType::CLASS
+            | TokenType::ELSE
+            | TokenType::FALSE
+            | TokenType::FOR
+            | TokenType::FUN
+            | TokenType::IF
+            | TokenType::NIL
+            | TokenType::OR
+            | TokenType::RETURN
+            | TokenType::SUPER
+            | TokenType::THIS
+            | TokenType::TRUE
+            | TokenType::VAR
+            | TokenType::WHILE => Self::Keyword,
+            TokenType::BraceLeft
+            | TokenType::BraceRight
+            | TokenType::ParenLeft
+            | TokenType::ParenRight
+            | TokenType::Comma
+            | TokenType::Dot
+            | TokenType::Semicolon
+            | TokenType::EOF => return None,
+        };
+        Some(kind)
+    }
+}
+
+impl Analysis {
+    ///
+    /// Reports the errors found by the scanner, the parser, the resolver and the compiler and
+    /// collects the variables and the semantic tokens; the variables are only known if the source
+    /// parses
+    ///
+    pub(crate) fn of(source: &str) -> Self {
+        let mut analysis = Analysis::default();
+        let mut tokens = vec![];
+        let mut scan_errors = vec![];
+        for result in Scanner::new(source).lossless() {
+            match result {
+                Ok(token) => {
+                    analysis.semantic_tokens_of(&token);
+                    tokens.push(token.token);
+                }
+                Err(e) => scan_errors.push(e.into()),
+            }
+        }
+        if !scan_errors.is_empty() {
+            analysis.report(scan_errors);
+            return analysis;
+        }
+
+        let mut program = match parser::parse(&tokens) {
+            Ok(program) => program,
+            Err(errors) => {
+                analysis.report(errors);
+                return analysis;
+            }
+        };
+        // the symbols are collected as far as the variables can be resolved, so that the
+        // variables are known even if some of them cannot be
+        let (symbols, errors) = resolver::resolve_symbols(&mut program);
+        if errors.is_empty() {
+            if let Err(errors) = vm::compile(&program) {
+                analysis.report(errors);
+            }
+        } else {
+            analysis.report(errors);
+        }
+
+        let types = symbols.types(&program);
+        analysis.declarations = symbols
+            .declarations
+            .iter()
+            .zip(types)
+            .map(|(declared, types)| VarInfo {
+                name: declared.name.clone(),
+                span: declared.span,
+                source: declaration_source(source, &tokens, declared.span),
+                scope_end: declared.scope.map_or(source.len(), |braces| braces.end.pos),
+                types,
+            })
+            .collect();
+        analysis.symbols = symbols;
+
+        let declared: HashSet<_> = analysis
+            .declarations
+            .iter()
+            .map(|decl| decl.span.start.pos)
+            .collect();
+        for token in &mut analysis.semantic_tokens {
+            token.declaration = declared.contains(&token.start);
+        }
+        analysis
+    }
+
+    fn report(&mut self, errors: Vec<anyhow::Error>) {
+        self.diagnostics.extend(
+            errors
+                .iter()
+                .map(|e| diagnostic_of(e).unwrap_or_else(|| Diagnostic::new(e.to_string()))),
+        );
+    }
+
+    fn semantic_tokens_of(&mut self, token: &crate::domain::scanning::LosslessToken) {
+        let start = token.token.location().pos;
+        let trivia_len: usize = token.leading_trivia.iter().map(|t| t.text.len()).sum();
+        let mut trivia_start = start - trivia_len;
+        for trivia in &token.leading_trivia {
+            if trivia.kind == TriviaKind::Comment {
+                self.semantic_tokens.push(SemanticToken {
+                    start: trivia_start,
+                    end: trivia_start + trivia.text.trim_end().len(),
+                    kind: SemanticKind::Comment,
+                    declaration: false,
+                });
+            }
+            trivia_start += trivia.text.len();
+        }
+        if let Some(kind) = SemanticKind::of(&token.token.t_type) {
+            self.semantic_tokens.push(SemanticToken {
+                start,
+                end: start + token.text.len(),
+                kind,
+                declaration: false,
+            });
+        }
+    }
+
+    ///
+    /// The declaration of the variable whose name is at the offset, in a declaration or a use
+    ///
+    pub(crate) fn declaration_at(&self, offset: usize) -> Option<usize> {
+        let contains = |span: &CodeSpan| span.start.pos <= offset && offset <= span.end.pos;
+        self.symbols
+            .references
+            .iter()
+            .find(|reference| contains(&reference.span))
+            .map(|reference| reference.declaration)
+            .or_else(|| {
+                self.declarations
+                    .iter()
+                    .position(|decl| contains(&decl.span))
+            })
+    }
+
+    ///
+    /// The spans of the uses of the declaration; the declarations of a global variable which is
+    /// declared again are only included with `redeclarations`
+    ///
+    pub(crate) fn references_of(
+        &self,
+        declaration: usize,
+        redeclarations: bool,
+    ) -> impl Iterator<Item = CodeSpan> + '_ {
+        self.symbols
+            .references_of(declaration)
+            .filter(move |reference| {
+                redeclarations || reference.kind != ReferenceKind::Redeclaration
+            })
+            .map(|reference| reference.span)
+    }
+}
+
+///
+/// The source from the `var` in front of the name up to the semicolon which ends the declaration
+///
+fn declaration_source(source: &str, tokens: &[Token], name: CodeSpan) -> String {
+    let Some(i) = tokens
+        .iter()
+        .position(|token| token.location().pos == name.start.pos)
+    else {
+        return String::new();
+    };
+    let start = tokens[..i]
+        .iter()
+        .rev()
+        .find(|token| token.t_type == TokenType::VAR)
+        .map_or(name.start.pos, |token| token.location().pos);
+    let end = tokens[i..]
+        .iter()
+        .find(|token| token.t_type == TokenType::Semicolon)
+        .map_or(name.end.pos, |token| token.location().pos + 1);
+    source[start..end].to_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Analysis, SemanticKind};
+
+    fn types_of(source: &str, name: &str) -> String {
+        let analysis = Analysis::of(source);
+        let decl = analysis
+            .declarations
+            .iter()
+            .rev()
+            .find(|decl| decl.name == name)
+            .expect("variable not declared");
+        decl.types.to_string()
+    }
+
+    #[test]
+    fn types_are_inferred_from_initializers_and_assignments() {
+        assert_eq!("Nil", types_of("var n;", "n"));
+        assert_eq!("Number | String", types_of("var a = 1; a = \"x\";", "a"));
+        assert_eq!(
+            "Number | Nil",
+            types_of("var a = 1; var b = a or nil;", "b")
+        );
+        assert_eq!("unknown", types_of("var a = b;", "a"));
+    }
+
+    #[test]
+    fn uses_are_linked_to_the_declarations_in_scope() {
+        let source = "var a = 1; { var a = a; print a; } a = 2;";
+        let analysis = Analysis::of(source);
+
+        assert_eq!(2, analysis.declarations.len());
+        let outer: Vec<_> = analysis
+            .references_of(0, false)
+            .map(|s| s.start.pos)
+            .collect();
+        let inner: Vec<_> = analysis
+            .references_of(1, false)
+            .map(|s| s.start.pos)
+            .collect();
+        assert_eq!(vec![35], outer);
+        assert_eq!(vec![21, 30], inner);
+        assert_eq!(Some(1), analysis.declaration_at(31));
+        assert_eq!(Some(0), analysis.declaration_at(4));
+        // like the resolver, the initializer reads the variable it declares, which is reported
+        assert_eq!(1, analysis.diagnostics.len());
+    }
+
+    #[test]
+    fn redeclared_globals_are_one_variable() {
+        let analysis = Analysis::of("var a = 1; var a = a + 1; print a;");
+
+        assert_eq!(1, analysis.declarations.len());
+        assert_eq!("Number", analysis.declarations[0].types.to_string());
+        let uses: Vec<_> = analysis
+            .references_of(0, false)
+            .map(|s| s.start.pos)
+            .collect();
+        assert_eq!(vec![19, 32], uses);
+        let all: Vec<_> = analysis
+            .references_of(0, true)
+            .map(|s| s.start.pos)
+            .collect();
+        assert_eq!(vec![15, 19, 32], all);
+        assert_eq!(Some(0), analysis.declaration_at(15));
+    }
+
+    #[test]
+    fn scopes_end_with_their_blocks() {
+        let source =
+            "var a; { var b; { } } for {var i = 0;} {i < 1} {i = i + 1;} { var c; } var d;";
+        let analysis = Analysis::of(source);
+        let ends: Vec<_> = analysis.declarations.iter().map(|d| d.scope_end).collect();
+        assert_eq!(vec![source.len(), 21, 70, 70, source.len()], ends);
+    }
+
+    #[test]
+    fn declarations_keep_their_source() {
+        let analysis = Analysis::of("{ var total = 1 +\n  2; }");
+        assert_eq!("var total = 1 +\n  2;", analysis.declarations[0].source);
+    }
+
+    #[test]
+    fn errors_of_all_stages_are_reported() {
+        assert_eq!(1, Analysis::of("var a = 1 @ 2;").diagnostics.len());
+        assert_eq!(1, Analysis::of("var a = ;").diagnostics.len());
+        assert_eq!(
+            1,
+            Analysis::of("{ var a = 1; var a = 2; }").diagnostics.len()
+        );
+        assert!(Analysis::of("var a = 1; print a;").diagnostics.is_empty());
+    }
+
+    #[test]
+    fn semantic_tokens_include_comments() {
+        let analysis = Analysis::of("// count\nvar a = 1; print a + \"s\";");
+        let kinds: Vec<_> = analysis.semantic_tokens.iter().map(|t| t.kind).collect();
+        use SemanticKind::*;
+        assert_eq!(
+            vec![Comment, Keyword, Variable, Operator, Number, Keyword, Variable, Operator, String],
+            kinds
+        );
+        assert_eq!(
+            (0, 8),
+            (
+                analysis.semantic_tokens[0].start,
+                analysis.semantic_tokens[0].end
+            )
+        );
+        let declarations: Vec<_> = analysis
+            .semantic_tokens
+            .iter()
+            .map(|t| t.declaration)
+            .collect();
+        assert_eq!(
+            vec![false, false, true, false, false, false, false, false, false],
+            declarations
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use lsp_types::{Position, Range};
+
+use crate::domain::location::CodeSpan;
+
+///
+/// Converts between the byte offsets of the interpreter and the positions of the protocol, which
+/// count lines from zero and columns in UTF-16 code units
+///
+pub(crate) struct LineIndex {
+    /// The byte offset at which each line starts
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub(crate) fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { line_starts }
+    }
+
+    pub(crate) fn position(&self, text: &str, offset: usize) -> Position {
+        let offset = floor_char_boundary(text, offset);
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let character = text[line_start..offset].encode_utf16().count();
+        Position::new(line as u32, character as u32)
+    }
+
+    pub(crate) fn range(&self, text: &str, span: CodeSpan) -> Range {
+        Range::new(
+            self.position(text, span.start.pos),
+            self.position(text, span.end.pos),
+        )
+    }
+
+    ///
+    /// The byte offset of the position; positions behind the end of a line are clamped to it
+    ///
+    pub(crate) fn offset(&self, text: &str, position: Position) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+            return text.len();
+        };
+        let line_end = self
+            .line_starts
+            .get(position.line as usize + 1)
+            .map_or(text.len(), |next| next - 1);
+        let mut units = 0;
+        for (i, c) in text[line_start..line_end].char_indices() {
+            if units >= position.character as usize {
+                return line_start + i;
+            }
+            units += c.len_utf16();
+        }
+        line_end
+    }
+}
+
+///
+/// The spans of errors may reach beyond the end of the source, or into a character
+///
+fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+#[cfg(test)]
+mod test {
+    use lsp_types::Position;
+
+    use super::LineIndex;
+
+    #[test]
+    fn columns_are_counted_in_utf16_units() {
+        let text = "var a;\nvar s = \"😀\"; s;\n";
+        let index = LineIndex::new(text);
+        let offset = text.rfind('s').unwrap();
+
+        assert_eq!(Position::new(1, 14), index.position(text, offset));
+        assert_eq!(offset, index.offset(text, Position::new(1, 14)));
+        assert_eq!(Position::new(0, 4), index.position(text, 4));
+        assert_eq!(4, index.offset(text, Position::new(0, 4)));
+    }
+
+    #[test]
+    fn positions_out_of_the_text_are_clamped() {
+        let text = "a;\nb;";
+        let index = LineIndex::new(text);
+
+        assert_eq!(2, index.offset(text, Position::new(0, 40)));
+        assert_eq!(text.len(), index.offset(text, Position::new(7, 0)));
+        assert_eq!(Position::new(1, 2), index.position(text, 99));
+    }
+}
@@ -1,14 +1,56 @@
-use anyhow::Result;
+use std::process::ExitCode;
+
 use clap::Parser;
-use rlox::{interpret_lox_file, run_prompt, Arguments};
+use rlox::{
+    check_lox_file, debug_lox_file, dump_lox_file, explain_error_code, format_lox_files,
+    interpret_lox_file, lint_lox_files, print_ast_of_lox_file, run_prompt, Arguments,
+    ErrorsReported, LintConfig,
+};
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let arguments = Arguments::parse();
 
-    match arguments.mode() {
-        rlox::Mode::Prompt => run_prompt(),
+    let interpreter = arguments.interpreter();
+    let result = match arguments.mode() {
+        rlox::Mode::Prompt => run_prompt(interpreter),
         rlox::Mode::File {
             file_path: lox_string,
-        } => interpret_lox_file(&lox_string),
+            error_format,
+        } => interpret_lox_file(lox_string, interpreter, *error_format),
+        rlox::Mode::Debug { file_path } => debug_lox_file(file_path, interpreter),
+        rlox::Mode::Check {
+            file_path,
+            error_format,
+        } => check_lox_file(file_path, interpreter, *error_format),
+        rlox::Mode::Ast { format, file_path } => print_ast_of_lox_file(file_path, *format),
+        rlox::Mode::Dump {
+            stage,
+            format,
+            file_path,
+        } => dump_lox_file(file_path, stage.stage(), *format),
+        rlox::Mode::Fmt { check, file_paths } => format_lox_files(file_paths, *check),
+        rlox::Mode::Lint {
+            format,
+            allow,
+            file_paths,
+        } => {
+            let config = allow
+                .iter()
+                .fold(LintConfig::default(), |config, rule| config.allow(*rule));
+            lint_lox_files(file_paths, &config, *format)
+        }
+        rlox::Mode::Explain { code } => explain_error_code(code),
+        #[cfg(feature = "lsp")]
+        rlox::Mode::Lsp => rlox::lsp::run_stdio(),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // the errors have already been written in the requested format
+        Err(e) if e.is::<ErrorsReported>() => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            ExitCode::FAILURE
+        }
     }
 }
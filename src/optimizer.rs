@@ -0,0 +1,206 @@
+//! Optional pass over the program which folds constant expressions and removes branches whose
+//! condition is known before running the program. Folded literals keep the spans of the code
+//! they replace, so that runtime errors still point at the original source.
+
+use crate::{
+    domain::grammar::{
+        visit_mut::{
+            walk_block, walk_comparison, walk_declaration, walk_equality, walk_factor,
+            walk_primary, walk_program, walk_term, walk_unary, VisitorMut,
+        },
+        Block, Comparison, Declaration, Equality, Factor, Primary, Program, Term, Unary,
+    },
+    Environment, Limits,
+};
+
+mod expressions;
+mod statements;
+
+///
+/// The optimizations applied before a program is run
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum OptLevel {
+    /// No optimizations
+    #[default]
+    #[cfg_attr(feature = "cli", value(name = "0"))]
+    O0,
+    /// Constant folding and dead-branch elimination
+    #[cfg_attr(feature = "cli", value(name = "1"))]
+    O1,
+}
+
+///
+/// Optimizes the program for a run with the given limits; operations which would exceed them are
+/// not folded, so that the run reports the error
+///
+pub(super) fn optimize(program: &mut Program, limits: Limits) {
+    let mut environment = Environment::default();
+    environment.set_limits(limits);
+    Optimizer { environment }.visit_program(program);
+}
+
+///
+/// Rewrites the nodes after their children: the operands are folded before the operation, and the
+/// statements in blocks before the blocks are cleaned up
+///
+struct Optimizer {
+    /// The environment the folded operations are evaluated in; it holds the limits of the run
+    environment: Environment,
+}
+
+impl VisitorMut for Optimizer {
+    fn visit_program(&mut self, program: &mut Program) {
+        walk_program(self, program);
+        program.0.retain(|decl| !statements::is_empty_block(decl));
+    }
+
+    fn visit_declaration(&mut self, decl: &mut Declaration) {
+        walk_declaration(self, decl);
+        statements::remove_dead_branches(decl);
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        walk_block(self, block);
+        block.retain(|decl| !statements::is_empty_block(decl));
+    }
+
+    fn visit_equality(&mut self, eq: &mut Equality) {
+        walk_equality(self, eq);
+        expressions::fold_equality(eq);
+    }
+
+    fn visit_comparison(&mut self, comp: &mut Comparison) {
+        walk_comparison(self, comp);
+        expressions::fold_comparison(comp);
+    }
+
+    fn visit_term(&mut self, term: &mut Term) {
+        walk_term(self, term);
+        expressions::fold_term(term, &self.environment);
+    }
+
+    fn visit_factor(&mut self, factor: &mut Factor) {
+        walk_factor(self, factor);
+        expressions::fold_factor(factor);
+    }
+
+    fn visit_unary(&mut self, unary: &mut Unary) {
+        walk_unary(self, unary);
+        expressions::fold_unary(unary);
+    }
+
+    fn visit_primary(&mut self, primary: &mut Primary) {
+        walk_primary(self, primary);
+        expressions::fold_primary(primary);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::grammar::{Declaration, Program, Statement},
+        parser::parse,
+        scanner::scan_input,
+        Limits,
+    };
+
+    use super::optimize;
+
+    fn optimized_with(src: &str, limits: Limits) -> Program {
+        let tokens = scan_input(src).expect("scanning failed");
+        let mut program = parse(&tokens).expect("parsing failed");
+        optimize(&mut program, limits);
+        program
+    }
+
+    fn optimized(src: &str) -> Program {
+        optimized_with(src, Limits::default())
+    }
+
+    fn printed_expression(program: &Program) -> String {
+        match &program[0] {
+            Declaration::Statement(Statement::Print(expr), _) => format!("{expr}"),
+            _ => panic!("expected print statement"),
+        }
+    }
+
+    #[test]
+    fn constant_arithmetic_is_folded() {
+        let program = optimized("print (1 + 2) * 3 - -4;");
+        assert_eq!("13", printed_expression(&program));
+    }
+
+    #[test]
+    fn constant_comparisons_and_strings_are_folded() {
+        let program = optimized(r#"print "a" + "b" == "ab";"#);
+        assert_eq!("true", printed_expression(&program));
+        let program = optimized("print !(1 >= 2);");
+        assert_eq!("true", printed_expression(&program));
+    }
+
+    #[test]
+    fn strings_over_the_length_limit_are_not_folded() {
+        let limits = Limits {
+            max_string_len: Some(3),
+            ..Default::default()
+        };
+        let program = optimized_with(r#"print "ab" + "c" + "d";"#, limits);
+        assert_eq!("(+ 'abc' 'd')", printed_expression(&program));
+    }
+
+    #[test]
+    fn variables_are_not_folded() {
+        let program = optimized("print a + 1 * 2;");
+        assert_eq!("(+ a 2)", printed_expression(&program));
+    }
+
+    #[test]
+    fn failing_operations_are_kept() {
+        let program = optimized("print 1 + 2 + true;");
+        assert_eq!("(+ 3 true)", printed_expression(&program));
+    }
+
+    #[test]
+    fn folded_literals_keep_the_original_span() {
+        let program = optimized("print 1 + 2;");
+        let Declaration::Statement(Statement::Print(expr), _) = &program[0] else {
+            panic!("expected print statement")
+        };
+        let value = expr.constant().expect("expression should be folded");
+        assert_eq!(6, value.span().start.pos);
+        assert_eq!(11, value.span().end.pos);
+    }
+
+    #[test]
+    fn dead_branches_are_removed() {
+        let program = optimized("if 1 < 2 { print 1; } else { print 2; } while false { print 3; }");
+        assert_eq!(1, program.len());
+        let Declaration::Block(block) = &program[0] else {
+            panic!("expected the then block")
+        };
+        assert_eq!(1, block.as_ref().len());
+    }
+
+    #[test]
+    fn nested_dead_branches_and_empty_blocks_are_removed() {
+        let program = optimized("while a { if !true { print 1; } {} print 2 + 3; }");
+        let Declaration::Statement(Statement::While(while_loop), _) = &program[0] else {
+            panic!("expected the while loop")
+        };
+        assert_eq!(
+            "(while a\n  (block\n    (print 5)))",
+            while_loop.to_string()
+        );
+    }
+
+    #[test]
+    fn non_boolean_conditions_are_kept() {
+        let program = optimized("if 1 { print 1; }");
+        assert!(matches!(
+            &program[0],
+            Declaration::Statement(Statement::IfThen(_), _)
+        ));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    domain::grammar::{
+        Comparison, Equality, Expression, Factor, LogicAnd, LogicOr, NumLiteral, Primary,
+        StringLiteral, Term, Unary,
+    },
+    interpreter::{
+        error::RuntimeError,
+        operations::{self, ComparisonOperator, EqualityOperator},
+    },
+    Environment, Value, ValueType,
+};
+
+pub(super) fn fold_equality(eq: &mut Equality) {
+    let folded = match eq {
+        Equality::Comparison(_) => return,
+        Equality::EqualityCheck {
+            left,
+            operator,
+            right,
+        } => fold(left.constant(), right.constant(), |l, r| {
+            operations::check_equality(l, r, EqualityOperator::Equal, *operator)
+        }),
+        Equality::InequalityCheck {
+            left,
+            operator,
+            right,
+        } => fold(left.constant(), right.constant(), |l, r| {
+            operations::check_equality(l, r, EqualityOperator::NotEqual, *operator)
+        }),
+    };
+    if let Some(literal) = folded {
+        *eq = Equality::Comparison(Comparison::Term(literal_term(literal)));
+    }
+}
+
+pub(super) fn fold_comparison(comp: &mut Comparison) {
+    let (left, operator_span, right, operator) = match comp {
+        Comparison::Term(_) => return,
+        Comparison::Greater {
+            left,
+            operator,
+            right,
+        } => (left, operator, right, ComparisonOperator::Greater),
+        Comparison::GreaterEqual {
+            left,
+            operator,
+            right,
+        } => (left, operator, right, ComparisonOperator::GreaterEqual),
+        Comparison::Less {
+            left,
+            operator,
+            right,
+        } => (left, operator, right, ComparisonOperator::Less),
+        Comparison::LessEqual {
+            left,
+            operator,
+            right,
+        } => (left, operator, right, ComparisonOperator::LessEqual),
+    };
+    if let Some(literal) = fold(left.constant(), right.constant(), |l, r| {
+        operations::compare(l, r, operator, *operator_span)
+    }) {
+        *comp = Comparison::Term(literal_term(literal));
+    }
+}
+
+///
+/// Folds additions and subtractions; concatenated strings are checked against the limits of the
+/// environment
+///
+pub(super) fn fold_term(term: &mut Term, env: &Environment) {
+    let folded = match term {
+        Term::Factor(_) => return,
+        Term::Addition {
+            left,
+            operator,
+            right,
+        } => fold(left.constant(), right.constant(), |l, r| {
+            operations::add(l, r, *operator, env)
+        }),
+        Term::Subtraction {
+            left,
+            operator,
+            right,
+        } => fold(left.constant(), right.constant(), |l, r| {
+            operations::subtract(l, r, *operator)
+        }),
+    };
+    if let Some(literal) = folded {
+        *term = literal_term(literal);
+    }
+}
+
+pub(super) fn fold_factor(factor: &mut Factor) {
+    let folded = match factor {
+        Factor::Unary(_) => return,
+        Factor::Multiplication {
+            left,
+            operator,
+            right,
+        } => fold(left.constant(), right.constant(), |l, r| {
+            operations::multiply(l, r, *operator)
+        }),
+        Factor::Division {
+            left,
+            operator,
+            right,
+        } => fold(left.constant(), right.constant(), |l, r| {
+            operations::divide(l, r, *operator)
+        }),
+    };
+    if let Some(literal) = folded {
+        *factor = Factor::Unary(Unary::Primary(literal));
+    }
+}
+
+pub(super) fn fold_unary(unary: &mut Unary) {
+    let folded = match unary {
+        Unary::Primary(_) => return,
+        Unary::LogicalNegation { operator, operand } => operand
+            .constant()
+            .and_then(|v| operations::not(v, *operator).ok()),
+        Unary::ArithmNegation { operator, operand } => operand
+            .constant()
+            .and_then(|v| operations::negate(v, *operator).ok()),
+    };
+    if let Some(value) = folded {
+        *unary = Unary::Primary(literal(value));
+    }
+}
+
+///
+/// Replaces a grouping of a single literal by the literal
+///
+pub(super) fn fold_primary(primary: &mut Primary) {
+    if let Primary::GroupedExpression(expr) = primary {
+        if let Some(value) = expr.constant() {
+            *primary = literal(value);
+        }
+    }
+}
+
+///
+/// Applies the operation if both operands are constant; operations which would fail at runtime
+/// are not folded, so that the error is still reported when the program is run
+///
+fn fold(
+    left: Option<Value>,
+    right: Option<Value>,
+    operation: impl FnOnce(Value, Value) -> Result<Value, RuntimeError>,
+) -> Option<Primary> {
+    let value = operation(left?, right?).ok()?;
+    Some(literal(value))
+}
+
+fn literal(value: Value) -> Primary {
+    let span = value.span();
+    match value.v_type {
+        ValueType::Number(value) => Primary::Number(NumLiteral { value, span }),
+        ValueType::String(value) => Primary::String(StringLiteral {
+            value: value.to_string(),
+            span,
+        }),
+        ValueType::Boolean(true) => Primary::True(span),
+        ValueType::Boolean(false) => Primary::False(span),
+        ValueType::Nil => Primary::Nil(span),
+    }
+}
+
+fn literal_term(literal: Primary) -> Term {
+    Term::Factor(Factor::Unary(Unary::Primary(literal)))
+}
+
+impl Expression {
+    ///
+    /// The value of the expression if it is a single literal
+    ///
+    pub(crate) fn constant(&self) -> Option<Value> {
+        match self {
+            Expression::LogicOr(LogicOr::LogicAnd(LogicAnd::Equality(eq))) => eq.constant(),
+            _ => None,
+        }
+    }
+}
+
+impl Equality {
+    fn constant(&self) -> Option<Value> {
+        match self {
+            Equality::Comparison(comp) => comp.constant(),
+            _ => None,
+        }
+    }
+}
+
+impl Comparison {
+    fn constant(&self) -> Option<Value> {
+        match self {
+            Comparison::Term(term) => term.constant(),
+            _ => None,
+        }
+    }
+}
+
+impl Term {
+    fn constant(&self) -> Option<Value> {
+        match self {
+            Term::Factor(factor) => factor.constant(),
+            _ => None,
+        }
+    }
+}
+
+impl Factor {
+    fn constant(&self) -> Option<Value> {
+        match self {
+            Factor::Unary(unary) => unary.constant(),
+            _ => None,
+        }
+    }
+}
+
+impl Unary {
+    fn constant(&self) -> Option<Value> {
+        match self {
+            Unary::Primary(primary) => primary.constant(),
+            _ => None,
+        }
+    }
+}
+
+impl Primary {
+    fn constant(&self) -> Option<Value> {
+        let value = match self {
+            Primary::Number(NumLiteral { value, span }) => {
+                Value::new(ValueType::Number(*value), *span)
+            }
+            Primary::String(StringLiteral { value, span }) => {
+                Value::new(ValueType::string(value), *span)
+            }
+            Primary::True(span) => Value::new(ValueType::Boolean(true), *span),
+            Primary::False(span) => Value::new(ValueType::Boolean(false), *span),
+            Primary::Nil(span) => Value::new(ValueType::Nil, *span),
+            Primary::Identifier(_) | Primary::GroupedExpression(_) => return None,
+        };
+        Some(value)
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    domain::{
+        grammar::{Block, Declaration, IfThen, IfThenElse, Statement, While},
+        location::CodeSpan,
+    },
+    ValueType,
+};
+
+///
+/// Replaces a statement whose condition is known by the branch which is taken. Statements which
+/// are removed altogether are replaced by an empty block, to be dropped by [is_empty_block].
+///
+pub(super) fn remove_dead_branches(decl: &mut Declaration) {
+    let Some(condition) = constant_condition(decl) else {
+        return;
+    };
+    let removed = Declaration::Block(Block::new(vec![], CodeSpan::default()));
+    *decl = match (std::mem::replace(decl, removed), condition) {
+        (Declaration::Statement(Statement::IfThen(IfThen { then, .. }), _), true)
+        | (
+            Declaration::Statement(
+                Statement::IfThenElse(IfThenElse {
+                    if_then: IfThen { then, .. },
+                    ..
+                }),
+                _,
+            ),
+            true,
+        ) => *then,
+        (
+            Declaration::Statement(Statement::IfThenElse(IfThenElse { else_block, .. }), _),
+            false,
+        ) => *else_block,
+        (infinite_loop @ Declaration::Statement(Statement::While(_), _), true) => infinite_loop,
+        _ => Declaration::Block(Block::new(vec![], CodeSpan::default())),
+    };
+}
+
+///
+/// Whether the declaration is an empty block, which has no effect and can be dropped unless it is
+/// the branch of a statement
+///
+pub(super) fn is_empty_block(decl: &Declaration) -> bool {
+    matches!(decl, Declaration::Block(block) if block.as_ref().is_empty())
+}
+
+///
+/// The condition of an `if` or `while` statement, if it is a Boolean literal
+///
+fn constant_condition(decl: &Declaration) -> Option<bool> {
+    let condition = match decl {
+        Declaration::Statement(
+            Statement::IfThen(IfThen { condition, .. })
+            | Statement::IfThenElse(IfThenElse {
+                if_then: IfThen { condition, .. },
+                ..
+            })
+            | Statement::While(While { condition, .. }),
+            _,
+        ) => condition,
+        _ => return None,
+    };
+    match condition.constant()?.v_type {
+        ValueType::Boolean(b) => Some(b),
+        _ => None,
+    }
+}
@@ -0,0 +1,128 @@
+//! Destinations for the output of the interpreter
+
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    io::{self, Write},
+    rc::Rc,
+};
+
+///
+/// A destination for lines of output. By default, the lines are written to stdout.
+///
+pub struct Output(Sink);
+
+enum Sink {
+    Writer(Box<dyn Write>),
+    Callback(Box<dyn FnMut(&str)>),
+}
+
+impl Output {
+    pub fn stdout() -> Self {
+        Self::writer(io::stdout())
+    }
+
+    pub fn stderr() -> Self {
+        Self::writer(io::stderr())
+    }
+
+    ///
+    /// Discards all output
+    ///
+    pub fn null() -> Self {
+        Self::writer(io::sink())
+    }
+
+    pub fn writer(writer: impl Write + 'static) -> Self {
+        Self(Sink::Writer(Box::new(writer)))
+    }
+
+    ///
+    /// Calls the given function with every line of output (without the line break)
+    ///
+    pub fn callback(callback: impl FnMut(&str) + 'static) -> Self {
+        Self(Sink::Callback(Box::new(callback)))
+    }
+
+    pub(crate) fn write_line(&mut self, line: &str) -> io::Result<()> {
+        match &mut self.0 {
+            Sink::Writer(writer) => writeln!(writer, "{line}"),
+            Sink::Callback(callback) => {
+                callback(line);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self::stdout()
+    }
+}
+
+impl Debug for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Sink::Writer(_) => write!(f, "Output::Writer"),
+            Sink::Callback(_) => write!(f, "Output::Callback"),
+        }
+    }
+}
+
+///
+/// An in-memory output whose contents can be read while the interpreter still writes to it
+///
+#[derive(Debug, Clone, Default)]
+pub struct OutputBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl OutputBuffer {
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
+impl Write for OutputBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl From<OutputBuffer> for Output {
+    fn from(buffer: OutputBuffer) -> Self {
+        Self::writer(buffer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{Output, OutputBuffer};
+
+    #[test]
+    fn buffer_is_shared() {
+        let buffer = OutputBuffer::default();
+        let mut output = Output::from(buffer.clone());
+        output.write_line("a").unwrap();
+        output.write_line("b").unwrap();
+        assert_eq!("a\nb\n", buffer.contents());
+    }
+
+    #[test]
+    fn callback_gets_lines() {
+        let lines = Rc::new(RefCell::new(vec![]));
+        let collected = lines.clone();
+        let mut output = Output::callback(move |line| collected.borrow_mut().push(line.to_owned()));
+        output.write_line("first").unwrap();
+        assert_eq!(vec!["first".to_owned()], *lines.borrow());
+    }
+}
@@ -1,13 +1,19 @@
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, Result};
 
-use crate::domain::{
-    grammar::Program,
-    scanning::{Token, TokenType},
+use crate::{
+    domain::{
+        grammar::{Declaration, Program},
+        scanning::{Token, TokenType},
+    },
+    errors::{MisspelledKeyword, ParseError},
+    suggestion,
 };
 
 mod expressions;
 mod statements;
 
+pub(crate) use statements::statement::{desugar, desugared_for};
+
 #[macro_export]
 macro_rules! matches_t_type {
     ( $token: ident, $t_type: expr ) => {
@@ -18,69 +24,245 @@ macro_rules! matches_t_type {
     };
 }
 
-pub(super) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<anyhow::Error>> {
-    Parser::new(&tokens).parse()
+/// The deepest nesting of groupings, unary operators and blocks the parser accepts unless a run
+/// sets its own limit, so that neither the parser nor the passes over the syntax tree overflow the
+/// stack on untrusted input. Programs nested this deep fit into the 2 MiB stack of spawned threads
+/// in release builds and into the 8 MiB of the main thread in debug builds.
+pub(crate) const MAX_DEPTH: usize = 128;
+
+/// The most binary operators the chains enclosing an operand may have together. The operands of a
+/// chain are not nested, but the passes over the syntax tree recurse into them one after the other.
+pub(crate) const MAX_CHAIN_LENGTH: usize = 1024;
+
+pub(super) fn parse(tokens: &[Token]) -> Result<Program, Vec<anyhow::Error>> {
+    Parser::new(tokens).parse()
+}
+
+///
+/// Parses the tokens, failing if expressions and blocks are nested deeper than the given depth,
+/// which replaces [MAX_DEPTH]
+///
+pub(super) fn parse_with_max_depth(
+    tokens: &[Token],
+    max_depth: Option<usize>,
+) -> Result<Program, Vec<anyhow::Error>> {
+    Parser {
+        max_depth: max_depth.unwrap_or(MAX_DEPTH),
+        ..Parser::new(tokens)
+    }
+    .parse()
+}
+
+///
+/// Parses the tokens while keeping the for loops as they are written, instead of desugaring them
+/// into while loops. The resulting program can only be displayed, not interpreted.
+///
+pub(super) fn parse_without_desugaring(tokens: &[Token]) -> Result<Program, Vec<anyhow::Error>> {
+    Parser {
+        desugar: false,
+        ..Parser::new(tokens)
+    }
+    .parse()
 }
 
 struct Parser<'tokens> {
-    tokens: &'tokens [Token],
+    tokens: &'tokens [Token<'tokens>],
     cur_pos: usize,
+    /// Whether for loops are desugared into while loops
+    desugar: bool,
+    /// The errors of the declarations which were skipped to recover
+    errors: Vec<anyhow::Error>,
+    /// The number of blocks enclosing the current token
+    block_depth: usize,
+    /// The nesting of the blocks, groupings and unary operators enclosing the current token
+    depth: usize,
+    max_depth: usize,
+    /// The number of binary operators in the chains enclosing the current token
+    chain_length: usize,
+    /// Set once a limit is exceeded; the parser then skips the rest of the input, and the errors of
+    /// the enclosing declarations are not reported
+    aborted: bool,
 }
 
 impl<'tokens> Parser<'tokens> {
-    fn new(tokens: &'tokens [Token]) -> Self {
-        Self { tokens, cur_pos: 0 }
+    fn new(tokens: &'tokens [Token<'tokens>]) -> Self {
+        Self {
+            tokens,
+            cur_pos: 0,
+            desugar: true,
+            errors: vec![],
+            block_depth: 0,
+            depth: 0,
+            max_depth: MAX_DEPTH,
+            chain_length: 0,
+            aborted: false,
+        }
     }
 
     fn parse(mut self) -> Result<Program, Vec<anyhow::Error>> {
         let mut declarations = vec![];
-        let mut errors = vec![];
         while self.not_finished() {
-            match self.declaration() {
-                Ok(s) => declarations.push(s),
-                Err(err) => {
-                    errors.push(err);
-                    self.synchronize();
-                }
+            if let Some(declaration) = self.recovering_declaration() {
+                declarations.push(declaration);
             }
         }
-        if errors.is_empty() {
+        if self.errors.is_empty() {
             Ok(Program(declarations))
         } else {
-            Err(errors)
+            Err(self.errors)
+        }
+    }
+
+    ///
+    /// Parses the next declaration. On an error, the error is recorded and the parser skips ahead
+    /// to the next point where parsing can continue. Parsing stops at an exceeded nesting or chain
+    /// limit, as the rest of the input would be read inside the cut off levels.
+    ///
+    fn recovering_declaration(&mut self) -> Option<Declaration> {
+        let start = self.cur_pos;
+        let depth = self.depth;
+        let chain_length = self.chain_length;
+        match self.declaration() {
+            Ok(declaration) => Some(declaration),
+            Err(_) if self.aborted => None,
+            Err(mut err) => {
+                self.depth = depth;
+                self.chain_length = chain_length;
+                self.aborted = matches!(
+                    err.downcast_ref::<ParseError>(),
+                    Some(ParseError::TooDeeplyNested { .. } | ParseError::ChainTooLong { .. })
+                );
+                self.suggest_keyword(&mut err, start);
+                self.errors.push(err);
+                if self.aborted {
+                    self.cur_pos = self.tokens.len() - 1;
+                } else {
+                    self.synchronize(start);
+                }
+                None
+            }
+        }
+    }
+
+    ///
+    /// If the failed declaration starts with an identifier which is similar to a keyword (e.g.,
+    /// `whlie x {`), the keyword is suggested in the error
+    ///
+    fn suggest_keyword(&self, err: &mut anyhow::Error, start: usize) {
+        let Some(ParseError::UnexpectedToken {
+            misspelled_keyword, ..
+        }) = err.downcast_mut::<ParseError>()
+        else {
+            return;
+        };
+        let token = &self.tokens[start];
+        let TokenType::Identifier(name) = token.t_type() else {
+            return;
+        };
+        if let Some(keyword) = suggestion::closest(name, Token::KEYWORDS) {
+            *misspelled_keyword = Some(MisspelledKeyword {
+                keyword,
+                span: token.span(),
+            });
         }
     }
 
     fn not_finished(&self) -> bool {
-        &self.current().expect("current pos is out of bounds").t_type != &TokenType::EOF
+        self.current().expect("current pos is out of bounds").t_type != TokenType::EOF
     }
 
-    fn current(&self) -> Result<&'tokens Token> {
+    fn current(&self) -> Result<&'tokens Token<'tokens>> {
         self.tokens.get(self.cur_pos).ok_or(anyhow!(
             "Unexpected end of token stream when looking at current"
         ))
     }
 
-    fn next(&self) -> Result<&'tokens Token> {
+    ///
+    /// The token in front of the current one, i.e., the last consumed token
+    ///
+    fn previous(&self) -> &'tokens Token<'tokens> {
+        &self.tokens[self.cur_pos.saturating_sub(1)]
+    }
+
+    fn next(&self) -> Result<&'tokens Token<'tokens>> {
         self.tokens.get(self.cur_pos + 1).ok_or(anyhow!(
             "Unexpected end of token stream when looking at next"
         ))
     }
 
-    fn synchronize(&mut self) {
-        let mut current = self.current().expect("current pos is out of bounds");
-        while !matches_t_type!(current, &TokenType::Semicolon, &TokenType::EOF) {
-            self.advance();
-            current = self.current().expect("current pos is out of bounds");
-        }
-        match current.t_type() {
-            TokenType::Semicolon => self.advance(),
-            _ => (),
+    ///
+    /// Skips the tokens up to the next semicolon (which is consumed), brace or keyword starting a
+    /// statement. The tokens the failed declaration started with are skipped, so that the parser
+    /// makes progress; a closing brace outside of blocks is thus reported as the next error.
+    ///
+    fn synchronize(&mut self, start: usize) {
+        loop {
+            let current = self.current().expect("current pos is out of bounds");
+            match current.t_type {
+                TokenType::EOF => return,
+                TokenType::Semicolon => {
+                    self.advance();
+                    return;
+                }
+                TokenType::BraceRight
+                | TokenType::BraceLeft
+                | TokenType::VAR
+                | TokenType::PRINT
+                | TokenType::IF
+                | TokenType::WHILE
+                | TokenType::FOR
+                    if self.cur_pos > start =>
+                {
+                    return
+                }
+                _ => self.advance(),
+            }
         }
     }
 
+    ///
+    /// Moves to the next token; the parser never moves past the EOF token
+    ///
     fn advance(&mut self) {
-        self.cur_pos += 1;
+        if self.cur_pos + 1 < self.tokens.len() {
+            self.cur_pos += 1;
+        }
+    }
+
+    ///
+    /// Goes one level deeper into a nested block or expression; fails if that exceeds the maximum
+    /// depth. Has to be paired with [Parser::leave], unless the declaration fails.
+    ///
+    fn enter(&mut self) -> Result<()> {
+        if self.depth >= self.max_depth {
+            return Err(ParseError::TooDeeplyNested {
+                limit: self.max_depth,
+                span: self.current()?.span(),
+            }
+            .into());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    ///
+    /// Counts the current binary operator of a chain; fails if the enclosing chains exceed
+    /// [MAX_CHAIN_LENGTH] with it. The parsers of the chains restore the count once they are done.
+    ///
+    fn chain(&mut self) -> Result<()> {
+        if self.chain_length >= MAX_CHAIN_LENGTH {
+            return Err(ParseError::ChainTooLong {
+                limit: MAX_CHAIN_LENGTH,
+                span: self.current()?.span(),
+            }
+            .into());
+        }
+        self.chain_length += 1;
+        Ok(())
     }
 
     fn expect(&mut self, t_type: &TokenType, context: &'static str) -> Result<()> {
@@ -88,24 +270,34 @@ impl<'tokens> Parser<'tokens> {
         if matches_t_type!(current, t_type) {
             Ok(())
         } else {
-            bail!(
-                "Expected token type '{:?}' but got '{:?}'; Context: '{context}'; Token location: {loc};",
-                t_type,
-                current.t_type(),
-                loc = current.location()
-            )
+            Err(self.unexpected(&[&format!("'{t_type:?}'")], context)?)
+        }
+    }
+
+    ///
+    /// The error for a current token which is none of the expected ones
+    ///
+    fn unexpected(&self, expected: &[&str], context: &'static str) -> Result<anyhow::Error> {
+        let current = self.current()?;
+        Ok(ParseError::UnexpectedToken {
+            expected: expected.iter().map(|e| e.to_string()).collect(),
+            found: current.t_type().clone().into_owned(),
+            context,
+            span: current.span(),
+            misspelled_keyword: None,
         }
+        .into())
     }
 }
 
 #[cfg(test)]
-use crate::domain::grammar::{Declaration, Expression, Statement};
+use crate::domain::grammar::{Expression, Statement};
 
 #[cfg(test)]
 fn assert_expression(program: Program, expected: Expression) {
     assert_eq!(1, program.len());
     match &program[0] {
-        Declaration::Statement(Statement::Expression(e)) => assert_eq!(expected, *e),
+        Declaration::Statement(Statement::Expression(e), _) => assert_eq!(expected, *e),
         _ => panic!("Expected expression"),
     }
 }
@@ -170,7 +362,7 @@ mod test {
             Token::eof(loc),
         ];
 
-        let output = parse(input).expect("failed to parse");
+        let output = parse(&input).expect("failed to parse");
 
         let expected_expr = Equality::string_equality("a", "b").into();
         assert_expression(output, expected_expr);
@@ -191,7 +383,7 @@ mod test {
             Token::eof(loc),
         ];
 
-        let output = parse(input).expect("failed to parse");
+        let output = parse(&input).expect("failed to parse");
         assert_eq!(2, output.len());
 
         let expected_first_expr: Expression = Equality::string_equality("a", "b").into();
@@ -199,13 +391,13 @@ mod test {
             Primary::String(StringLiteral::new_string("abc", loc)).into();
 
         match &output[0] {
-            Declaration::Statement(Statement::Expression(expr)) => {
+            Declaration::Statement(Statement::Expression(expr), _) => {
                 assert_eq!(expected_first_expr, *expr);
             }
             _ => panic!("Expected expression"),
         }
         match &output[1] {
-            Declaration::Statement(Statement::Print(expr)) => {
+            Declaration::Statement(Statement::Print(expr), _) => {
                 assert_eq!(expected_second_expr, *expr);
             }
             _ => panic!("Expected print statement"),
@@ -13,6 +13,7 @@ impl<'tokens> Parser<'tokens> {
     ///
     pub(crate) fn comparison(&mut self) -> Result<Comparison> {
         let mut comp = Comparison::Term(self.term()?);
+        let chain_length = self.chain_length;
         if let Ok(mut current) = self.current() {
             while matches_t_type!(
                 current,
@@ -21,14 +22,32 @@ impl<'tokens> Parser<'tokens> {
                 &TokenType::Less,
                 &TokenType::LessEqual
             ) {
+                self.chain()?;
+                let operator = current.span();
                 self.advance();
                 let left = Box::new(comp);
                 let right = self.term()?;
                 comp = match current.t_type() {
-                    TokenType::Greater => Comparison::Greater { left, right },
-                    TokenType::GreaterEqual => Comparison::GreaterEqual { left, right },
-                    TokenType::Less => Comparison::Less { left, right },
-                    TokenType::LessEqual => Comparison::LessEqual { left, right },
+                    TokenType::Greater => Comparison::Greater {
+                        left,
+                        operator,
+                        right,
+                    },
+                    TokenType::GreaterEqual => Comparison::GreaterEqual {
+                        left,
+                        operator,
+                        right,
+                    },
+                    TokenType::Less => Comparison::Less {
+                        left,
+                        operator,
+                        right,
+                    },
+                    TokenType::LessEqual => Comparison::LessEqual {
+                        left,
+                        operator,
+                        right,
+                    },
                     _ => unreachable!(),
                 };
                 if let Ok(c) = self.current() {
@@ -39,6 +58,8 @@ impl<'tokens> Parser<'tokens> {
             }
         }
 
+        self.chain_length = chain_length;
+
         Ok(comp)
     }
 }
@@ -66,7 +87,7 @@ mod test {
             Token::eof(loc),
         ];
 
-        let output = parse(input).expect("failed to parse");
+        let output = parse(&input).expect("failed to parse");
 
         let expected_comp = Comparison::string_less_equal("a", "b");
         assert_expression(output, expected_comp.into());
@@ -9,14 +9,25 @@ use crate::{
 impl<'tokens> Parser<'tokens> {
     pub(crate) fn equality(&mut self) -> Result<Equality> {
         let mut comp = Equality::Comparison(self.comparison()?);
+        let chain_length = self.chain_length;
         let mut current = self.current()?;
         while matches_t_type!(current, &TokenType::EqualEqual, &TokenType::BangEqual) {
+            self.chain()?;
+            let operator = current.span();
             self.advance(); // consume the ==/!=
             let left = Box::new(comp);
             let right = self.comparison()?;
             comp = match current.t_type() {
-                TokenType::EqualEqual => Equality::EqualityCheck { left, right },
-                TokenType::BangEqual => Equality::InequalityCheck { left, right },
+                TokenType::EqualEqual => Equality::EqualityCheck {
+                    left,
+                    operator,
+                    right,
+                },
+                TokenType::BangEqual => Equality::InequalityCheck {
+                    left,
+                    operator,
+                    right,
+                },
                 _ => unreachable!(),
             };
             if let Ok(c) = self.current() {
@@ -25,6 +36,7 @@ impl<'tokens> Parser<'tokens> {
                 break;
             }
         }
+        self.chain_length = chain_length;
         Ok(comp)
     }
 }
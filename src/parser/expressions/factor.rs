@@ -9,14 +9,25 @@ use crate::{
 impl<'tokens> Parser<'tokens> {
     pub(super) fn factor(&mut self) -> Result<Factor> {
         let mut factor = Factor::Unary(self.unary()?);
+        let chain_length = self.chain_length;
         if let Ok(mut current) = self.current() {
             while matches_t_type!(current, &TokenType::Star, &TokenType::Division) {
+                self.chain()?;
+                let operator = current.span();
                 self.advance();
                 let left = Box::new(factor);
                 let right = self.unary()?;
                 factor = match current.t_type() {
-                    TokenType::Star => Factor::Multiplication { left, right },
-                    TokenType::Division => Factor::Division { left, right },
+                    TokenType::Star => Factor::Multiplication {
+                        left,
+                        operator,
+                        right,
+                    },
+                    TokenType::Division => Factor::Division {
+                        left,
+                        operator,
+                        right,
+                    },
                     _ => unreachable!(),
                 };
                 if let Ok(c) = self.current() {
@@ -27,6 +38,8 @@ impl<'tokens> Parser<'tokens> {
             }
         }
 
+        self.chain_length = chain_length;
+
         Ok(factor)
     }
 }
@@ -55,7 +68,7 @@ mod test {
             Token::eof(loc),
         ];
 
-        let output = parse(input).expect("failed to parse");
+        let output = parse(&input).expect("failed to parse");
 
         let expected_factor = Factor::string_multiplication("a", "b");
         assert_expression(output, expected_factor.into());
@@ -9,18 +9,26 @@ use crate::{
 impl<'tokens> Parser<'tokens> {
     pub(crate) fn logic_and(&mut self) -> Result<LogicAnd> {
         let mut eq_check = LogicAnd::Equality(self.equality()?);
+        let chain_length = self.chain_length;
         let mut current = self.current()?;
         while matches_t_type!(current, &TokenType::AND) {
+            self.chain()?;
+            let operator = current.span();
             self.advance(); // consume the and
             let left = Box::new(eq_check);
             let right = self.equality()?;
-            eq_check = LogicAnd::And { left, right };
+            eq_check = LogicAnd::And {
+                left,
+                operator,
+                right,
+            };
             if let Ok(c) = self.current() {
                 current = c;
             } else {
                 break;
             }
         }
+        self.chain_length = chain_length;
         Ok(eq_check)
     }
 }
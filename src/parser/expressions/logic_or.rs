@@ -9,18 +9,26 @@ use crate::{
 impl<'tokens> Parser<'tokens> {
     pub(crate) fn logic_or(&mut self) -> Result<LogicOr> {
         let mut l_and = LogicOr::LogicAnd(self.logic_and()?);
+        let chain_length = self.chain_length;
         let mut current = self.current()?;
         while matches_t_type!(current, &TokenType::OR) {
+            self.chain()?;
+            let operator = current.span();
             self.advance(); // consume the or
             let left = Box::new(l_and);
             let right = self.logic_and()?;
-            l_and = LogicOr::Or { left, right };
+            l_and = LogicOr::Or {
+                left,
+                operator,
+                right,
+            };
             if let Ok(c) = self.current() {
                 current = c;
             } else {
                 break;
             }
         }
+        self.chain_length = chain_length;
         Ok(l_and)
     }
 }
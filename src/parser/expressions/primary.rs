@@ -2,7 +2,7 @@ use anyhow::Result;
 
 use crate::{
     domain::{
-        grammar::{NumLiteral, Primary, StringLiteral},
+        grammar::{NumLiteral, Primary, StringLiteral, Variable},
         scanning::TokenType,
     },
     parser::Parser,
@@ -16,53 +16,37 @@ impl<'tokens> Parser<'tokens> {
         let primary = match current.t_type {
             TokenType::Number(n) => Primary::Number(NumLiteral::new(n, location)),
             TokenType::String(ref s) => {
-                Primary::String(StringLiteral::new_string(s.clone(), location))
-            } // TODO check whether we can not copy here
-            TokenType::Identifier(ref i) => {
-                Primary::Identifier(StringLiteral::new_identifier(i.clone(), location))
+                Primary::String(StringLiteral::new_string(s.as_ref(), location))
             }
+            TokenType::Identifier(ref i) => Primary::Identifier(Variable::new(
+                StringLiteral::new_identifier(i.as_ref(), location),
+            )),
             TokenType::TRUE => Primary::true_literal(location),
             TokenType::FALSE => Primary::false_literal(location),
             TokenType::NIL => Primary::nil_literal(location),
             TokenType::ParenLeft => {
+                self.enter()?;
                 self.advance();
                 let expr = self.expression()?;
+                self.leave();
                 self.expect(&TokenType::ParenRight, "closing bracket of a grouping")?;
                 Primary::GroupedExpression(Box::new(expr))
             }
 
-            TokenType::BraceLeft => todo!(),
-            TokenType::BraceRight => todo!(),
-            TokenType::ParenRight => todo!(),
-            TokenType::Comma => todo!(),
-            TokenType::Dot => todo!(),
-            TokenType::Minus => todo!(),
-            TokenType::Plus => todo!(),
-            TokenType::Semicolon => todo!(),
-            TokenType::Star => todo!(),
-            TokenType::EqualEqual => todo!(),
-            TokenType::BangEqual => todo!(),
-            TokenType::LessEqual => todo!(),
-            TokenType::GreaterEqual => todo!(),
-            TokenType::Equal => todo!(),
-            TokenType::Bang => todo!(),
-            TokenType::Less => todo!(),
-            TokenType::Greater => todo!(),
-            TokenType::Division => todo!(),
-            TokenType::PRINT => todo!(),
-            TokenType::AND => todo!(),
-            TokenType::CLASS => todo!(),
-            TokenType::ELSE => todo!(),
-            TokenType::FOR => todo!(),
-            TokenType::FUN => todo!(),
-            TokenType::IF => todo!(),
-            TokenType::OR => todo!(),
-            TokenType::RETURN => todo!(),
-            TokenType::SUPER => todo!(),
-            TokenType::THIS => todo!(),
-            TokenType::VAR => todo!(),
-            TokenType::WHILE => todo!(),
-            TokenType::EOF => todo!(),
+            _ => {
+                return Err(self.unexpected(
+                    &[
+                        "number",
+                        "string",
+                        "identifier",
+                        "'true'",
+                        "'false'",
+                        "'nil'",
+                        "'('",
+                    ],
+                    "expression",
+                )?)
+            }
         };
         self.advance();
         Ok(primary)
@@ -94,7 +78,7 @@ mod test {
             Token::eof(location),
         ];
 
-        let output = parse(input).expect("parsing failed");
+        let output = parse(&input).expect("parsing failed");
 
         let expected_inner: Expression = 42.0.into();
         let expected_group = Primary::grouped_expr(expected_inner);
@@ -115,7 +99,7 @@ mod test {
             Token::eof(location),
         ];
 
-        let output = parse(input);
+        let output = parse(&input);
         assert_err!(output);
     }
 }
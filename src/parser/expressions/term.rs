@@ -13,14 +13,25 @@ impl<'tokens> Parser<'tokens> {
     ///
     pub(super) fn term(&mut self) -> Result<Term> {
         let mut term = Term::Factor(self.factor()?);
+        let chain_length = self.chain_length;
         if let Ok(mut current) = self.current() {
             while matches_t_type!(current, &TokenType::Plus, &TokenType::Minus) {
+                self.chain()?;
+                let operator = current.span();
                 self.advance();
                 let left = Box::new(term);
                 let right = self.factor()?;
                 term = match current.t_type() {
-                    TokenType::Plus => Term::Addition { left, right },
-                    TokenType::Minus => Term::Subtraction { left, right },
+                    TokenType::Plus => Term::Addition {
+                        left,
+                        operator,
+                        right,
+                    },
+                    TokenType::Minus => Term::Subtraction {
+                        left,
+                        operator,
+                        right,
+                    },
                     _ => unreachable!(),
                 };
                 if let Ok(c) = self.current() {
@@ -30,6 +41,7 @@ impl<'tokens> Parser<'tokens> {
                 }
             }
         }
+        self.chain_length = chain_length;
         Ok(term)
     }
 }
@@ -57,7 +69,7 @@ mod test {
             Token::eof(loc),
         ];
 
-        let output = parse(input).expect("failed to parse");
+        let output = parse(&input).expect("failed to parse");
 
         let expected_term = Term::string_addition("a", "b");
         assert_expression(output, expected_term.into());
@@ -10,10 +10,14 @@ impl<'tokens> Parser<'tokens> {
     pub(super) fn unary(&mut self) -> Result<Unary> {
         let current = self.current()?;
         if matches_t_type!(current, &TokenType::Bang, &TokenType::Minus) {
+            let operator = current.span();
+            self.enter()?;
             self.advance();
+            let operand = Box::new(self.unary()?);
+            self.leave();
             let unary = match current.t_type() {
-                TokenType::Bang => Unary::LogicalNegation(Box::new(self.unary()?)),
-                TokenType::Minus => Unary::ArithmNegation(Box::new(self.unary()?)),
+                TokenType::Bang => Unary::LogicalNegation { operator, operand },
+                TokenType::Minus => Unary::ArithmNegation { operator, operand },
                 _ => unreachable!(),
             };
             Ok(unary)
@@ -49,7 +53,7 @@ mod test {
         ];
 
         let expected_unary = Unary::string_arithm_negation("a");
-        let output = parse(input).expect("failed to parse");
+        let output = parse(&input).expect("failed to parse");
         assert_expression(output, expected_unary.into());
     }
 }
@@ -3,8 +3,10 @@ use anyhow::Result;
 use crate::{
     domain::{
         grammar::{Block, Declaration},
-        scanning::TokenType,
+        location::CodeSpan,
+        scanning::{Token, TokenType},
     },
+    errors::ParseError,
     parser::Parser,
 };
 
@@ -22,13 +24,38 @@ impl<'tokens> Parser<'tokens> {
         Ok(Declaration::Block(block))
     }
 
+    ///
+    /// Reads the declarations up to the closing brace. Erroneous declarations are recorded and
+    /// skipped, so that the rest of the block is still parsed.
+    ///
     pub(crate) fn read_block_content(&mut self) -> Result<Block> {
+        self.expect(&TokenType::BraceLeft, "opening brace of a block")?;
+        let opening_brace = self.current()?;
+        self.enter()?;
         self.advance(); // go past opening brace
+        self.block_depth += 1;
         let mut statements = vec![];
         while !self.at_end_of_block()? {
-            statements.push(self.declaration()?);
+            if !self.not_finished() {
+                self.block_depth -= 1;
+                return Err(unclosed_block_error(opening_brace, self.current()?));
+            }
+            if let Some(declaration) = self.recovering_declaration() {
+                statements.push(declaration);
+            }
         }
+        self.block_depth -= 1;
+        self.leave();
+        let braces = CodeSpan::merged(opening_brace.span(), self.current()?.span());
         self.advance(); // go past closing brace
-        Ok(statements.into())
+        Ok(Block::new(statements, braces))
     }
 }
+
+fn unclosed_block_error(opening_brace: &Token, eof: &Token) -> anyhow::Error {
+    ParseError::UnclosedBlock {
+        opening: opening_brace.span(),
+        span: eof.span(),
+    }
+    .into()
+}
@@ -14,8 +14,8 @@ impl<'tokens> Parser<'tokens> {
             let var_declaration = self.var_declaration()?;
             Ok(Declaration::Declaration(var_declaration))
         } else {
-            let statement = self.statement()?;
-            Ok(Declaration::Statement(statement))
+            let (statement, span) = self.statement()?;
+            Ok(Declaration::Statement(statement, span))
         }
     }
 
@@ -3,8 +3,11 @@ use anyhow::Result;
 use crate::{
     domain::{
         grammar::{
-            Declaration, DesugeredFor, For, IfThen, IfThenElse, Statement, StringLiteral, While,
+            visit_mut::{walk_statement, VisitorMut},
+            Block, Declaration, DesugeredFor, For, IfThen, IfThenElse, Program, Statement,
+            StringLiteral, Variable, While,
         },
+        location::CodeSpan,
         scanning::TokenType,
     },
     matches_t_type,
@@ -13,9 +16,11 @@ use crate::{
 
 impl<'tokens> Parser<'tokens> {
     ///
-    /// Reads out an expression; Checks that it is followed by a semicolon. Also advances the current
+    /// Reads out a statement together with its span, which ends with the semicolon or in front of
+    /// the first block of the statement. Also advances the current
     ///
-    pub(crate) fn statement(&mut self) -> Result<Statement> {
+    pub(crate) fn statement(&mut self) -> Result<(Statement, CodeSpan)> {
+        let start = self.current()?.span();
         let statement = match self.current_statement()? {
             StatementType::Print => self.print_statement()?,
             StatementType::Expression => self.expression_statement()?,
@@ -24,8 +29,19 @@ impl<'tokens> Parser<'tokens> {
             StatementType::While => self.while_statement()?,
             StatementType::For => self.for_statement()?,
         };
-
-        Ok(statement)
+        let end = match &statement {
+            Statement::IfThen(IfThen { condition, .. })
+            | Statement::IfThenElse(IfThenElse {
+                if_then: IfThen { condition, .. },
+                ..
+            })
+            | Statement::While(While { condition, .. }) => condition.span(),
+            Statement::For(_) | Statement::RawFor(_) => start,
+            Statement::Expression(_) | Statement::Print(_) | Statement::Assignment(..) => {
+                self.previous().span()
+            }
+        };
+        Ok((statement, CodeSpan::merged(start, end)))
     }
 
     fn consume_semicolon(&mut self) -> Result<()> {
@@ -58,7 +74,10 @@ impl<'tokens> Parser<'tokens> {
 
     fn for_statement(&mut self) -> Result<Statement> {
         let for_statement = self.raw_for_statement()?;
-        let desugered_for = desugered_for(for_statement);
+        if !self.desugar {
+            return Ok(Statement::RawFor(for_statement));
+        }
+        let desugered_for = desugared_for(for_statement);
         Ok(Statement::For(desugered_for))
     }
 
@@ -92,12 +111,12 @@ impl<'tokens> Parser<'tokens> {
     }
 
     fn assignment_statement(&mut self) -> Result<Statement> {
-        let literal = StringLiteral::identifier_from_token(self.current()?)?;
+        let variable = Variable::new(StringLiteral::identifier_from_token(self.current()?)?);
         self.advance();
         self.advance();
         let expr = self.expression()?;
         self.consume_semicolon()?;
-        Ok(Statement::Assignment(literal, expr))
+        Ok(Statement::Assignment(variable, expr))
     }
 
     fn expression_statement(&mut self) -> Result<Statement> {
@@ -173,24 +192,59 @@ impl<'tokens> Parser<'tokens> {
     }
 }
 
-fn desugered_for(for_statement: For) -> DesugeredFor {
+///
+/// Turns the for loop into a block with the init declarations followed by a while loop, whose body
+/// runs the body of the for loop and then the update. The while loop has the span of the condition.
+///
+pub(crate) fn desugared_for(for_statement: For) -> DesugeredFor {
     let For {
         init,
         condition,
         update,
         block,
     } = for_statement;
+    // the loop variables are in scope from the init up to the end of the body
+    let for_braces = CodeSpan::merged(init.braces(), block.braces());
+    let while_braces = CodeSpan::merged(update.braces(), block.braces());
     let mut while_declarations = block.into_inner();
     while_declarations.extend(update.into_inner());
-    let while_block = Declaration::Block(while_declarations.into());
+    let while_block = Declaration::Block(Block::new(while_declarations, while_braces));
+    let condition_span = condition.span();
     let while_loop = While::new(condition, while_block);
     let mut init_declarations = init.into_inner();
     // append the while loop to the end of the init block
-    init_declarations.push(Declaration::Statement(Statement::While(while_loop)));
-    let for_block = Declaration::Block(init_declarations.into());
+    init_declarations.push(Declaration::Statement(
+        Statement::While(while_loop),
+        condition_span,
+    ));
+    let for_block = Declaration::Block(Block::new(init_declarations, for_braces));
     DesugeredFor::new(for_block)
 }
 
+///
+/// Desugars the for loops which were kept as written, e.g., in a program deserialized from the
+/// output of `rlox ast`, so that the program can be run
+///
+pub(crate) fn desugar(program: &mut Program) {
+    Desugarer.visit_program(program);
+}
+
+struct Desugarer;
+
+impl VisitorMut for Desugarer {
+    fn visit_statement(&mut self, statement: &mut Statement) {
+        if matches!(statement, Statement::RawFor(_)) {
+            let empty = Block::new(vec![], CodeSpan::default());
+            let placeholder = Statement::For(DesugeredFor::new(Declaration::Block(empty)));
+            *statement = match std::mem::replace(statement, placeholder) {
+                Statement::RawFor(for_statement) => Statement::For(desugared_for(for_statement)),
+                other => other,
+            };
+        }
+        walk_statement(self, statement);
+    }
+}
+
 enum StatementType {
     Print,
     Assignment,
@@ -10,6 +10,9 @@ use crate::{
 
 impl<'tokens> Parser<'tokens> {
     pub(crate) fn var_declaration(&mut self) -> Result<VarDeclaration> {
+        if !matches!(self.current()?.t_type, TokenType::Identifier(_)) {
+            return Err(self.unexpected(&["identifier"], "variable name after 'var'")?);
+        }
         let iden = StringLiteral::identifier_from_token(self.current()?)?;
         self.advance();
 
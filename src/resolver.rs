@@ -0,0 +1,616 @@
+//! Static pass between parsing and interpretation which binds each use of a variable to the
+//! scope it was declared in and to its slot within that scope.
+//!
+//! Along the way, it tracks which variables are definitely assigned at each point of the program.
+//! Reads of variables which cannot have been assigned on any path are reported; reads of variables
+//! which are assigned on some paths only are left to the check of the interpreter.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    domain::{
+        grammar::{
+            visit_mut::VisitorMut, Binding, Block, Declaration, Program, StringLiteral,
+            VarDeclaration, Variable,
+        },
+        location::CodeSpan,
+    },
+    errors::ResolveError,
+    suggestion,
+};
+
+mod statements;
+mod symbols;
+
+pub(crate) use symbols::{ReferenceKind, Symbols, TypeTracker};
+
+use symbols::Declared;
+
+///
+/// Resolves all variable uses in the given program, annotating them with the depth of their
+/// declaring scope and their index within it. The names in `globals` are treated as already
+/// declared in the global scope (e.g., by previous inputs of the prompt).
+///
+pub(super) fn resolve<'a>(
+    program: &mut Program,
+    globals: impl IntoIterator<Item = &'a str>,
+) -> Result<(), Vec<anyhow::Error>> {
+    let mut resolver = Resolver::new(globals, &program.0);
+    resolver.visit_program(program);
+    if resolver.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(resolver.errors)
+    }
+}
+
+///
+/// Resolves the program like [resolve] and collects the table of its declarations and of the
+/// references to them. The table is complete as far as the variables could be resolved, also if
+/// there are errors.
+///
+pub(crate) fn resolve_symbols(program: &mut Program) -> (Symbols, Vec<anyhow::Error>) {
+    let mut resolver = Resolver::new([], &program.0);
+    resolver.symbols = Some(Symbols::default());
+    resolver.visit_program(program);
+    let symbols = resolver.symbols.unwrap_or_default();
+    (symbols, resolver.errors)
+}
+
+struct Resolver {
+    /// The scopes enclosing the current position; the first entry is the global scope
+    scopes: Vec<Scope>,
+    errors: Vec<anyhow::Error>,
+    /// The table of declarations and references, if it is asked for
+    symbols: Option<Symbols>,
+}
+
+#[derive(Default)]
+struct Scope {
+    /// The declared names with their state and their index in the order of declaration
+    declared: HashMap<String, Var>,
+    /// Names which are declared in this scope, but further down in the source
+    upcoming: HashSet<String>,
+    /// The braces of the block; `None` for the global scope
+    braces: Option<CodeSpan>,
+}
+
+struct Var {
+    state: VarState,
+    index: usize,
+    assignment: Assignment,
+    /// The span of the name in the declaration
+    span: CodeSpan,
+    /// The index of the declaration in the symbol table; `None` for the globals declared by
+    /// previous inputs
+    id: Option<usize>,
+}
+
+#[derive(PartialEq)]
+enum VarState {
+    /// The initializer of the variable is being resolved
+    Declaring,
+    Defined,
+}
+
+///
+/// Whether a variable has a value at the current point of the program
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Assignment {
+    Unassigned,
+    /// Assigned on some paths to the current point, or unknown (e.g., for globals declared by
+    /// previous inputs of the prompt)
+    Maybe,
+    Assigned,
+}
+
+impl Assignment {
+    ///
+    /// The state after two paths of the program merge
+    ///
+    fn join(self, other: Self) -> Self {
+        if self == other {
+            self
+        } else {
+            Self::Maybe
+        }
+    }
+}
+
+///
+/// The assignment states of the variables of all scopes at one point of the program
+///
+type Assignments = Vec<Vec<(String, Assignment)>>;
+
+impl Scope {
+    fn new(declarations: &[Declaration], braces: Option<CodeSpan>) -> Self {
+        let upcoming = declarations
+            .iter()
+            .filter_map(|decl| match decl {
+                Declaration::Declaration(var_decl) => Some(var_decl.iden().value.clone()),
+                _ => None,
+            })
+            .collect();
+        Self {
+            declared: HashMap::default(),
+            upcoming,
+            braces,
+        }
+    }
+}
+
+impl Resolver {
+    fn new<'a>(globals: impl IntoIterator<Item = &'a str>, program: &[Declaration]) -> Self {
+        let mut global_scope = Scope::new(program, None);
+        for name in globals {
+            let var = Var {
+                state: VarState::Defined,
+                index: global_scope.declared.len(),
+                assignment: Assignment::Maybe,
+                span: CodeSpan::default(),
+                id: None,
+            };
+            global_scope.declared.insert(name.to_owned(), var);
+        }
+        Self {
+            scopes: vec![global_scope],
+            errors: vec![],
+            symbols: None,
+        }
+    }
+
+    fn begin_scope(&mut self, block: &Block) {
+        self.scopes
+            .push(Scope::new(block.as_ref(), Some(block.braces())));
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn is_global(&self) -> bool {
+        self.scopes.len() == 1
+    }
+
+    fn innermost(&mut self) -> &mut Scope {
+        self.scopes
+            .last_mut()
+            .expect("global scope is never removed")
+    }
+
+    fn declare(&mut self, iden: &StringLiteral) {
+        let is_global = self.is_global();
+        let scope = self.innermost();
+        // redeclaring globals is fine, e.g., when entering the same declaration in the prompt again
+        if !is_global && scope.declared.contains_key(&iden.value) {
+            self.errors.push(
+                ResolveError::AlreadyDeclared {
+                    name: iden.value.clone(),
+                    span: iden.span,
+                }
+                .into(),
+            );
+            return;
+        }
+        scope.upcoming.remove(&iden.value);
+        // the initializer of a redeclared global reads the previous value
+        if let Some(var) = scope.declared.get(&iden.value) {
+            let id = var.id;
+            self.refer(iden, id, ReferenceKind::Redeclaration);
+            return;
+        }
+        let id = self.record_declaration(iden);
+        let scope = self.innermost();
+        let var = Var {
+            state: VarState::Declaring,
+            index: scope.declared.len(),
+            assignment: Assignment::Unassigned,
+            span: iden.span,
+            id,
+        };
+        scope.declared.insert(iden.value.clone(), var);
+    }
+
+    ///
+    /// Adds the declaration to the symbol table, if it is collected
+    ///
+    fn record_declaration(&mut self, iden: &StringLiteral) -> Option<usize> {
+        let (current, enclosing) = self
+            .scopes
+            .split_last()
+            .expect("global scope is never removed");
+        let symbols = self.symbols.as_mut()?;
+        let shadows = enclosing
+            .iter()
+            .rev()
+            .find_map(|scope| scope.declared.get(&iden.value))
+            .and_then(|var| var.id);
+        let declared = Declared {
+            name: iden.value.clone(),
+            span: iden.span,
+            scope: current.braces,
+            shadows,
+        };
+        Some(symbols.declare(declared))
+    }
+
+    fn refer(&mut self, iden: &StringLiteral, id: Option<usize>, kind: ReferenceKind) {
+        if let (Some(symbols), Some(id)) = (&mut self.symbols, id) {
+            symbols.refer(iden, id, kind);
+        }
+    }
+
+    ///
+    /// Completes the declaration of the variable; `assigned` tells whether it has an initializer
+    ///
+    fn define(&mut self, iden: &StringLiteral, assigned: bool) {
+        if let Some(var) = self.innermost().declared.get_mut(&iden.value) {
+            var.state = VarState::Defined;
+            var.assignment = if assigned {
+                Assignment::Assigned
+            } else {
+                Assignment::Unassigned
+            };
+        }
+    }
+
+    ///
+    /// Resolves a variable which is read, reporting it if it cannot have been assigned yet
+    ///
+    fn read_variable(&mut self, var: &mut Variable) {
+        let Some(var_decl) = self.resolve_variable(var) else {
+            return;
+        };
+        let id = var_decl.id;
+        if var_decl.state == VarState::Defined && var_decl.assignment == Assignment::Unassigned {
+            let declaration = var_decl.span;
+            let error = ResolveError::ReadBeforeAssignment {
+                name: var.name.value.clone(),
+                span: var.name.span,
+                declaration,
+            };
+            self.errors.push(error.into());
+        }
+        self.refer(&var.name, id, ReferenceKind::Read);
+    }
+
+    ///
+    /// Resolves a variable which is assigned; it is assigned from here on
+    ///
+    fn assign_variable(&mut self, var: &mut Variable) {
+        if let Some(var_decl) = self.resolve_variable(var) {
+            var_decl.assignment = Assignment::Assigned;
+            let id = var_decl.id;
+            self.refer(&var.name, id, ReferenceKind::Assignment);
+        }
+    }
+
+    ///
+    /// The assignment states of all variables in scope, to be restored or joined after a branch
+    ///
+    fn assignments(&self) -> Assignments {
+        self.scopes
+            .iter()
+            .map(|scope| {
+                scope
+                    .declared
+                    .iter()
+                    .map(|(name, var)| (name.clone(), var.assignment))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn restore_assignments(&mut self, assignments: &Assignments) {
+        self.update_assignments(assignments, |_, before| before);
+    }
+
+    ///
+    /// Merges the assignment states of another path into the current ones
+    ///
+    fn join_assignments(&mut self, assignments: &Assignments) {
+        self.update_assignments(assignments, Assignment::join);
+    }
+
+    fn update_assignments(
+        &mut self,
+        assignments: &Assignments,
+        update: impl Fn(Assignment, Assignment) -> Assignment,
+    ) {
+        for (scope, assignments) in self.scopes.iter_mut().zip(assignments) {
+            for (name, assignment) in assignments {
+                if let Some(var) = scope.declared.get_mut(name) {
+                    var.assignment = update(var.assignment, *assignment);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Marks the unassigned variables with the given names as maybe assigned, e.g., the variables
+    /// assigned in the body of a loop, which are assigned when the body runs again
+    ///
+    fn assume_assigned<'a>(&mut self, names: impl IntoIterator<Item = &'a str>) {
+        for name in names {
+            let var = self
+                .scopes
+                .iter_mut()
+                .rev()
+                .find_map(|scope| scope.declared.get_mut(name));
+            if let Some(var) = var {
+                var.assignment = var.assignment.join(Assignment::Maybe);
+            }
+        }
+    }
+
+    ///
+    /// Looks up the declaring scope of the variable, starting from the innermost scope. Variables
+    /// which are not declared anywhere are left unresolved and reported by the interpreter.
+    ///
+    fn resolve_variable(&mut self, var: &mut Variable) -> Option<&mut Var> {
+        let name = &var.name;
+        let global_depth = self.scopes.len() - 1;
+        let mut found = None;
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if let Some(Var { state, index, .. }) = scope.declared.get(&name.value) {
+                if state == &VarState::Declaring {
+                    self.errors.push(
+                        ResolveError::ReadInOwnInitializer {
+                            name: name.value.clone(),
+                            span: name.span,
+                        }
+                        .into(),
+                    );
+                }
+                var.binding = Some(if depth == global_depth {
+                    Binding::Global
+                } else {
+                    Binding::Local {
+                        depth,
+                        index: *index,
+                    }
+                });
+                found = Some(global_depth - depth);
+                break;
+            }
+            if scope.upcoming.contains(&name.value) {
+                self.errors.push(
+                    ResolveError::UsedBeforeDeclaration {
+                        name: name.value.clone(),
+                        span: name.span,
+                    }
+                    .into(),
+                );
+                return None;
+            }
+        }
+        let Some(found) = found else {
+            let names = self.scopes.iter().flat_map(|scope| scope.declared.keys());
+            var.suggestion =
+                suggestion::closest(&var.name.value, names.map(String::as_str)).map(str::to_owned);
+            return None;
+        };
+        self.scopes[found].declared.get_mut(&var.name.value)
+    }
+}
+
+impl VarDeclaration {
+    fn iden(&self) -> &StringLiteral {
+        match self {
+            VarDeclaration::Declare(iden) => iden,
+            VarDeclaration::DeclareAndAssign(iden, _) => iden,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::grammar::{
+            Binding, Comparison, Declaration, Equality, Expression, Factor, LogicAnd, LogicOr,
+            Primary, Program, Statement, Term, Unary, VarDeclaration, Variable,
+        },
+        parser::parse,
+        scanner::scan_input,
+    };
+
+    use super::{resolve, resolve_symbols, ReferenceKind};
+
+    fn parsed(src: &str) -> Program {
+        let tokens = scan_input(src).expect("scanning failed");
+        parse(&tokens).expect("parsing failed")
+    }
+
+    fn variable(expr: &Expression) -> &Variable {
+        let Expression::LogicOr(LogicOr::LogicAnd(LogicAnd::Equality(Equality::Comparison(
+            Comparison::Term(Term::Factor(Factor::Unary(Unary::Primary(Primary::Identifier(var))))),
+        )))) = expr
+        else {
+            panic!("expected a plain variable")
+        };
+        var
+    }
+
+    fn var_binding(expr: &Expression) -> Option<Binding> {
+        variable(expr).binding
+    }
+
+    #[test]
+    fn bindings_are_recorded() {
+        let mut program = parsed("var a = 1; { var c; var b = a; { b = a; } }");
+        resolve(&mut program, []).expect("resolving failed");
+
+        let Declaration::Block(outer) = &program[1] else {
+            panic!("expected block")
+        };
+        let Declaration::Declaration(VarDeclaration::DeclareAndAssign(_, init)) =
+            &outer.as_ref()[1]
+        else {
+            panic!("expected declaration")
+        };
+        assert_eq!(Some(Binding::Global), var_binding(init));
+
+        let Declaration::Block(inner) = &outer.as_ref()[2] else {
+            panic!("expected block")
+        };
+        let Declaration::Statement(Statement::Assignment(target, value), _) = &inner.as_ref()[0]
+        else {
+            panic!("expected assignment")
+        };
+        assert_eq!(Some(Binding::Local { depth: 1, index: 1 }), target.binding);
+        assert_eq!(Some(Binding::Global), var_binding(value));
+    }
+
+    #[test]
+    fn symbols_link_uses_to_declarations() {
+        let mut program = parsed("var a = 1; { var b; b = a; { print b; } } var a = 2;");
+        let (symbols, errors) = resolve_symbols(&mut program);
+        assert!(errors.is_empty());
+
+        let declared: Vec<_> = symbols
+            .declarations
+            .iter()
+            .map(|decl| (decl.name.as_str(), decl.scope.map(|braces| braces.end.pos)))
+            .collect();
+        assert_eq!(vec![("a", None), ("b", Some(41))], declared);
+        let references: Vec<_> = symbols
+            .references
+            .iter()
+            .map(|reference| {
+                (
+                    reference.span.start.pos,
+                    reference.declaration,
+                    reference.kind,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (24, 0, ReferenceKind::Read),
+                (20, 1, ReferenceKind::Assignment),
+                (35, 1, ReferenceKind::Read),
+                (46, 0, ReferenceKind::Redeclaration),
+            ],
+            references
+        );
+    }
+
+    #[test]
+    fn undeclared_variables_stay_unresolved() {
+        let mut program = parsed("print a;");
+        resolve(&mut program, []).expect("resolving failed");
+        let Declaration::Statement(Statement::Print(expr), _) = &program[0] else {
+            panic!("expected print")
+        };
+        assert_eq!(None, var_binding(expr));
+    }
+
+    #[test]
+    fn unresolved_variables_get_the_closest_name_in_scope() {
+        let mut program = parsed("var total = 0; { var count = 1; print coutn; print totl; }");
+        resolve(&mut program, []).expect("resolving failed");
+        let Declaration::Block(block) = &program[1] else {
+            panic!("expected block")
+        };
+        let suggestions: Vec<_> = block.as_ref()[1..]
+            .iter()
+            .map(|decl| {
+                let Declaration::Statement(Statement::Print(expr), _) = decl else {
+                    panic!("expected print")
+                };
+                variable(expr).suggestion.as_deref()
+            })
+            .collect();
+        assert_eq!(vec![Some("count"), Some("total")], suggestions);
+    }
+
+    #[test]
+    fn known_globals_are_resolved() {
+        let mut program = parsed("print a; var a = 2;");
+        assert!(resolve(&mut program, ["a"]).is_ok());
+    }
+
+    #[test]
+    fn use_before_declare() {
+        let mut program = parsed("{ print a; var a = 1; }");
+        let errors = resolve(&mut program, []).unwrap_err();
+        assert_eq!(1, errors.len());
+        assert!(errors[0].to_string().contains("before its declaration"));
+    }
+
+    #[test]
+    fn duplicate_declaration_in_block() {
+        let mut program = parsed("{ var a = 1; var a = 2; }");
+        let errors = resolve(&mut program, []).unwrap_err();
+        assert_eq!(1, errors.len());
+        assert!(errors[0].to_string().contains("already declared"));
+    }
+
+    #[test]
+    fn global_redeclaration_is_allowed() {
+        let mut program = parsed("var a = 1; var a = 2;");
+        assert!(resolve(&mut program, []).is_ok());
+    }
+
+    #[test]
+    fn read_before_any_assignment() {
+        let mut program = parsed("var a; print a; { var b; if true { print b; } b = 1; }");
+        let errors = resolve(&mut program, []).unwrap_err();
+        assert_eq!(2, errors.len());
+        assert!(errors[0].to_string().contains("'a'"));
+        assert!(errors[1].to_string().contains("'b'"));
+    }
+
+    #[test]
+    fn reads_after_assignment_on_all_paths() {
+        let src = "var a; if true { a = 1; } else { a = 2; } print a; var b; b = a; print b;";
+        let mut program = parsed(src);
+        assert!(resolve(&mut program, []).is_ok());
+    }
+
+    #[test]
+    fn reads_after_assignment_on_some_paths_are_left_to_the_interpreter() {
+        let src = "var a; if true { a = 1; } print a; { var b; while false { b = 1; } print b; }";
+        let mut program = parsed(src);
+        assert!(resolve(&mut program, []).is_ok());
+    }
+
+    #[test]
+    fn loop_bodies_see_assignments_of_earlier_iterations() {
+        let src = "{ var a; var i = 0; while i < 2 { if i == 1 { print a; } a = i; i = i + 1; } }";
+        let mut program = parsed(src);
+        assert!(resolve(&mut program, []).is_ok());
+    }
+
+    #[test]
+    fn known_globals_may_be_assigned() {
+        let mut program = parsed("print a;");
+        assert!(resolve(&mut program, ["a"]).is_ok());
+    }
+
+    #[test]
+    fn redeclared_global_reads_the_previous_value() {
+        let mut program = parsed("var a = 1; var a = a + 1; var b; var b = b;");
+        let errors = resolve(&mut program, []).unwrap_err();
+        // only the unassigned `b` is reported
+        assert_eq!(1, errors.len());
+        assert!(errors[0].to_string().contains("'b'"));
+    }
+
+    #[test]
+    fn self_referencing_global_initializer() {
+        let mut program = parsed("var a = a;");
+        let errors = resolve(&mut program, []).unwrap_err();
+        assert_eq!(1, errors.len());
+        assert!(errors[0].to_string().contains("own initializer"));
+    }
+
+    #[test]
+    fn self_referencing_initializer() {
+        let mut program = parsed("var a = 1; { var a = a; }");
+        let errors = resolve(&mut program, []).unwrap_err();
+        assert_eq!(1, errors.len());
+        assert!(errors[0].to_string().contains("own initializer"));
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use crate::domain::grammar::{
+    visit::Visitor,
+    visit_mut::{self, VisitorMut},
+    Block, Expression, For, IfThen, IfThenElse, VarDeclaration, Variable, While,
+};
+
+use super::Resolver;
+
+impl VisitorMut for Resolver {
+    fn visit_var_declaration(&mut self, var_decl: &mut VarDeclaration) {
+        match var_decl {
+            VarDeclaration::Declare(iden) => {
+                self.declare(iden);
+                self.define(iden, false);
+            }
+            VarDeclaration::DeclareAndAssign(iden, expr) => {
+                self.declare(iden);
+                self.visit_expression(expr);
+                self.define(iden, true);
+            }
+        }
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        self.begin_scope(block);
+        visit_mut::walk_block(self, block);
+        self.end_scope();
+    }
+
+    fn visit_assignment(&mut self, var: &mut Variable, value: &mut Expression) {
+        self.visit_expression(value);
+        self.assign_variable(var);
+    }
+
+    fn visit_if_then(&mut self, if_then: &mut IfThen) {
+        self.visit_expression(&mut if_then.condition);
+        let before = self.assignments();
+        self.visit_declaration(&mut if_then.then);
+        self.join_assignments(&before);
+    }
+
+    fn visit_if_then_else(&mut self, if_then_else: &mut IfThenElse) {
+        let IfThenElse {
+            if_then,
+            else_block,
+        } = if_then_else;
+        self.visit_expression(&mut if_then.condition);
+        let before = self.assignments();
+        self.visit_declaration(&mut if_then.then);
+        let after_then = self.assignments();
+        self.restore_assignments(&before);
+        self.visit_declaration(else_block);
+        self.join_assignments(&after_then);
+    }
+
+    fn visit_while(&mut self, while_loop: &mut While) {
+        let While { condition, block } = while_loop;
+        // the condition and the body also run after the body assigned variables
+        let mut assigned = AssignedNames::default();
+        assigned.visit_declaration(block);
+        self.assume_assigned(assigned.0);
+        self.visit_expression(condition);
+        let before = self.assignments();
+        self.visit_declaration(block);
+        self.join_assignments(&before);
+    }
+
+    fn visit_for(&mut self, _for_loop: &mut For) {
+        unreachable!("for loops are desugared before the program is resolved")
+    }
+
+    fn visit_variable(&mut self, var: &mut Variable) {
+        self.read_variable(var);
+    }
+}
+
+///
+/// Collects the names of the variables assigned anywhere in the visited nodes
+///
+#[derive(Default)]
+struct AssignedNames<'ast>(HashSet<&'ast str>);
+
+impl<'ast> Visitor<'ast> for AssignedNames<'ast> {
+    fn visit_assignment(&mut self, var: &'ast Variable, _value: &'ast Expression) {
+        self.0.insert(&var.name.value);
+    }
+
+    fn visit_for(&mut self, _for_loop: &'ast For) {
+        unreachable!("for loops are desugared before the program is resolved")
+    }
+}
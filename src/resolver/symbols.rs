@@ -0,0 +1,169 @@
+//! The table of the declared variables and of their uses, as the resolver binds them. Tools which
+//! need to know which declaration a name refers to (the language server and the linter) build on
+//! it instead of tracking the scopes themselves.
+
+use std::collections::HashMap;
+
+use crate::{
+    domain::{
+        grammar::{visit::Visitor, Expression, For, StringLiteral, VarDeclaration, Variable},
+        location::CodeSpan,
+    },
+    inference::{self, LoxType, Types},
+};
+
+///
+/// The declarations of a program and the references to them, both in the order of the source
+///
+#[derive(Debug, Default)]
+pub(crate) struct Symbols {
+    pub(crate) declarations: Vec<Declared>,
+    pub(crate) references: Vec<Reference>,
+    /// The declaration named at each start offset, of a declaration or of a reference
+    by_pos: HashMap<usize, usize>,
+}
+
+///
+/// A declared variable
+///
+#[derive(Debug)]
+pub(crate) struct Declared {
+    pub(crate) name: String,
+    /// The span of the name in the declaration
+    pub(crate) span: CodeSpan,
+    /// The braces of the block the variable is declared in; `None` for global variables
+    #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
+    pub(crate) scope: Option<CodeSpan>,
+    /// The declaration of an enclosing block with the same name
+    pub(crate) shadows: Option<usize>,
+}
+
+///
+/// A use of a declared variable
+///
+#[derive(Debug)]
+pub(crate) struct Reference {
+    #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
+    pub(crate) span: CodeSpan,
+    /// The index of the declaration in [Symbols::declarations]
+    pub(crate) declaration: usize,
+    pub(crate) kind: ReferenceKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReferenceKind {
+    Read,
+    Assignment,
+    /// A global variable which is declared again
+    Redeclaration,
+}
+
+impl Symbols {
+    pub(super) fn declare(&mut self, declared: Declared) -> usize {
+        let id = self.declarations.len();
+        self.by_pos.insert(declared.span.start.pos, id);
+        self.declarations.push(declared);
+        id
+    }
+
+    pub(super) fn refer(&mut self, name: &StringLiteral, declaration: usize, kind: ReferenceKind) {
+        self.by_pos.insert(name.span.start.pos, declaration);
+        self.references.push(Reference {
+            span: name.span,
+            declaration,
+            kind,
+        });
+    }
+
+    ///
+    /// The declaration a name refers to, given the span of the name in a declaration or a use
+    ///
+    pub(crate) fn declaration_of(&self, name: CodeSpan) -> Option<usize> {
+        self.by_pos.get(&name.start.pos).copied()
+    }
+
+    pub(crate) fn references_of(&self, declaration: usize) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(move |reference| reference.declaration == declaration)
+    }
+
+    ///
+    /// The types of the values each declaration is declared or assigned with, by the index of the
+    /// declaration
+    ///
+    #[cfg(feature = "lsp")]
+    pub(crate) fn types(&self, program: &crate::domain::grammar::Program) -> Vec<Types> {
+        let mut tracker = TypeTracker::new(self);
+        tracker.visit_program(program);
+        tracker.types
+    }
+}
+
+///
+/// Accumulates the types of the declared variables while the program is visited in the order of
+/// the source; the types of a variable are the ones it may have at the current point
+///
+pub(crate) struct TypeTracker<'s> {
+    symbols: &'s Symbols,
+    types: Vec<Types>,
+}
+
+impl<'s> TypeTracker<'s> {
+    pub(crate) fn new(symbols: &'s Symbols) -> Self {
+        Self {
+            symbols,
+            types: vec![Types::default(); symbols.declarations.len()],
+        }
+    }
+
+    pub(crate) fn infer(&self, expr: &Expression) -> Types {
+        inference::infer(expr, &|var| {
+            self.symbols
+                .declaration_of(var.name.span)
+                .map_or_else(Types::unknown, |id| self.types[id].clone())
+        })
+    }
+
+    ///
+    /// Records the declaration; declaring a global variable again adds to its types
+    ///
+    pub(crate) fn declare(&mut self, var_decl: &VarDeclaration) {
+        let (name, types) = match var_decl {
+            VarDeclaration::Declare(name) => (name, Types::of(LoxType::Nil)),
+            VarDeclaration::DeclareAndAssign(name, expr) => (name, self.infer(expr)),
+        };
+        let is_declaration = |id: usize| self.symbols.declarations[id].span == name.span;
+        match self.symbols.declaration_of(name.span) {
+            Some(id) if is_declaration(id) => self.types[id] = types,
+            Some(id) => self.add(id, types),
+            None => (),
+        }
+    }
+
+    pub(crate) fn assign(&mut self, var: &Variable, value: &Expression) {
+        let types = self.infer(value);
+        if let Some(id) = self.symbols.declaration_of(var.name.span) {
+            self.add(id, types);
+        }
+    }
+
+    fn add(&mut self, id: usize, types: Types) {
+        let declared = std::mem::take(&mut self.types[id]);
+        self.types[id] = declared.union(types);
+    }
+}
+
+impl<'ast> Visitor<'ast> for TypeTracker<'_> {
+    fn visit_var_declaration(&mut self, var_decl: &'ast VarDeclaration) {
+        self.declare(var_decl);
+    }
+
+    fn visit_assignment(&mut self, var: &'ast Variable, value: &'ast Expression) {
+        self.assign(var, value);
+    }
+
+    fn visit_for(&mut self, _for_loop: &'ast For) {
+        unreachable!("for loops are desugared before the program is resolved")
+    }
+}
@@ -1,76 +1,263 @@
-use std::str::Chars;
+//! Splits the source into tokens. The [Scanner] produces the tokens lazily and borrows the text of
+//! identifiers and strings from the source instead of copying it.
 
-use anyhow::Result;
+use crate::{
+    domain::{
+        location::{CodeSpan, Location},
+        scanning::{LosslessToken, Token, TokenType, Trivia},
+    },
+    errors::ScanError,
+};
 
-use crate::domain::scanning::Token;
-
-use self::state::State;
-
-mod state;
+pub(super) fn scan_input(input: &str) -> Result<Vec<Token<'_>>, Vec<anyhow::Error>> {
+    let mut tokens = vec![];
+    let mut errors = vec![];
+    for result in Scanner::new(input) {
+        match result {
+            Ok(token) => tokens.push(token),
+            Err(e) => errors.push(e.into()),
+        }
+    }
 
-pub(super) fn scan_input(input: &str) -> Result<Vec<Token>, Vec<anyhow::Error>> {
-    Scanner::new(input).scan()
+    if errors.is_empty() {
+        Ok(tokens)
+    } else {
+        Err(errors)
+    }
 }
 
-struct Scanner<'input> {
-    char_iterator: Chars<'input>,
-    state: State,
+///
+/// An iterator over the tokens of a source string, ending with the EOF token. Errors are returned
+/// in place of the offending characters, and scanning continues after them.
+///
+/// ```
+/// use rlox::{domain::scanning::TokenType, Scanner};
+///
+/// let types: Vec<_> = Scanner::new("print a;")
+///     .map(|token| token.unwrap().t_type)
+///     .collect();
+/// assert_eq!(TokenType::PRINT, types[0]);
+/// assert_eq!(TokenType::Identifier("a".into()), types[1]);
+/// assert_eq!(TokenType::EOF, types[3]);
+/// ```
+///
+pub struct Scanner<'src> {
+    source: &'src str,
+    /// The location of the next character; `pos` is its byte offset in the source
+    location: Location,
+    finished: bool,
 }
 
-enum ProcResult {
-    Tokens(Vec<Token>),
-    Error(anyhow::Error),
-    Empty,
-    Finished,
-}
+impl<'src> Scanner<'src> {
+    pub fn new(source: &'src str) -> Self {
+        Self {
+            source,
+            location: Location::default(),
+            finished: false,
+        }
+    }
 
-impl From<anyhow::Error> for ProcResult {
-    fn from(value: anyhow::Error) -> Self {
-        ProcResult::Error(value)
+    fn peek(&self) -> Option<char> {
+        self.source[self.location.pos..].chars().next()
     }
-}
 
-impl<'input> Scanner<'input> {
-    fn new(input: &'input str) -> Self {
-        Scanner {
-            char_iterator: input.chars(),
-            state: State::default(),
+    ///
+    /// Consumes the next character, keeping track of lines and columns
+    ///
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.location = match c {
+            '\n' => Location {
+                line: self.location.line + 1,
+                column: 1,
+                pos: self.location.pos + 1,
+            },
+            // columns beyond the range of the type stick to its maximum
+            _ => Location {
+                column: self.location.column.saturating_add(1),
+                pos: self.location.pos + c.len_utf8(),
+                ..self.location
+            },
+        };
+        Some(c)
+    }
+
+    fn advance_while(&mut self, condition: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&condition) {
+            self.advance();
         }
     }
 
-    fn scan(mut self) -> Result<Vec<Token>, Vec<anyhow::Error>> {
-        let mut tokens = vec![];
-        let mut errors = vec![];
+    ///
+    /// The source from the given location up to the next character
+    ///
+    fn lexeme(&self, start: Location) -> &'src str {
+        &self.source[start.pos..self.location.pos]
+    }
 
-        loop {
-            match self.process_char() {
-                ProcResult::Tokens(mut t) => tokens.append(&mut t),
-                ProcResult::Error(e) => errors.push(e),
-                ProcResult::Empty => continue,
-                ProcResult::Finished => break,
-            }
+    fn one_or_two_char(
+        &mut self,
+        second: char,
+        one: TokenType<'src>,
+        two: TokenType<'src>,
+    ) -> TokenType<'src> {
+        if self.peek() == Some(second) {
+            self.advance();
+            two
+        } else {
+            one
         }
+    }
 
-        if errors.is_empty() {
-            tokens.push(self.state.eof());
-            Ok(tokens)
-        } else {
-            Err(errors)
+    fn string(&mut self, start: Location) -> Result<Token<'src>, ScanError> {
+        let content_start = self.location;
+        self.advance_while(|c| c != '"');
+        let content = self.lexeme(content_start);
+        if self.advance().is_none() {
+            let span = CodeSpan {
+                start,
+                end: start.shifted(content.len() + 1),
+            };
+            return Err(ScanError::UnterminatedString {
+                content: content.to_owned(),
+                span,
+            });
         }
+        Ok(Token::string(content, start))
     }
 
-    fn process_char(&mut self) -> ProcResult {
-        if let Some(c) = self.char_iterator.next() {
-            self.state.add_char(c)
-        } else {
-            match self.state.extract_token() {
-                Ok(Some(t)) => ProcResult::Tokens(vec![t]),
-                Ok(None) => ProcResult::Finished,
-                Err(e) => ProcResult::Error(e),
-            }
+    fn number(&mut self, start: Location) -> Token<'src> {
+        self.advance_while(|c| c.is_ascii_digit());
+        if self.peek() == Some('.') {
+            self.advance();
+            self.advance_while(|c| c.is_ascii_digit());
         }
+        Token::number(self.lexeme(start), start)
+    }
+
+    fn identifier(&mut self, start: Location) -> Token<'src> {
+        self.advance_while(is_identifier_char);
+        Token::keyword_or_identifier(self.lexeme(start), start)
     }
 }
 
+impl<'src> Scanner<'src> {
+    ///
+    /// Turns the scanner into one which keeps the whitespace and comments in front of each token
+    ///
+    pub fn lossless(self) -> LosslessScanner<'src> {
+        LosslessScanner { scanner: self }
+    }
+}
+
+impl<'src> Iterator for Scanner<'src> {
+    type Item = Result<Token<'src>, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        loop {
+            let start = self.location;
+            let Some(c) = self.advance() else {
+                self.finished = true;
+                return Some(Ok(Token::eof(start)));
+            };
+            let t_type = match c {
+                ' ' | '\t' | '\r' | '\n' => continue,
+                '(' => TokenType::ParenLeft,
+                ')' => TokenType::ParenRight,
+                '{' => TokenType::BraceLeft,
+                '}' => TokenType::BraceRight,
+                '-' => TokenType::Minus,
+                '+' => TokenType::Plus,
+                ';' => TokenType::Semicolon,
+                '*' => TokenType::Star,
+                ',' => TokenType::Comma,
+                '.' => TokenType::Dot,
+                '!' => self.one_or_two_char('=', TokenType::Bang, TokenType::BangEqual),
+                '<' => self.one_or_two_char('=', TokenType::Less, TokenType::LessEqual),
+                '>' => self.one_or_two_char('=', TokenType::Greater, TokenType::GreaterEqual),
+                '=' => self.one_or_two_char('=', TokenType::Equal, TokenType::EqualEqual),
+                '/' if self.peek() == Some('/') => {
+                    self.advance_while(|c| c != '\n');
+                    continue;
+                }
+                '/' => TokenType::Division,
+                '"' => return Some(self.string(start)),
+                '@' | '#' | '^' => {
+                    let span = CodeSpan {
+                        start,
+                        end: self.location,
+                    };
+                    return Some(Err(ScanError::IllegalCharacter { character: c, span }));
+                }
+                _ if c.is_ascii_digit() => return Some(Ok(self.number(start))),
+                _ => return Some(Ok(self.identifier(start))),
+            };
+            return Some(Ok(Token::new(t_type, start)));
+        }
+    }
+}
+
+///
+/// An iterator over the tokens of a source string with their text and leading trivia; see
+/// [Scanner::lossless]
+///
+pub struct LosslessScanner<'src> {
+    scanner: Scanner<'src>,
+}
+
+impl<'src> Iterator for LosslessScanner<'src> {
+    type Item = Result<LosslessToken<'src>, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let trivia_start = self.scanner.location.pos;
+        let token = match self.scanner.next()? {
+            Ok(token) => token,
+            Err(e) => return Some(Err(e)),
+        };
+        let source = self.scanner.source;
+        let token_start = token.location().pos;
+        Some(Ok(LosslessToken {
+            leading_trivia: Trivia::split(&source[trivia_start..token_start]),
+            text: &source[token_start..self.scanner.location.pos],
+            token,
+        }))
+    }
+}
+
+///
+/// Identifiers consist of all characters which neither start another token nor separate tokens.
+/// Digits start a number, so they end an identifier.
+///
+fn is_identifier_char(c: char) -> bool {
+    !matches!(
+        c,
+        ' ' | '\t'
+            | '\r'
+            | '\n'
+            | '('
+            | ')'
+            | '{'
+            | '}'
+            | '-'
+            | '+'
+            | ';'
+            | '*'
+            | ','
+            | '.'
+            | '!'
+            | '<'
+            | '>'
+            | '='
+            | '/'
+            | '"'
+            | '@'
+            | '#'
+            | '^'
+    ) && !c.is_ascii_digit()
+}
+
 #[cfg(test)]
 mod test;
@@ -77,13 +77,13 @@ macro_rules! splitting_test {
 }
 
 fn assert_result_locations_match(output: &[Token], expected: &[Token]) {
-    for (actual, expected) in output.into_iter().zip(expected.into_iter()) {
+    for (actual, expected) in output.iter().zip(expected) {
         assert_eq!(actual.location(), expected.location())
     }
 }
 
 fn assert_result_types_match(output: &[Token], expected: &[Token]) {
-    for (actual, expected) in output.into_iter().zip(expected.into_iter()) {
+    for (actual, expected) in output.iter().zip(expected) {
         assert_eq!(
             actual.t_type(),
             expected.t_type(),
@@ -223,3 +223,43 @@ fn test_one_char_not_spaced(c: impl Display, t_type: TokenType) {
     assert_result_types_match(&output, &expected);
     assert_result_locations_match(&output, &expected);
 }
+
+#[test]
+fn scanning_is_lazy() {
+    // the unterminated string at the end is only reached when the iterator gets that far
+    let mut scanner = super::Scanner::new(r#"print a; "unterminated"#);
+    assert_eq!(TokenType::PRINT, scanner.next().unwrap().unwrap().t_type);
+    assert_eq!(
+        TokenType::Identifier("a".into()),
+        scanner.next().unwrap().unwrap().t_type
+    );
+}
+
+#[test]
+fn scanning_continues_after_errors() {
+    let results: Vec<_> = super::Scanner::new("a # b").collect();
+    assert_eq!(4, results.len());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+}
+
+#[test]
+fn lossless_tokens_reproduce_the_source() {
+    let src = "// header\nvar a = \"x y\";  // trailing\n\n{ print a; }\n// end\n";
+    let tokens: Vec<_> = super::Scanner::new(src)
+        .lossless()
+        .map(|token| token.unwrap())
+        .collect();
+    let mut reproduced = String::new();
+    for token in &tokens {
+        token
+            .leading_trivia
+            .iter()
+            .for_each(|trivia| reproduced.push_str(trivia.text));
+        reproduced.push_str(token.text);
+    }
+    assert_eq!(src, reproduced);
+    assert_eq!("\"x y\"", tokens[3].text);
+    assert_eq!("// trailing", tokens[5].leading_trivia[1].text);
+    assert_eq!(TokenType::EOF, tokens.last().unwrap().token.t_type);
+}
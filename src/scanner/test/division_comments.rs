@@ -30,7 +30,7 @@ fn comments_are_ignored() {
         line: 3,
         pos: 34,
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("abc", loc1),
         Token::keyword_or_identifier("bcd", loc2),
         Token::eof(loc3),
@@ -60,7 +60,7 @@ fn comments_are_ignored_multi_line() {
         line: 3,
         pos: 83,
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("abc", loc1),
         Token::keyword_or_identifier("bcd", loc2),
         Token::eof(loc3),
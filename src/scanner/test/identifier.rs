@@ -22,7 +22,7 @@ fn tokens_two_identifier() {
         line: 2,
         pos: 8,
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("abc", loc1),
         Token::keyword_or_identifier("bcd", loc2),
         Token::eof(loc3),
@@ -52,3 +52,15 @@ fn tokens_identifier() {
     let expected = vec![Token::keyword_or_identifier("abc", loc1), Token::eof(loc2)];
     assert_eq!(expected, output);
 }
+
+#[test]
+fn columns_saturate_on_long_lines() {
+    let input = format!("{} abc", " ".repeat(100_000));
+    let output = scan_input(&input).unwrap();
+    let loc = Location {
+        column: u16::MAX,
+        line: 1,
+        pos: 100_001,
+    };
+    assert_eq!(Token::keyword_or_identifier("abc", loc), output[0]);
+}
@@ -58,19 +58,19 @@ fn exact_keyword_recognized(s: &str, t_type: TokenType) {
     let loc2 = Location {
         column: 1 + "a ".len() as u16,
         line: 1,
-        pos: 0 + "a ".len(),
+        pos: "a ".len(),
     };
     let loc3 = Location {
         column: 1 + format!("a {s} ").len() as u16,
         line: 1,
-        pos: 0 + format!("a {s} ").len(),
+        pos: format!("a {s} ").len(),
     };
     let loc4 = Location {
         column: 1 + format!("a {s} b").len() as u16,
         line: 1,
-        pos: 0 + format!("a {s} b").len(),
+        pos: format!("a {s} b").len(),
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("a", loc1),
         Token::keyword_or_identifier(s, loc2),
         Token::keyword_or_identifier("b", loc3),
@@ -81,7 +81,7 @@ fn exact_keyword_recognized(s: &str, t_type: TokenType) {
     assert_eq!(expected[2], output[2], "third");
     assert_eq!(expected[3], output[3], "fourth");
 
-    assert!(&expected[1].t_type == &t_type);
+    assert!(expected[1].t_type == t_type);
 }
 
 fn shorter_keyword_is_identifier(s: &str) {
@@ -97,19 +97,19 @@ fn shorter_keyword_is_identifier(s: &str) {
     let loc2 = Location {
         column: 1 + "a ".len() as u16,
         line: 1,
-        pos: 0 + "a ".len(),
+        pos: "a ".len(),
     };
     let loc3 = Location {
         column: 1 + format!("a {s} ").len() as u16,
         line: 1,
-        pos: 0 + format!("a {s} ").len(),
+        pos: format!("a {s} ").len(),
     };
     let loc4 = Location {
         column: 1 + format!("a {s} b").len() as u16,
         line: 1,
-        pos: 0 + format!("a {s} b").len(),
+        pos: format!("a {s} b").len(),
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("a", loc1),
         Token::keyword_or_identifier(s, loc2),
         Token::keyword_or_identifier("b", loc3),
@@ -135,14 +135,14 @@ fn longer_keyword_is_identifier(s: &str) {
     let loc2 = Location {
         column: 1 + "a ".len() as u16,
         line: 1,
-        pos: 0 + "a ".len(),
+        pos: "a ".len(),
     };
     let loc3 = Location {
         column: 1 + format!("a {s}b").len() as u16,
         line: 1,
-        pos: 0 + format!("a {s}b").len(),
+        pos: format!("a {s}b").len(),
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("a", loc1),
         Token::keyword_or_identifier(format!("{s}b"), loc2),
         Token::eof(loc3),
@@ -153,3 +153,14 @@ fn longer_keyword_is_identifier(s: &str) {
 
     assert_matches!(&expected[1].t_type, TokenType::Identifier(_));
 }
+
+#[test]
+fn keyword_table_lists_all_keywords() {
+    for kw in Token::KEYWORDS {
+        let token = Token::keyword_or_identifier(kw, Location::default());
+        assert!(
+            !matches!(token.t_type, TokenType::Identifier(_)),
+            "{kw} is not a keyword"
+        );
+    }
+}
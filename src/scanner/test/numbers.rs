@@ -30,7 +30,7 @@ fn integer_spaced() {
         line: 1,
         pos: 6,
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("a", loc1),
         Token::one_two_char(TokenType::Equal, loc2),
         Token::number("42.0", loc3),
@@ -77,7 +77,7 @@ fn integer_spaced_not_ending() {
         line: 1,
         pos: 10,
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("a", loc1),
         Token::one_two_char(TokenType::Equal, loc2),
         Token::number("42.0", loc3),
@@ -117,7 +117,7 @@ fn integer_not_spaced() {
         line: 1,
         pos: 4,
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("a", loc1),
         Token::one_two_char(TokenType::Equal, loc2),
         Token::number("42.0", loc3),
@@ -153,7 +153,7 @@ fn float() {
         line: 1,
         pos: 9,
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("a", loc1),
         Token::one_two_char(TokenType::Equal, loc2),
         Token::number("42.24", loc3),
@@ -47,17 +47,17 @@ fn one_char_spaced(c: char, t_type: TokenType) {
     let loc2 = Location {
         column: 1 + "a ".len() as u16,
         line: 1,
-        pos: 0 + "a ".len(),
+        pos: "a ".len(),
     };
     let loc3 = Location {
         column: 1 + "a ( ".len() as u16,
         line: 1,
-        pos: 0 + "a ( ".len(),
+        pos: "a ( ".len(),
     };
     let loc4 = Location {
         column: 1 + "a ( b".len() as u16,
         line: 1,
-        pos: 0 + "a ( b".len(),
+        pos: "a ( b".len(),
     };
     let expected = vec![
         Token::keyword_or_identifier("a", loc1),
@@ -82,17 +82,17 @@ fn one_char_not_spaced(c: char, t_type: TokenType) {
     let loc2 = Location {
         column: 1 + "a".len() as u16,
         line: 1,
-        pos: 0 + "a".len(),
+        pos: "a".len(),
     };
     let loc3 = Location {
         column: 1 + "a(".len() as u16,
         line: 1,
-        pos: 0 + "a(".len(),
+        pos: "a(".len(),
     };
     let loc4 = Location {
         column: 1 + "a(b".len() as u16,
         line: 1,
-        pos: 0 + "a(b".len(),
+        pos: "a(b".len(),
     };
     let expected = vec![
         Token::keyword_or_identifier("a", loc1),
@@ -32,7 +32,7 @@ fn string_assignment() {
         line: 1,
         pos: 21,
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("a", loc1),
         Token::one_two_char(TokenType::Equal, loc2),
         Token::string("my great string", loc3),
@@ -75,7 +75,7 @@ fn string_not_spaced() {
         line: 1,
         pos: 19,
     };
-    let expected = vec![
+    let expected = [
         Token::keyword_or_identifier("a", loc1),
         Token::one_two_char(TokenType::Equal, loc2),
         Token::string("my great string", loc3),
@@ -86,3 +86,25 @@ fn string_not_spaced() {
     assert_eq!(expected[2], output[2], "third");
     assert_eq!(expected[3], output[3], "fourth");
 }
+
+#[test]
+fn string_may_contain_any_character() {
+    let input = "\"a @ b\nc\" d";
+    let output = scan_input(input).unwrap();
+    assert_eq!(TokenType::String("a @ b\nc".into()), output[0].t_type);
+    assert_eq!(2, output[1].location().line);
+}
+
+#[test]
+fn lexemes_are_borrowed_from_the_source() {
+    let input = r#"name = "text""#;
+    let output = scan_input(input).unwrap();
+    for token in &output[..3] {
+        match token.t_type() {
+            TokenType::Identifier(lexeme) | TokenType::String(lexeme) => {
+                assert!(matches!(lexeme, std::borrow::Cow::Borrowed(_)))
+            }
+            _ => {}
+        }
+    }
+}
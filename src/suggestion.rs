@@ -0,0 +1,77 @@
+///
+/// The candidate which is closest to the given name, if it is close enough to be a plausible
+/// misspelling of it. Up to one edit per three characters of the name is accepted; swapping two
+/// adjacent characters counts as a single edit. Ties are broken alphabetically, so that the result
+/// does not depend on the order of the candidates.
+///
+pub(crate) fn closest<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = name.chars().count().max(3) / 3;
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+///
+/// The optimal string alignment distance: the number of insertions, deletions, substitutions and
+/// transpositions of adjacent characters needed to turn one string into the other
+///
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] is the distance between the first i characters of a and the first j of b
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{closest, edit_distance};
+
+    #[test]
+    fn counts_edits() {
+        assert_eq!(0, edit_distance("while", "while"));
+        assert_eq!(1, edit_distance("whlie", "while"));
+        assert_eq!(1, edit_distance("pint", "print"));
+        assert_eq!(1, edit_distance("vat", "var"));
+        assert_eq!(3, edit_distance("", "abc"));
+    }
+
+    #[test]
+    fn suggests_only_close_candidates() {
+        let candidates = ["counter", "total", "while"];
+        assert_eq!(Some("counter"), closest("countr", candidates));
+        assert_eq!(Some("while"), closest("whlie", candidates));
+        assert_eq!(None, closest("x", candidates));
+        assert_eq!(None, closest("counter", candidates));
+    }
+
+    #[test]
+    fn breaks_ties_alphabetically() {
+        assert_eq!(Some("bar"), closest("baz", ["bat", "bar"]));
+    }
+}
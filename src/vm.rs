@@ -0,0 +1,68 @@
+//! Alternative backend: compiles a program to byte code and runs it on a stack machine.
+
+pub(crate) mod chunk;
+mod compiler;
+mod disassembler;
+mod machine;
+
+pub(crate) use compiler::compile;
+pub(crate) use machine::run;
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        parser::parse, resolver::resolve, scanner::scan_input, Environment, ErrorPolicy, ValueType,
+    };
+
+    use super::{chunk::Chunk, compile, run};
+
+    fn compiled(src: &str) -> Chunk {
+        let tokens = scan_input(src).expect("scanning failed");
+        let mut program = parse(&tokens).expect("parsing failed");
+        resolve(&mut program, []).expect("resolving failed");
+        compile(&program).expect("compiling failed")
+    }
+
+    #[test]
+    fn locals_live_on_the_stack() {
+        let chunk = compiled("var a = 1; { var b = 2; a = a + b; }");
+        let disassembly = chunk.to_string();
+        assert!(disassembly.contains("GET_LOCAL"));
+        assert!(!disassembly.contains("DECLARE_GLOBAL          1"));
+
+        let mut env = Environment::default();
+        let (_, errors) = run(&chunk, &mut env, ErrorPolicy::default());
+        assert!(errors.is_empty(), "running failed: {errors:?}");
+        assert_eq!(
+            ValueType::Number(3.0),
+            env.get_var_value("a").unwrap().v_type
+        );
+        assert!(env.get_var_value("b").is_none());
+    }
+
+    #[test]
+    fn errors_abort_only_their_declaration() {
+        let chunk = compiled("var a = 1 + true; var b = 2; b = c;");
+        let mut env = Environment::default();
+        let (stats, errors) = run(&chunk, &mut env, ErrorPolicy::default());
+        assert_eq!(2, errors.len());
+        assert_eq!(3, stats.declarations_run);
+        assert_eq!(2, stats.declarations_failed);
+        assert_eq!(
+            ValueType::Number(2.0),
+            env.get_var_value("b").unwrap().v_type
+        );
+    }
+
+    #[test]
+    fn disassembly_lists_declarations_and_lines() {
+        let chunk = compiled("var a = 1;\nwhile a < 3 { a = a + 1; }");
+        let disassembly = chunk.to_string();
+        assert!(disassembly.contains("== declaration 0 =="));
+        assert!(disassembly.contains("== declaration 1 =="));
+        assert!(disassembly.contains("JUMP_IF_FALSE"));
+        assert!(disassembly.contains("(while condition)"));
+        assert!(disassembly.contains("LOOP"));
+        assert!(disassembly.contains("   2 "));
+    }
+}
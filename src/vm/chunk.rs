@@ -0,0 +1,266 @@
+use crate::{domain::location::CodeSpan, Value};
+
+///
+/// The instructions of the VM. Each op code is encoded as a single byte, followed by its operands.
+/// Unless noted otherwise, operands are two bytes wide (big endian).
+///
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OpCode {
+    /// Pushes the value at the given index of the constant pool
+    Constant,
+    /// Pushes the value of a declared but unassigned variable, which must not be read
+    Uninitialized,
+    Pop,
+
+    /// Declares the global named by the identifier constant; its value is set to nil
+    DeclareGlobal,
+    /// Pops the top of the stack and stores it in the global named by the identifier constant
+    SetGlobal,
+    GetGlobal,
+    /// Pops the top of the stack and stores it in the local with the given slot
+    SetLocal,
+    GetLocal,
+
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Equal,
+    NotEqual,
+    Not,
+    Negate,
+    Or,
+    And,
+
+    /// Jumps forward by the given offset if the top of the stack decides an `or` (keeps the value)
+    OrShortCircuit,
+    /// Jumps forward by the given offset if the top of the stack decides an `and` (keeps the value)
+    AndShortCircuit,
+    /// Pops a condition and jumps forward if it is false. The first operand is a one-byte index
+    /// into [CONDITION_CONTEXTS], the second one the jump offset.
+    JumpIfFalse,
+    Jump,
+    /// Jumps backward by the given offset
+    Loop,
+
+    Print,
+    /// Pops the top of the stack and stores it as the temporary value of the environment
+    SetTmp,
+}
+
+///
+/// The contexts which are reported when a condition does not evaluate to a boolean
+///
+pub(crate) const CONDITION_CONTEXTS: [&str; 3] =
+    ["if condition", "if then block", "while condition"];
+
+impl TryFrom<u8> for OpCode {
+    type Error = u8;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        const OP_CODES: [OpCode; 29] = [
+            OpCode::Constant,
+            OpCode::Uninitialized,
+            OpCode::Pop,
+            OpCode::DeclareGlobal,
+            OpCode::SetGlobal,
+            OpCode::GetGlobal,
+            OpCode::SetLocal,
+            OpCode::GetLocal,
+            OpCode::Add,
+            OpCode::Subtract,
+            OpCode::Multiply,
+            OpCode::Divide,
+            OpCode::Greater,
+            OpCode::GreaterEqual,
+            OpCode::Less,
+            OpCode::LessEqual,
+            OpCode::Equal,
+            OpCode::NotEqual,
+            OpCode::Not,
+            OpCode::Negate,
+            OpCode::Or,
+            OpCode::And,
+            OpCode::OrShortCircuit,
+            OpCode::AndShortCircuit,
+            OpCode::JumpIfFalse,
+            OpCode::Jump,
+            OpCode::Loop,
+            OpCode::Print,
+            OpCode::SetTmp,
+        ];
+        OP_CODES.get(byte as usize).copied().ok_or(byte)
+    }
+}
+
+///
+/// Entries of the constant pool
+///
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Constant {
+    Value(Value),
+    /// The name of a global variable; each name is added once per chunk, the locations of its
+    /// uses are taken from the spans of the instructions
+    Identifier(String),
+}
+
+///
+/// A compiled program: the byte code, the constant pool, and a run-length encoded table mapping
+/// the byte code to the source spans it was compiled from.
+///
+#[derive(Debug, Default)]
+pub(crate) struct Chunk {
+    pub(crate) code: Vec<u8>,
+    pub(crate) constants: Vec<Constant>,
+    /// (span, offset after the last of the consecutive bytes compiled from that span), in
+    /// ascending order of the offsets
+    spans: Vec<(CodeSpan, usize)>,
+    /// The offsets at which the top-level declarations of the program start
+    pub(crate) declarations: Vec<usize>,
+    /// (offset of a GET_LOCAL instruction, name of the variable it reads), in ascending order of
+    /// the offsets; only used to report errors
+    local_names: Vec<(usize, String)>,
+    /// (offset of a GET_GLOBAL or SET_GLOBAL instruction, name suggested by the resolver if the
+    /// global is undefined), in ascending order of the offsets
+    suggestions: Vec<(usize, String)>,
+}
+
+impl Chunk {
+    pub(crate) fn write_op(&mut self, op: OpCode, span: CodeSpan) {
+        self.write_byte(op as u8, span);
+    }
+
+    pub(crate) fn write_byte(&mut self, byte: u8, span: CodeSpan) {
+        self.code.push(byte);
+        let end = self.code.len();
+        match self.spans.last_mut() {
+            Some((last_span, last_end)) if *last_span == span => *last_end = end,
+            _ => self.spans.push((span, end)),
+        }
+    }
+
+    pub(crate) fn write_u16(&mut self, value: u16, span: CodeSpan) {
+        let [high, low] = value.to_be_bytes();
+        self.write_byte(high, span);
+        self.write_byte(low, span);
+    }
+
+    pub(crate) fn read_u16(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.code[offset], self.code[offset + 1]])
+    }
+
+    pub(crate) fn patch_u16(&mut self, offset: usize, value: u16) {
+        let [high, low] = value.to_be_bytes();
+        self.code[offset] = high;
+        self.code[offset + 1] = low;
+    }
+
+    ///
+    /// Records the name of the local variable read by the GET_LOCAL instruction at the offset
+    ///
+    pub(crate) fn add_local_name(&mut self, offset: usize, name: &str) {
+        self.local_names.push((offset, name.to_owned()));
+    }
+
+    ///
+    /// The name of the local variable read by the GET_LOCAL instruction at the offset
+    ///
+    pub(crate) fn local_name(&self, offset: usize) -> &str {
+        let idx = self
+            .local_names
+            .binary_search_by_key(&offset, |(offset, _)| *offset)
+            .expect("GET_LOCAL without recorded name");
+        &self.local_names[idx].1
+    }
+
+    ///
+    /// Records the name to suggest if the global accessed by the instruction at the offset is
+    /// undefined
+    ///
+    pub(crate) fn add_suggestion(&mut self, offset: usize, name: &str) {
+        self.suggestions.push((offset, name.to_owned()));
+    }
+
+    ///
+    /// The name to suggest if the global accessed by the instruction at the offset is undefined
+    ///
+    pub(crate) fn suggestion(&self, offset: usize) -> Option<&str> {
+        let idx = self
+            .suggestions
+            .binary_search_by_key(&offset, |(offset, _)| *offset)
+            .ok()?;
+        Some(&self.suggestions[idx].1)
+    }
+
+    pub(crate) fn add_constant(&mut self, constant: Constant) -> usize {
+        self.constants.push(constant);
+        self.constants.len() - 1
+    }
+
+    ///
+    /// The source span of the byte at the given offset
+    ///
+    pub(crate) fn span(&self, offset: usize) -> CodeSpan {
+        let run = self.spans.partition_point(|(_, end)| *end <= offset);
+        self.spans
+            .get(run)
+            .or(self.spans.last())
+            .map(|(span, _)| *span)
+            .unwrap_or_default()
+    }
+
+    ///
+    /// The source line of the byte at the given offset
+    ///
+    pub(crate) fn line(&self, offset: usize) -> u64 {
+        self.span(offset).start.line
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::location::{CodeSpan, Location};
+
+    use super::{Chunk, OpCode};
+
+    fn in_line(line: u64) -> CodeSpan {
+        let location = Location {
+            line,
+            column: 0,
+            pos: 0,
+        };
+        CodeSpan {
+            start: location,
+            end: location,
+        }
+    }
+
+    #[test]
+    fn op_codes_round_trip() {
+        for byte in 0..=OpCode::SetTmp as u8 {
+            let op = OpCode::try_from(byte).expect("valid op code");
+            assert_eq!(byte, op as u8);
+        }
+        assert!(OpCode::try_from(OpCode::SetTmp as u8 + 1).is_err());
+    }
+
+    #[test]
+    fn spans_are_run_length_encoded() {
+        let mut chunk = Chunk::default();
+        chunk.write_op(OpCode::Uninitialized, in_line(1));
+        chunk.write_op(OpCode::Uninitialized, in_line(1));
+        chunk.write_op(OpCode::Pop, in_line(2));
+        chunk.write_u16(7, in_line(3));
+
+        assert_eq!(3, chunk.spans.len());
+        assert_eq!(1, chunk.line(1));
+        assert_eq!(2, chunk.line(2));
+        assert_eq!(3, chunk.line(4));
+        assert_eq!(7, chunk.read_u16(3));
+    }
+}
@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+
+use crate::{
+    domain::{
+        grammar::{
+            Block, Comparison, Declaration, Equality, Expression, Factor, LogicAnd, LogicOr,
+            NumLiteral, Primary, Program, Statement, StringLiteral, Term, Unary, VarDeclaration,
+            Variable,
+        },
+        location::CodeSpan,
+    },
+    errors::CompileError,
+    Value, ValueType,
+};
+
+use super::chunk::{Chunk, Constant, OpCode};
+
+///
+/// Compiles the given program into a chunk of byte code. Global variables are kept in the
+/// environment, local variables live on the stack of the VM.
+///
+pub(crate) fn compile(program: &Program) -> Result<Chunk, Vec<anyhow::Error>> {
+    let mut compiler = Compiler::default();
+    for decl in program.iter() {
+        compiler.chunk.declarations.push(compiler.chunk.code.len());
+        compiler.declaration(decl);
+    }
+    if compiler.errors.is_empty() {
+        Ok(compiler.chunk)
+    } else {
+        Err(compiler.errors)
+    }
+}
+
+#[derive(Default)]
+struct Compiler {
+    chunk: Chunk,
+    /// The local variables in the order of their stack slots
+    locals: Vec<Local>,
+    scope_depth: usize,
+    /// The source span of the code which is currently compiled
+    span: CodeSpan,
+    /// The index of the identifier constant of each global name used so far
+    identifiers: HashMap<String, u16>,
+    errors: Vec<anyhow::Error>,
+}
+
+struct Local {
+    name: String,
+    depth: usize,
+}
+
+impl Compiler {
+    fn declaration(&mut self, decl: &Declaration) {
+        match decl {
+            Declaration::Declaration(var_decl) => self.var_declaration(var_decl),
+            Declaration::Statement(statement, _) => self.statement(statement),
+            Declaration::Block(block) => self.block(block),
+        }
+    }
+
+    fn var_declaration(&mut self, var_decl: &VarDeclaration) {
+        let (iden, init) = match var_decl {
+            VarDeclaration::Declare(iden) => (iden, None),
+            VarDeclaration::DeclareAndAssign(iden, expr) => (iden, Some(expr)),
+        };
+        self.span = iden.span;
+
+        if self.scope_depth == 0 {
+            let name = self.identifier_constant(iden);
+            self.emit_with_operand(OpCode::DeclareGlobal, name);
+            match init {
+                Some(expr) => self.expression(expr),
+                None => self.emit(OpCode::Uninitialized),
+            }
+            self.span = iden.span;
+            self.emit_with_operand(OpCode::SetGlobal, name);
+        } else {
+            match init {
+                Some(expr) => self.expression(expr),
+                None => self.emit(OpCode::Uninitialized),
+            }
+            if self.locals.len() > u16::MAX as usize {
+                self.errors.push(
+                    CompileError::TooManyLocals {
+                        name: iden.value.clone(),
+                        span: self.span,
+                    }
+                    .into(),
+                );
+            }
+            self.locals.push(Local {
+                name: iden.value.clone(),
+                depth: self.scope_depth,
+            });
+        }
+    }
+
+    fn block(&mut self, block: &Block) {
+        self.scope_depth += 1;
+        for decl in block.as_ref() {
+            self.declaration(decl);
+        }
+        self.scope_depth -= 1;
+        while matches!(self.locals.last(), Some(local) if local.depth > self.scope_depth) {
+            self.locals.pop();
+            self.emit(OpCode::Pop);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Expression(expr) => {
+                self.expression(expr);
+                self.emit(OpCode::SetTmp);
+            }
+            Statement::Print(expr) => {
+                self.expression(expr);
+                self.emit(OpCode::Print);
+            }
+            Statement::Assignment(var, expr) => {
+                self.expression(expr);
+                self.set_variable(var);
+            }
+            Statement::IfThen(if_then) => {
+                self.expression(&if_then.condition);
+                let then_end = self.emit_condition_jump(0);
+                self.declaration(&if_then.then);
+                self.patch_jump(then_end);
+            }
+            Statement::IfThenElse(if_then_else) => {
+                self.expression(&if_then_else.if_then.condition);
+                let then_end = self.emit_condition_jump(1);
+                self.declaration(&if_then_else.if_then.then);
+                let else_end = self.emit_jump(OpCode::Jump);
+                self.patch_jump(then_end);
+                self.declaration(&if_then_else.else_block);
+                self.patch_jump(else_end);
+            }
+            Statement::While(while_loop) => {
+                let loop_start = self.chunk.code.len();
+                self.expression(&while_loop.condition);
+                let exit = self.emit_condition_jump(2);
+                self.declaration(&while_loop.block);
+                self.emit_loop(loop_start);
+                self.patch_jump(exit);
+            }
+            Statement::For(desugered_for) => self.declaration(&desugered_for.for_block),
+            Statement::RawFor(_) => {
+                unreachable!("for loops are desugared before the program is resolved")
+            }
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::LogicOr(l_or) => self.logic_or(l_or),
+        }
+    }
+
+    fn logic_or(&mut self, l_or: &LogicOr) {
+        match l_or {
+            LogicOr::LogicAnd(l_and) => self.logic_and(l_and),
+            LogicOr::Or {
+                left,
+                operator,
+                right,
+            } => {
+                self.logic_or(left);
+                self.span = *operator;
+                let end = self.emit_jump(OpCode::OrShortCircuit);
+                self.logic_and(right);
+                self.span = *operator;
+                self.emit(OpCode::Or);
+                self.patch_jump(end);
+            }
+        }
+    }
+
+    fn logic_and(&mut self, l_and: &LogicAnd) {
+        match l_and {
+            LogicAnd::Equality(eq) => self.equality(eq),
+            LogicAnd::And {
+                left,
+                operator,
+                right,
+            } => {
+                self.logic_and(left);
+                self.span = *operator;
+                let end = self.emit_jump(OpCode::AndShortCircuit);
+                self.equality(right);
+                self.span = *operator;
+                self.emit(OpCode::And);
+                self.patch_jump(end);
+            }
+        }
+    }
+
+    fn equality(&mut self, eq: &Equality) {
+        let (left, operator, right, op) = match eq {
+            Equality::Comparison(comp) => return self.comparison(comp),
+            Equality::EqualityCheck {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::Equal),
+            Equality::InequalityCheck {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::NotEqual),
+        };
+        self.equality(left);
+        self.comparison(right);
+        self.span = *operator;
+        self.emit(op);
+    }
+
+    fn comparison(&mut self, comp: &Comparison) {
+        let (left, operator, right, op) = match comp {
+            Comparison::Term(term) => return self.term(term),
+            Comparison::Greater {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::Greater),
+            Comparison::GreaterEqual {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::GreaterEqual),
+            Comparison::Less {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::Less),
+            Comparison::LessEqual {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::LessEqual),
+        };
+        self.comparison(left);
+        self.term(right);
+        self.span = *operator;
+        self.emit(op);
+    }
+
+    fn term(&mut self, term: &Term) {
+        let (left, operator, right, op) = match term {
+            Term::Factor(factor) => return self.factor(factor),
+            Term::Addition {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::Add),
+            Term::Subtraction {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::Subtract),
+        };
+        self.term(left);
+        self.factor(right);
+        self.span = *operator;
+        self.emit(op);
+    }
+
+    fn factor(&mut self, factor: &Factor) {
+        let (left, operator, right, op) = match factor {
+            Factor::Unary(unary) => return self.unary(unary),
+            Factor::Multiplication {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::Multiply),
+            Factor::Division {
+                left,
+                operator,
+                right,
+            } => (left, operator, right, OpCode::Divide),
+        };
+        self.factor(left);
+        self.unary(right);
+        self.span = *operator;
+        self.emit(op);
+    }
+
+    fn unary(&mut self, unary: &Unary) {
+        match unary {
+            Unary::Primary(primary) => self.primary(primary),
+            Unary::LogicalNegation { operator, operand } => {
+                self.unary(operand);
+                self.span = *operator;
+                self.emit(OpCode::Not);
+            }
+            Unary::ArithmNegation { operator, operand } => {
+                self.unary(operand);
+                self.span = *operator;
+                self.emit(OpCode::Negate);
+            }
+        }
+    }
+
+    fn primary(&mut self, primary: &Primary) {
+        let value = match primary {
+            Primary::Number(NumLiteral { value, span }) => {
+                Value::new(ValueType::Number(*value), *span)
+            }
+            Primary::String(StringLiteral { value, span }) => {
+                Value::new(ValueType::string(value), *span)
+            }
+            Primary::True(span) => Value::new(ValueType::Boolean(true), *span),
+            Primary::False(span) => Value::new(ValueType::Boolean(false), *span),
+            Primary::Nil(span) => Value::new(ValueType::Nil, *span),
+            Primary::GroupedExpression(expr) => return self.expression(expr),
+            Primary::Identifier(var) => return self.get_variable(var),
+        };
+        self.span = value.span();
+        let idx = self.add_constant(Constant::Value(value));
+        self.emit_with_operand(OpCode::Constant, idx);
+    }
+
+    fn get_variable(&mut self, var: &Variable) {
+        self.span = var.name.span;
+        match self.local_slot(var) {
+            Some(slot) => {
+                let offset = self.chunk.code.len();
+                self.chunk.add_local_name(offset, &var.name.value);
+                self.emit_with_operand(OpCode::GetLocal, slot);
+            }
+            None => {
+                let name = self.identifier_constant(&var.name);
+                self.add_suggestion(var);
+                self.emit_with_operand(OpCode::GetGlobal, name);
+            }
+        }
+    }
+
+    fn set_variable(&mut self, var: &Variable) {
+        self.span = var.name.span;
+        match self.local_slot(var) {
+            Some(slot) => self.emit_with_operand(OpCode::SetLocal, slot),
+            None => {
+                let name = self.identifier_constant(&var.name);
+                self.add_suggestion(var);
+                self.emit_with_operand(OpCode::SetGlobal, name);
+            }
+        }
+    }
+
+    fn local_slot(&self, var: &Variable) -> Option<u16> {
+        self.locals
+            .iter()
+            .rposition(|local| local.name == var.name.value)
+            .map(|slot| slot as u16)
+    }
+
+    ///
+    /// Records the name suggested by the resolver for the global accessed by the next instruction
+    ///
+    fn add_suggestion(&mut self, var: &Variable) {
+        if let Some(suggestion) = &var.suggestion {
+            let offset = self.chunk.code.len();
+            self.chunk.add_suggestion(offset, suggestion);
+        }
+    }
+
+    fn identifier_constant(&mut self, iden: &StringLiteral) -> u16 {
+        if let Some(&idx) = self.identifiers.get(&iden.value) {
+            return idx;
+        }
+        let idx = self.add_constant(Constant::Identifier(iden.value.clone()));
+        self.identifiers.insert(iden.value.clone(), idx);
+        idx
+    }
+
+    fn add_constant(&mut self, constant: Constant) -> u16 {
+        let idx = self.chunk.add_constant(constant);
+        match u16::try_from(idx) {
+            Ok(idx) => idx,
+            Err(_) => {
+                self.errors
+                    .push(CompileError::TooManyConstants { span: self.span }.into());
+                0
+            }
+        }
+    }
+
+    fn emit(&mut self, op: OpCode) {
+        self.chunk.write_op(op, self.span);
+    }
+
+    fn emit_with_operand(&mut self, op: OpCode, operand: u16) {
+        self.emit(op);
+        self.chunk.write_u16(operand, self.span);
+    }
+
+    ///
+    /// Emits a jump with a placeholder offset; returns the position of the offset to patch
+    ///
+    fn emit_jump(&mut self, op: OpCode) -> usize {
+        self.emit_with_operand(op, u16::MAX);
+        self.chunk.code.len() - 2
+    }
+
+    ///
+    /// Emits a conditional jump with the given index into the condition contexts
+    ///
+    fn emit_condition_jump(&mut self, context: u8) -> usize {
+        self.emit(OpCode::JumpIfFalse);
+        self.chunk.write_byte(context, self.span);
+        self.chunk.write_u16(u16::MAX, self.span);
+        self.chunk.code.len() - 2
+    }
+
+    fn patch_jump(&mut self, operand_pos: usize) {
+        let jump = self.chunk.code.len() - (operand_pos + 2);
+        let jump = self.checked_jump(jump);
+        self.chunk.patch_u16(operand_pos, jump);
+    }
+
+    fn emit_loop(&mut self, loop_start: usize) {
+        self.emit(OpCode::Loop);
+        let jump = self.chunk.code.len() + 2 - loop_start;
+        let jump = self.checked_jump(jump);
+        self.chunk.write_u16(jump, self.span);
+    }
+
+    fn checked_jump(&mut self, jump: usize) -> u16 {
+        match u16::try_from(jump) {
+            Ok(jump) => jump,
+            Err(_) => {
+                self.errors
+                    .push(CompileError::JumpTooLarge { span: self.span }.into());
+                0
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt::Display;
+
+use super::chunk::{Chunk, Constant, OpCode, CONDITION_CONTEXTS};
+
+impl Display for Chunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut offset = 0;
+        while offset < self.code.len() {
+            if let Some(idx) = self.declarations.iter().position(|start| *start == offset) {
+                writeln!(f, "== declaration {idx} ==")?;
+            }
+            offset = self.fmt_instruction(f, offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl Chunk {
+    ///
+    /// Writes the instruction at the given offset; returns the offset of the next instruction
+    ///
+    fn fmt_instruction(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        offset: usize,
+    ) -> Result<usize, std::fmt::Error> {
+        let line = self.line(offset);
+        if offset > 0 && self.line(offset - 1) == line {
+            write!(f, "{offset:04}    | ")?;
+        } else {
+            write!(f, "{offset:04} {line:>4} ")?;
+        }
+
+        let Ok(op) = OpCode::try_from(self.code[offset]) else {
+            writeln!(f, "UNKNOWN {byte}", byte = self.code[offset])?;
+            return Ok(offset + 1);
+        };
+        let name = op_name(op);
+        let next = match op {
+            OpCode::Constant | OpCode::DeclareGlobal | OpCode::SetGlobal | OpCode::GetGlobal => {
+                let idx = self.read_u16(offset + 1);
+                let constant = match &self.constants[idx as usize] {
+                    Constant::Value(value) => format!("{value}"),
+                    Constant::Identifier(iden) => iden.clone(),
+                };
+                writeln!(f, "{name:<18} {idx:>5} {constant}")?;
+                offset + 3
+            }
+            OpCode::SetLocal | OpCode::GetLocal => {
+                let slot = self.read_u16(offset + 1);
+                writeln!(f, "{name:<18} {slot:>5}")?;
+                offset + 3
+            }
+            OpCode::OrShortCircuit | OpCode::AndShortCircuit | OpCode::Jump => {
+                let jump = self.read_u16(offset + 1) as usize;
+                writeln!(
+                    f,
+                    "{name:<18} {offset:>5} -> {target}",
+                    target = offset + 3 + jump
+                )?;
+                offset + 3
+            }
+            OpCode::JumpIfFalse => {
+                let context = CONDITION_CONTEXTS[self.code[offset + 1] as usize];
+                let jump = self.read_u16(offset + 2) as usize;
+                writeln!(
+                    f,
+                    "{name:<18} {offset:>5} -> {target} ({context})",
+                    target = offset + 4 + jump
+                )?;
+                offset + 4
+            }
+            OpCode::Loop => {
+                let jump = self.read_u16(offset + 1) as usize;
+                writeln!(
+                    f,
+                    "{name:<18} {offset:>5} -> {target}",
+                    target = offset + 3 - jump
+                )?;
+                offset + 3
+            }
+            _ => {
+                writeln!(f, "{name}")?;
+                offset + 1
+            }
+        };
+        Ok(next)
+    }
+}
+
+fn op_name(op: OpCode) -> &'static str {
+    match op {
+        OpCode::Constant => "CONSTANT",
+        OpCode::Uninitialized => "UNINITIALIZED",
+        OpCode::Pop => "POP",
+        OpCode::DeclareGlobal => "DECLARE_GLOBAL",
+        OpCode::SetGlobal => "SET_GLOBAL",
+        OpCode::GetGlobal => "GET_GLOBAL",
+        OpCode::SetLocal => "SET_LOCAL",
+        OpCode::GetLocal => "GET_LOCAL",
+        OpCode::Add => "ADD",
+        OpCode::Subtract => "SUBTRACT",
+        OpCode::Multiply => "MULTIPLY",
+        OpCode::Divide => "DIVIDE",
+        OpCode::Greater => "GREATER",
+        OpCode::GreaterEqual => "GREATER_EQUAL",
+        OpCode::Less => "LESS",
+        OpCode::LessEqual => "LESS_EQUAL",
+        OpCode::Equal => "EQUAL",
+        OpCode::NotEqual => "NOT_EQUAL",
+        OpCode::Not => "NOT",
+        OpCode::Negate => "NEGATE",
+        OpCode::Or => "OR",
+        OpCode::And => "AND",
+        OpCode::OrShortCircuit => "OR_SHORT_CIRCUIT",
+        OpCode::AndShortCircuit => "AND_SHORT_CIRCUIT",
+        OpCode::JumpIfFalse => "JUMP_IF_FALSE",
+        OpCode::Jump => "JUMP",
+        OpCode::Loop => "LOOP",
+        OpCode::Print => "PRINT",
+        OpCode::SetTmp => "SET_TMP",
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    domain::{grammar::StringLiteral, location::CodeSpan},
+    interpreter::{
+        error::RuntimeError,
+        operations::{self, ComparisonOperator, EqualityOperator},
+        run_declarations, ErrorPolicy, RunStats,
+    },
+    Environment, Value,
+};
+
+use super::chunk::{Chunk, Constant, OpCode, CONDITION_CONTEXTS};
+
+///
+/// Runs the given chunk on a fresh stack. As with the tree-walking interpreter, an error only
+/// aborts the top-level declaration it occurred in, and the policy decides whether the run goes on.
+///
+pub(crate) fn run(
+    chunk: &Chunk,
+    env: &mut Environment,
+    policy: ErrorPolicy,
+) -> (RunStats, Vec<RuntimeError>) {
+    let mut vm = Vm {
+        chunk,
+        env,
+        stack: vec![],
+    };
+    run_declarations(chunk.declarations.len(), policy, |idx| {
+        let start = chunk.declarations[idx];
+        let end = chunk
+            .declarations
+            .get(idx + 1)
+            .copied()
+            .unwrap_or(chunk.code.len());
+        let result = vm.run_range(start, end);
+        vm.stack.clear();
+        result
+    })
+}
+
+struct Vm<'a> {
+    chunk: &'a Chunk,
+    env: &'a mut Environment,
+    stack: Vec<Value>,
+}
+
+impl<'a> Vm<'a> {
+    fn run_range(&mut self, start: usize, end: usize) -> Result<(), RuntimeError> {
+        let mut ip = start;
+        while ip < end {
+            self.env.safepoint(&self.stack);
+            self.env.budget().charge(|| self.chunk.span(ip))?;
+            let op = OpCode::try_from(self.chunk.code[ip]).expect("invalid op code in chunk");
+            ip += 1;
+            match op {
+                OpCode::Constant => {
+                    let Constant::Value(value) = self.constant(ip) else {
+                        unreachable!("constant op refers to an identifier")
+                    };
+                    self.stack.push(value.clone());
+                    ip += 2;
+                }
+                OpCode::Uninitialized => self.stack.push(Value::uninitialized()),
+                OpCode::Pop => {
+                    self.pop();
+                }
+                OpCode::DeclareGlobal => {
+                    self.env.declare_var(self.identifier(ip));
+                    ip += 2;
+                }
+                OpCode::SetGlobal => {
+                    let name = self.identifier(ip);
+                    let value = self.pop();
+                    if self.env.set_var_value(name, value).is_err() {
+                        let suggestion = self.chunk.suggestion(ip - 1);
+                        return Err(RuntimeError::identifier_not_defined(
+                            self.name_at(ip),
+                            suggestion,
+                            self.env,
+                        ));
+                    }
+                    ip += 2;
+                }
+                OpCode::GetGlobal => {
+                    match self.env.get_var_value(self.identifier(ip)) {
+                        Some(value) if !value.is_initialized() => {
+                            return Err(RuntimeError::uninitialized_variable(&self.name_at(ip)))
+                        }
+                        Some(value) => {
+                            let value = value.clone().at(self.chunk.span(ip - 1));
+                            self.stack.push(value);
+                        }
+                        None => {
+                            return Err(RuntimeError::identifier_not_defined(
+                                self.name_at(ip),
+                                self.chunk.suggestion(ip - 1),
+                                self.env,
+                            ))
+                        }
+                    }
+                    ip += 2;
+                }
+                OpCode::SetLocal => {
+                    let slot = self.chunk.read_u16(ip) as usize;
+                    self.stack[slot] = self.pop();
+                    ip += 2;
+                }
+                OpCode::GetLocal => {
+                    let slot = self.chunk.read_u16(ip) as usize;
+                    let span = self.chunk.span(ip - 1);
+                    if !self.stack[slot].is_initialized() {
+                        let name = StringLiteral {
+                            value: self.chunk.local_name(ip - 1).to_owned(),
+                            span,
+                        };
+                        return Err(RuntimeError::uninitialized_variable(&name));
+                    }
+                    let value = self.stack[slot].clone().at(span);
+                    self.stack.push(value);
+                    ip += 2;
+                }
+                OpCode::Add => {
+                    let right = self.pop();
+                    let left = self.pop();
+                    let value = operations::add(left, right, self.chunk.span(ip - 1), self.env)?;
+                    self.stack.push(value);
+                }
+                OpCode::Subtract => self.binary(operations::subtract, ip - 1)?,
+                OpCode::Multiply => self.binary(operations::multiply, ip - 1)?,
+                OpCode::Divide => self.binary(operations::divide, ip - 1)?,
+                OpCode::Greater => self.comparison(ComparisonOperator::Greater, ip - 1)?,
+                OpCode::GreaterEqual => {
+                    self.comparison(ComparisonOperator::GreaterEqual, ip - 1)?
+                }
+                OpCode::Less => self.comparison(ComparisonOperator::Less, ip - 1)?,
+                OpCode::LessEqual => self.comparison(ComparisonOperator::LessEqual, ip - 1)?,
+                OpCode::Equal => self.equality(EqualityOperator::Equal, ip - 1)?,
+                OpCode::NotEqual => self.equality(EqualityOperator::NotEqual, ip - 1)?,
+                OpCode::Not => {
+                    let value = operations::not(self.pop(), self.chunk.span(ip - 1))?;
+                    self.stack.push(value);
+                }
+                OpCode::Negate => {
+                    let value = operations::negate(self.pop(), self.chunk.span(ip - 1))?;
+                    self.stack.push(value);
+                }
+                OpCode::Or => self.binary(operations::or, ip - 1)?,
+                OpCode::And => self.binary(operations::and, ip - 1)?,
+                OpCode::OrShortCircuit => {
+                    let jump = self.chunk.read_u16(ip) as usize;
+                    ip += 2;
+                    if operations::or_short_circuits(self.peek()) {
+                        ip += jump;
+                    }
+                }
+                OpCode::AndShortCircuit => {
+                    let jump = self.chunk.read_u16(ip) as usize;
+                    ip += 2;
+                    if operations::and_short_circuits(self.peek()) {
+                        ip += jump;
+                    }
+                }
+                OpCode::JumpIfFalse => {
+                    let context = CONDITION_CONTEXTS[self.chunk.code[ip] as usize];
+                    let jump = self.chunk.read_u16(ip + 1) as usize;
+                    ip += 3;
+                    if !RuntimeError::unwrap_bool(self.pop(), context)? {
+                        ip += jump;
+                    }
+                }
+                OpCode::Jump => {
+                    let jump = self.chunk.read_u16(ip) as usize;
+                    ip += 2 + jump;
+                }
+                OpCode::Loop => {
+                    self.env
+                        .budget()
+                        .check_interrupt(|| self.chunk.span(ip - 1))?;
+                    let jump = self.chunk.read_u16(ip) as usize;
+                    ip = ip + 2 - jump;
+                }
+                OpCode::Print => {
+                    let value = self.pop();
+                    self.env
+                        .print(&value)
+                        .map_err(|error| RuntimeError::output(error, value.span()))?;
+                }
+                OpCode::SetTmp => {
+                    let value = self.pop();
+                    self.env.set_tmp_value(value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn constant(&self, operand_pos: usize) -> &'a Constant {
+        &self.chunk.constants[self.chunk.read_u16(operand_pos) as usize]
+    }
+
+    fn identifier(&self, operand_pos: usize) -> &'a str {
+        let Constant::Identifier(name) = self.constant(operand_pos) else {
+            unreachable!("variable op refers to a value constant")
+        };
+        name
+    }
+
+    ///
+    /// The name of the global accessed by the instruction, at the location of that access
+    ///
+    fn name_at(&self, operand_pos: usize) -> StringLiteral {
+        StringLiteral {
+            value: self.identifier(operand_pos).to_owned(),
+            span: self.chunk.span(operand_pos - 1),
+        }
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("stack underflow")
+    }
+
+    fn peek(&self) -> &Value {
+        self.stack.last().expect("stack underflow")
+    }
+
+    ///
+    /// Applies the operation to the two topmost values; the span of the operator is the one of the
+    /// op code at the given position
+    ///
+    fn binary(
+        &mut self,
+        operation: fn(Value, Value, CodeSpan) -> Result<Value, RuntimeError>,
+        op_pos: usize,
+    ) -> Result<(), RuntimeError> {
+        let right = self.pop();
+        let left = self.pop();
+        self.stack
+            .push(operation(left, right, self.chunk.span(op_pos))?);
+        Ok(())
+    }
+
+    fn comparison(
+        &mut self,
+        operator: ComparisonOperator,
+        op_pos: usize,
+    ) -> Result<(), RuntimeError> {
+        let right = self.pop();
+        let left = self.pop();
+        let span = self.chunk.span(op_pos);
+        self.stack
+            .push(operations::compare(left, right, operator, span)?);
+        Ok(())
+    }
+
+    fn equality(&mut self, operator: EqualityOperator, op_pos: usize) -> Result<(), RuntimeError> {
+        let right = self.pop();
+        let left = self.pop();
+        let span = self.chunk.span(op_pos);
+        self.stack
+            .push(operations::check_equality(left, right, operator, span)?);
+        Ok(())
+    }
+}
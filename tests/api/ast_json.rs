@@ -0,0 +1,57 @@
+use rlox::{ast_json, Interpreter, OutputBuffer};
+
+use crate::test_backend;
+
+const PROGRAM: &str = r#"
+    var total = 0;
+    for {var i = 0;} {i < 4} {i = i + 1;} {
+        if (i == 2 or i == 3) { total = total + i * 10; } else { total = total - 1; }
+    }
+    print total;
+    print "done" + "!";
+"#;
+
+fn run(program: rlox::domain::grammar::Program) -> String {
+    let output = OutputBuffer::default();
+    let mut interpreter = Interpreter::default()
+        .with_backend(test_backend())
+        .with_output(output.clone())
+        .quiet();
+    interpreter.run(program).unwrap();
+    output.contents()
+}
+
+#[test]
+fn program_read_from_json_runs_like_the_source() {
+    // Arrange
+    let json = ast_json::to_json(&rlox::parse(PROGRAM).unwrap()).unwrap();
+    // Act
+    let program = ast_json::from_json(&json).unwrap();
+    // Assert
+    assert_eq!(run(rlox::parse(PROGRAM).unwrap()), run(program));
+}
+
+#[test]
+fn json_contains_the_schema_version_and_spans() {
+    // Act
+    let json = ast_json::to_json(&rlox::parse("print x;").unwrap()).unwrap();
+    // Assert
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!("rlox-ast", value["schema"]);
+    assert_eq!(ast_json::SCHEMA_VERSION, value["version"]);
+    let variable = &value["program"][0]["expression"];
+    assert_eq!("variable", variable["kind"]);
+    assert_eq!("x", variable["name"]);
+    assert_eq!(7, variable["span"]["start"]["column"]);
+    assert_eq!(8, variable["span"]["end"]["column"]);
+}
+
+#[test]
+fn invalid_json_is_an_error() {
+    // Act
+    let result = ast_json::from_json(
+        r#"{"schema": "rlox-ast", "version": 1, "program": [{"kind": "loop"}]}"#,
+    );
+    // Assert
+    assert!(result.is_err());
+}
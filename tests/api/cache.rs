@@ -0,0 +1,99 @@
+use std::{fs, path::PathBuf};
+
+use rlox::{
+    errors::{ParseError, ScanError},
+    Interpreter, Limits, ValueType,
+};
+
+use crate::TestApp;
+
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rlox-api-cache-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn spawn_with_cache(dir: &PathBuf) -> TestApp {
+    TestApp {
+        interpreter: Interpreter::default().with_cache_dir(dir),
+    }
+}
+
+#[test]
+fn cached_program_gives_same_result() {
+    // Arrange
+    let dir = cache_dir("same-result");
+    let input = "var a = 0; for {var i = 0;} {i < 3} {i = i + 1;} { a = a + i; } a;";
+
+    // Act
+    let first = spawn_with_cache(&dir).process_input(input).unwrap();
+    let second = spawn_with_cache(&dir).process_input(input).unwrap();
+
+    // Assert
+    assert_eq!(1, fs::read_dir(&dir).unwrap().count());
+    assert_eq!(Some(ValueType::Number(3.0)), first.map(|v| v.v_type));
+    assert_eq!(Some(ValueType::Number(3.0)), second.map(|v| v.v_type));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn corrupt_entry_is_regenerated() {
+    // Arrange
+    let dir = cache_dir("corrupt");
+    let input = "1 + 2;";
+    spawn_with_cache(&dir).process_input(input).unwrap();
+    let entry = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    fs::write(&entry, b"garbage").unwrap();
+
+    // Act
+    let value = spawn_with_cache(&dir).process_input(input).unwrap();
+
+    // Assert
+    assert_eq!(Some(ValueType::Number(3.0)), value.map(|v| v.v_type));
+    assert_ne!(b"garbage".to_vec(), fs::read(&entry).unwrap());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn scan_errors_are_not_cached() {
+    // Arrange
+    let dir = cache_dir("errors");
+    let mut app = spawn_with_cache(&dir);
+
+    // Act
+    let errors = app.process_input("var a = 1 @ 2;").unwrap_err();
+
+    // Assert
+    assert!(
+        errors[0].downcast_ref::<ScanError>().is_some(),
+        "{}",
+        errors[0]
+    );
+    assert!(fs::read_dir(&dir).map(|d| d.count()).unwrap_or(0) == 0);
+}
+
+#[test]
+fn cached_programs_are_depth_checked() {
+    // Arrange
+    let dir = cache_dir("depth");
+    let input = "{{{{ 1; }}}}";
+    spawn_with_cache(&dir).process_input(input).unwrap();
+    let mut app = TestApp {
+        interpreter: Interpreter::default()
+            .with_cache_dir(&dir)
+            .with_limits(Limits {
+                max_depth: Some(3),
+                ..Default::default()
+            }),
+    };
+
+    // Act
+    let errors = app.process_input(input).unwrap_err();
+
+    // Assert
+    assert!(matches!(
+        errors[0].downcast_ref::<ParseError>(),
+        Some(ParseError::TooDeeplyNested { limit: 3, .. })
+    ));
+    fs::remove_dir_all(&dir).unwrap();
+}
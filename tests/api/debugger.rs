@@ -0,0 +1,147 @@
+use std::{cell::RefCell, io::Cursor, rc::Rc};
+
+use rlox::{Backend, ConsoleDebugger, Debugger, Interpreter, OutputBuffer, Pause};
+
+const PROGRAM: &str = r#"var a = 1;
+{
+    var b = a + 1;
+    print b;
+}
+print a;
+"#;
+
+///
+/// Runs [PROGRAM] under a console debugger which is fed the given commands; returns what the
+/// debugger and the program wrote, and the errors of the run
+///
+fn debug(commands: &str) -> (String, String, Vec<anyhow::Error>) {
+    debug_program(PROGRAM, commands)
+}
+
+fn debug_program(program: &str, commands: &str) -> (String, String, Vec<anyhow::Error>) {
+    let shown = OutputBuffer::default();
+    let printed = OutputBuffer::default();
+    let debugger = ConsoleDebugger::new(
+        Cursor::new(commands.to_owned()),
+        shown.clone(),
+        program,
+        "test.lox",
+    );
+    let mut interpreter = Interpreter::default()
+        .with_backend(Backend::TreeWalker)
+        .quiet()
+        .with_output(printed.clone())
+        .with_debugger(debugger);
+    let errors = interpreter
+        .interpret_src_str(program)
+        .err()
+        .unwrap_or_default();
+    (shown.contents(), printed.contents(), errors)
+}
+
+fn paused_lines(shown: &str) -> Vec<&str> {
+    shown
+        .lines()
+        .filter_map(|line| line.split("test.lox:").nth(1))
+        .filter_map(|line| line.split(':').next())
+        .collect()
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Rc<RefCell<Vec<(u64, usize)>>>);
+
+impl Debugger for Recorder {
+    fn before_declaration(&mut self, pause: &Pause) {
+        self.0
+            .borrow_mut()
+            .push((pause.span().start.line, pause.depth()));
+    }
+}
+
+#[test]
+fn debugger_is_called_before_each_declaration() {
+    // Arrange
+    let recorder = Recorder::default();
+    let mut interpreter = Interpreter::default()
+        .with_backend(Backend::TreeWalker)
+        .quiet()
+        .with_output(OutputBuffer::default())
+        .with_debugger(recorder.clone());
+    // Act
+    interpreter.interpret_src_str(PROGRAM).unwrap();
+    // Assert
+    assert_eq!(vec![(1, 0), (3, 1), (4, 1), (6, 0)], *recorder.0.borrow());
+}
+
+#[test]
+fn step_enters_blocks_and_next_steps_over_them() {
+    // Act
+    let (stepped, _, step_errors) = debug("s\ns\ns\ns\n");
+    let (nexted, printed, next_errors) = debug("n\nn\n");
+    // Assert
+    assert!(step_errors.is_empty());
+    assert_eq!(vec!["1", "3", "4", "6"], paused_lines(&stepped));
+    assert!(next_errors.is_empty());
+    assert_eq!(vec!["1", "6"], paused_lines(&nexted));
+    // the program runs to its end once the commands are used up
+    assert_eq!("2\n1\n", printed);
+}
+
+#[test]
+fn breakpoints_pause_the_run_to_evaluate_and_show_scopes() {
+    // Act
+    let (shown, _, errors) = debug("b 4\nc\np a + b\np c\nscopes\nc\n");
+    // Assert
+    assert!(errors.is_empty());
+    assert_eq!(vec!["1", "4"], paused_lines(&shown));
+    assert!(shown.contains("breakpoint set on line 4"), "{shown}");
+    assert!(shown.contains("(rlox) 3\n"), "{shown}");
+    assert!(shown.contains("identifier 'c'"), "{shown}");
+    assert!(
+        shown.contains("block 1:\n  b = 2\nglobals:\n  a = 1\n"),
+        "{shown}"
+    );
+}
+
+#[test]
+fn quit_stops_the_run() {
+    // Act
+    let (_, printed, errors) = debug("n\nq\n");
+    // Assert
+    // the block is run, the last statement is not
+    assert_eq!("2\n", printed);
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[test]
+fn for_loops_are_paused_at() {
+    // Arrange
+    let recorder = Recorder::default();
+    let mut interpreter = Interpreter::default()
+        .with_backend(Backend::TreeWalker)
+        .quiet()
+        .with_output(OutputBuffer::default())
+        .with_debugger(recorder.clone());
+    // Act
+    interpreter
+        .interpret_src_str("for {var i = 0;}\n {i < 2}\n {i = i + 1;}\n { print i; }\n")
+        .unwrap();
+    // Assert
+    let lines: Vec<u64> = recorder.0.borrow().iter().map(|(line, _)| *line).collect();
+    assert_eq!(vec![1, 1, 2, 4, 3, 4, 3], lines);
+}
+
+#[test]
+fn breakpoints_in_one_line_loops_are_hit_in_each_iteration() {
+    // Arrange
+    let program = "var i = 0;\nwhile i < 3 { i = i + 1; print i; }\nprint i;\n";
+
+    // Act
+    let (shown, printed, errors) = debug_program(program, "b 2\nc\nc\nc\nc\nc\n");
+
+    // Assert
+    assert!(errors.is_empty(), "{errors:?}");
+    // the loop, then the first declaration of the body in each iteration
+    assert_eq!(vec!["1", "2", "2", "2", "2"], paused_lines(&shown));
+    assert_eq!("1\n2\n3\n3\n", printed);
+}
@@ -0,0 +1,214 @@
+use rlox::{render_error, Backend};
+
+use crate::TestApp;
+
+fn rendered_errors(input: &str) -> Vec<String> {
+    let mut app = TestApp::spawn();
+    let errors = app.process_input(input).unwrap_err();
+    errors
+        .iter()
+        .map(|e| render_error(e, input, "test.lox", false))
+        .collect()
+}
+
+#[test]
+fn runtime_error_points_at_operands() {
+    // Arrange
+    let input = "var a = 1;\nvar b = a + true;";
+
+    // Act
+    let errors = rendered_errors(input);
+
+    // Assert
+    let expected = "\
+error[L0401]: operator + not defined for types Number and Boolean
+ --> test.lox:2:11
+  |
+2 | var b = a + true;
+  |           ^ operator
+  |         - left operand: Number
+  |             ---- right operand: Boolean
+";
+    assert_eq!(vec![expected], errors);
+}
+
+#[test]
+fn type_error_points_at_variable_use() {
+    // Arrange
+    let input = "var a = 1;\nif a { }";
+
+    // Act
+    let errors = rendered_errors(input);
+
+    // Assert
+    assert_eq!(1, errors.len());
+    assert!(errors[0].contains(" --> test.lox:2:4\n"), "{}", errors[0]);
+    assert!(errors[0].contains("2 | if a { }\n  |    ^ expected 'Boolean', found 'Number'\n"));
+}
+
+#[test]
+fn read_of_unassigned_variable_points_at_declaration() {
+    // Arrange
+    let input = "var a; var b = a + 1;";
+
+    // Act
+    let errors = rendered_errors(input);
+
+    // Assert
+    assert_eq!(1, errors.len());
+    assert!(
+        errors[0].contains(
+            "\
+1 | var a; var b = a + 1;
+  |                ^ read before any assignment
+  |     - declared without a value
+"
+        ),
+        "{}",
+        errors[0]
+    );
+}
+
+#[test]
+fn scan_and_parse_errors_are_rendered() {
+    // Assert
+    let scan_errors = rendered_errors("var a = 1 @ 2;");
+    assert!(scan_errors[0].contains("  |           ^ illegal character\n"));
+
+    let parse_errors = rendered_errors("var a = 1\nprint a;");
+    assert!(parse_errors[0].contains(" --> test.lox:2:1\n"));
+    assert!(parse_errors[0].contains("2 | print a;\n  | ^^^^^ expected semicolon"));
+}
+
+#[test]
+fn errors_without_diagnostic_show_message() {
+    // Arrange
+    let error = anyhow::anyhow!("something went wrong");
+
+    // Act
+    let rendered = render_error(&error, "", "test.lox", false);
+
+    // Assert
+    assert_eq!("error: something went wrong\n", rendered);
+}
+
+#[test]
+fn undefined_identifier_suggests_similar_variable() {
+    // Arrange
+    let mut app = TestApp::spawn();
+    app.process_input("var counter = 1;").unwrap();
+    let input = "print countr;";
+
+    // Act
+    let errors = app.process_input(input).unwrap_err();
+
+    // Assert
+    let rendered = render_error(&errors[0], input, "test.lox", false);
+    assert!(
+        rendered.ends_with("  = help: did you mean 'counter'?\n"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn undefined_identifier_suggests_similar_local_on_both_backends() {
+    for backend in [Backend::TreeWalker, Backend::Vm] {
+        // Arrange
+        let mut app = TestApp::spawn_with_backend(backend);
+        let input = "{ var count = 1; print coutn; }\n{ var total = 1; totl = 2; }";
+
+        // Act
+        let errors = app.process_input(input).unwrap_err();
+
+        // Assert
+        let help: Vec<_> = errors
+            .iter()
+            .map(|e| render_error(e, input, "test.lox", false))
+            .map(|rendered| rendered.lines().last().unwrap().to_owned())
+            .collect();
+        assert_eq!(
+            vec![
+                "  = help: did you mean 'count'?",
+                "  = help: did you mean 'total'?"
+            ],
+            help,
+            "{backend:?}"
+        );
+    }
+}
+
+#[test]
+fn undefined_identifier_without_similar_variable_has_no_help() {
+    // Act
+    let errors = rendered_errors("var counter = 1;\nprint total;");
+
+    // Assert
+    assert!(!errors[0].contains("help"), "{}", errors[0]);
+}
+
+#[test]
+fn misspelled_keyword_is_suggested() {
+    // Arrange
+    let input = "var x = true;\nwhlie x { x = false; }";
+
+    // Act
+    let errors = rendered_errors(input);
+
+    // Assert
+    assert_eq!(1, errors.len(), "{errors:?}");
+    assert!(
+        errors[0].ends_with(
+            "\
+2 | whlie x { x = false; }
+  |       ^ expected semicolon after statement
+  | ----- not a keyword
+  = help: did you mean the keyword 'while'?
+"
+        ),
+        "{}",
+        errors[0]
+    );
+}
+
+#[test]
+fn operator_is_underlined_when_the_operands_span_lines() {
+    // Arrange
+    let input = "var s = \"a\";\ns * (1 +\n 2);";
+
+    // Act
+    let errors = rendered_errors(input);
+
+    // Assert
+    let expected = "\
+error[L0401]: operator * not defined for types String and Number
+ --> test.lox:2:3
+  |
+2 | s * (1 +
+  |   ^ operator
+  | - left operand: String
+  |      ---
+3 |  2);
+  |  - right operand: Number
+";
+    assert_eq!(vec![expected], errors);
+}
+
+#[test]
+fn unary_operator_is_underlined_apart_from_its_operand() {
+    // Arrange
+    let input = "var s = \"a\";\nprint -  s;";
+
+    // Act
+    let errors = rendered_errors(input);
+
+    // Assert
+    let expected = "\
+error[L0402]: operator - not defined for type String
+ --> test.lox:2:10
+  |
+2 | print -  s;
+  |          ^ operand: String
+  |       - operator
+";
+    assert_eq!(vec![expected], errors);
+}
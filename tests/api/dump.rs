@@ -0,0 +1,41 @@
+use rlox::{dump, DumpFormat, DumpStage};
+
+#[test]
+fn ast_covers_statements() {
+    // Arrange
+    let src = "var a; while a != nil { a = nil; } print a;";
+
+    // Act
+    let actual = dump(src, DumpStage::Ast, DumpFormat::Text).unwrap();
+
+    // Assert
+    assert_eq!(
+        "(var a)\n(while (!= a nil)\n  (block\n    (= a nil)))\n(print a)\n",
+        actual
+    );
+}
+
+#[test]
+fn scan_errors_are_reported() {
+    // Arrange
+    let src = "var a = \"unterminated;";
+
+    // Act
+    let result = dump(src, DumpStage::Tokens, DumpFormat::Json);
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[test]
+fn ast_json_can_be_read_back() {
+    // Arrange
+    let src = "for {var i = 0;} {i < 2} {i = i + 1;} { print i; }";
+
+    // Act
+    let json = dump(src, DumpStage::Ast, DumpFormat::Json).unwrap();
+
+    // Assert
+    assert!(json.contains(r#""schema": "rlox-ast""#));
+    assert!(rlox::ast_json::from_json(&json).is_ok());
+}
@@ -0,0 +1,119 @@
+use rlox::{format_errors, render_error, ErrorFormat};
+
+use crate::TestApp;
+
+#[test]
+fn json_lines_contain_code_and_span_of_each_error() {
+    // Arrange
+    let mut app = TestApp::spawn();
+    let input = "var a = 1;\nprint a + true;\nprint -nil;";
+    let errors = app.process_input(input).unwrap_err();
+
+    // Act
+    let json = format_errors(&errors, input, "main.lox", ErrorFormat::Json);
+
+    // Assert
+    let lines: Vec<serde_json::Value> = json
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(2, lines.len());
+    assert_eq!("error", lines[0]["severity"]);
+    assert_eq!("L0401", lines[0]["code"]);
+    assert_eq!("main.lox", lines[0]["file"]);
+    assert_eq!(
+        "operator + not defined for types Number and Boolean",
+        lines[0]["message"]
+    );
+    assert_eq!(2, lines[0]["start"]["line"]);
+    assert_eq!(9, lines[0]["start"]["column"]);
+    assert_eq!(2, lines[0]["end"]["line"]);
+    assert_eq!(10, lines[0]["end"]["column"]);
+    assert_eq!("L0402", lines[1]["code"]);
+    assert_eq!(3, lines[1]["start"]["line"]);
+}
+
+#[test]
+fn json_lines_report_scan_errors() {
+    // Arrange
+    let mut app = TestApp::spawn();
+    let input = "var a = 1 # 2;";
+    let errors = app.check(input).unwrap_err();
+
+    // Act
+    let json = format_errors(&errors, input, "main.lox", ErrorFormat::Json);
+
+    // Assert
+    let line: serde_json::Value = serde_json::from_str(json.trim_end()).unwrap();
+    assert_eq!("L0001", line["code"]);
+    assert_eq!(11, line["start"]["column"]);
+}
+
+#[test]
+fn sarif_log_lists_results_and_rules() {
+    // Arrange
+    let mut app = TestApp::spawn();
+    let input = "var = 1;\nprint 1";
+    let errors = app.check(input).unwrap_err();
+
+    // Act
+    let sarif = format_errors(&errors, input, "main.lox", ErrorFormat::Sarif);
+
+    // Assert
+    let log: serde_json::Value = serde_json::from_str(&sarif).unwrap();
+    assert_eq!("2.1.0", log["version"]);
+    let run = &log["runs"][0];
+    assert_eq!("rlox", run["tool"]["driver"]["name"]);
+    assert_eq!("L0101", run["tool"]["driver"]["rules"][0]["id"]);
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(2, results.len());
+    assert_eq!("L0101", results[0]["ruleId"]);
+    assert_eq!("error", results[0]["level"]);
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!("main.lox", location["artifactLocation"]["uri"]);
+    assert_eq!(1, location["region"]["startLine"]);
+    assert_eq!(5, location["region"]["startColumn"]);
+}
+
+#[test]
+fn sarif_log_without_errors_has_empty_results() {
+    // Act
+    let sarif = format_errors(&[], "", "main.lox", ErrorFormat::Sarif);
+
+    // Assert
+    let log: serde_json::Value = serde_json::from_str(&sarif).unwrap();
+    assert_eq!(0, log["runs"][0]["results"].as_array().unwrap().len());
+}
+
+#[test]
+fn human_format_renders_each_error_with_its_snippet() {
+    // Arrange
+    let mut app = TestApp::spawn();
+    let input = "var a = 1;\nprint a + true;\nprint -nil;";
+    let errors = app.process_input(input).unwrap_err();
+
+    // Act
+    let human = format_errors(&errors, input, "main.lox", ErrorFormat::Human);
+
+    // Assert
+    let expected: String = errors
+        .iter()
+        .map(|e| format!("{}\n", render_error(e, input, "main.lox", false)))
+        .collect();
+    assert_eq!(expected, human);
+    assert!(human.contains(" --> main.lox:2:9\n"), "{human}");
+    assert!(human.contains(" --> main.lox:3:8\n"), "{human}");
+}
+
+#[test]
+fn check_does_not_run_the_program() {
+    // Arrange
+    let mut app = TestApp::spawn();
+
+    // Act
+    let result = app.check("var a = 1; print a + true;");
+
+    // Assert
+    assert!(result.is_ok());
+    assert!(app.interpreter_state().get_var_value("a").is_none());
+}
@@ -0,0 +1,159 @@
+use rlox::{
+    interpret_lox_file, ErrorFormat, ErrorPolicy, Interpreter, OnError, OutputBuffer, RunStats,
+};
+
+use crate::test_backend;
+
+const PROGRAM: &str = "print 1; print -true; print 2; print -nil; print 3;";
+
+fn interpreter(policy: ErrorPolicy, output: &OutputBuffer) -> Interpreter {
+    Interpreter::default()
+        .with_backend(test_backend())
+        .with_error_policy(policy)
+        .with_output(output.clone())
+        .quiet()
+}
+
+#[test]
+fn all_declarations_run_by_default() {
+    // Arrange
+    let output = OutputBuffer::default();
+    let mut interpreter = interpreter(ErrorPolicy::default(), &output);
+
+    // Act
+    let errors = interpreter.interpret_src_str(PROGRAM).unwrap_err();
+
+    // Assert
+    assert_eq!(2, errors.len());
+    assert_eq!("1\n2\n3\n", output.contents());
+    assert_eq!(
+        Some(RunStats {
+            declarations: 5,
+            declarations_run: 5,
+            declarations_failed: 2,
+        }),
+        interpreter.run_stats()
+    );
+}
+
+#[test]
+fn stop_policy_stops_at_the_first_error() {
+    // Arrange
+    let output = OutputBuffer::default();
+    let policy = ErrorPolicy {
+        on_error: OnError::Stop,
+        ..Default::default()
+    };
+    let mut interpreter = interpreter(policy, &output);
+
+    // Act
+    let errors = interpreter.interpret_src_str(PROGRAM).unwrap_err();
+
+    // Assert
+    assert_eq!(1, errors.len());
+    assert_eq!("1\n", output.contents());
+    assert_eq!(2, interpreter.run_stats().unwrap().declarations_run);
+}
+
+#[test]
+fn run_stops_after_the_maximum_number_of_errors() {
+    // Arrange
+    let output = OutputBuffer::default();
+    let policy = ErrorPolicy {
+        on_error: OnError::Continue,
+        max_errors: Some(2),
+    };
+    let mut interpreter = interpreter(policy, &output);
+
+    // Act
+    let errors = interpreter.interpret_src_str(PROGRAM).unwrap_err();
+
+    // Assert
+    assert_eq!(2, errors.len());
+    assert_eq!("1\n2\n", output.contents());
+    assert_eq!(4, interpreter.run_stats().unwrap().declarations_run);
+}
+
+#[test]
+fn nothing_runs_when_parsing_fails() {
+    // Arrange
+    let output = OutputBuffer::default();
+    let mut interpreter = interpreter(ErrorPolicy::default(), &output);
+    interpreter.interpret_src_str("print 1;").unwrap();
+
+    // Act
+    interpreter.interpret_src_str("print 1; print").unwrap_err();
+
+    // Assert
+    assert_eq!(None, interpreter.run_stats());
+}
+
+///
+/// Runs the source as a file and returns the diagnostic messages
+///
+fn file_diagnostics(name: &str, source: &str) -> String {
+    let path = std::env::temp_dir().join(format!("rlox-api-{name}-{}.lox", std::process::id()));
+    std::fs::write(&path, source).unwrap();
+    let diagnostics = OutputBuffer::default();
+    let interpreter = Interpreter::default()
+        .with_backend(test_backend())
+        .with_output(OutputBuffer::default())
+        .with_diagnostics(diagnostics.clone());
+    let _ = interpret_lox_file(path.to_str().unwrap(), interpreter, ErrorFormat::Human);
+    std::fs::remove_file(&path).unwrap();
+    diagnostics.contents()
+}
+
+#[test]
+fn file_reports_the_declarations_run() {
+    // Act
+    let diagnostics = file_diagnostics("declarations-run", "print 1; print 2;");
+
+    // Assert
+    assert!(
+        diagnostics.contains("ran 2 of 2 top-level declarations"),
+        "{diagnostics}"
+    );
+}
+
+#[test]
+fn file_reports_the_failed_declarations() {
+    // Act
+    let diagnostics = file_diagnostics("declarations-failed", PROGRAM);
+
+    // Assert
+    assert!(
+        diagnostics.contains("ran 5 of 5 top-level declarations, 2 of which failed"),
+        "{diagnostics}"
+    );
+}
+
+#[test]
+fn file_with_syntax_error_reports_no_declarations_run() {
+    // Act
+    let diagnostics = file_diagnostics("syntax-error", "print 1; print");
+
+    // Assert
+    assert!(
+        !diagnostics.contains("top-level declarations"),
+        "{diagnostics}"
+    );
+}
+
+#[test]
+fn failed_initializer_leaves_the_variable_without_value() {
+    // Arrange
+    let output = OutputBuffer::default();
+    let mut interpreter = interpreter(ErrorPolicy::default(), &output);
+
+    // Act
+    let errors = interpreter
+        .interpret_src_str("var a = -true; a = 1; print a; var b = -nil; print b;")
+        .unwrap_err();
+
+    // Assert
+    assert_eq!("1\n", output.contents());
+    let messages: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(3, messages.len(), "{messages:?}");
+    assert!(messages[2].contains("'b'"), "{messages:?}");
+}
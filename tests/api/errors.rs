@@ -0,0 +1,101 @@
+use rlox::{
+    domain::scanning::TokenType,
+    errors::{error_code, explain, ParseError, ResolveError, RuntimeError, ScanError},
+};
+
+use crate::TestApp;
+
+fn errors(input: &str) -> Vec<anyhow::Error> {
+    let mut app = TestApp::spawn();
+    app.process_input(input).unwrap_err()
+}
+
+#[test]
+fn scan_errors_are_typed() {
+    // Act
+    let errors = errors("var a = 1 # 2;");
+
+    // Assert
+    let error = errors[0].downcast_ref::<ScanError>().unwrap();
+    assert!(matches!(
+        error,
+        ScanError::IllegalCharacter { character: '#', .. }
+    ));
+    assert_eq!("L0001", error.code());
+    assert_eq!(11, error.span().start.column);
+}
+
+#[test]
+fn parse_errors_carry_expected_and_found() {
+    // Act
+    let errors = errors("var a = 1 print a;");
+
+    // Assert
+    let Some(ParseError::UnexpectedToken {
+        expected, found, ..
+    }) = errors[0].downcast_ref::<ParseError>()
+    else {
+        panic!("not an unexpected token error: {:?}", errors[0]);
+    };
+    assert_eq!(&vec!["';'".to_owned()], expected);
+    assert_eq!(&TokenType::PRINT, found);
+    assert_eq!(Some("L0101"), error_code(&errors[0]));
+}
+
+#[test]
+fn resolve_errors_are_typed() {
+    // Act
+    let errors = errors("{ var a = 1; var a = 2; }");
+
+    // Assert
+    let error = errors[0].downcast_ref::<ResolveError>().unwrap();
+    assert!(matches!(error, ResolveError::AlreadyDeclared { name, .. } if name == "a"));
+    assert_eq!("L0201", error.code());
+}
+
+#[test]
+fn runtime_errors_are_typed() {
+    // Act
+    let errors = errors("var a = -true;\nif 1 { }\nprint b;");
+
+    // Assert
+    let codes: Vec<_> = errors.iter().filter_map(error_code).collect();
+    assert_eq!(vec!["L0402", "L0404", "L0403"], codes);
+    let RuntimeError::TypeError(error) = errors[1].downcast_ref::<RuntimeError>().unwrap() else {
+        panic!("not a type error: {:?}", errors[1]);
+    };
+    assert_eq!("Boolean", error.expected);
+    assert_eq!("Number", error.found);
+    assert_eq!(2, error.span.start.line);
+    assert_eq!(
+        "type error - expected: 'Boolean'; found: 'Number'; context: if condition",
+        errors[1].to_string()
+    );
+}
+
+#[test]
+fn error_codes_are_explained() {
+    // Act
+    let errors = errors("var a = \"unterminated");
+
+    // Assert
+    let code = error_code(&errors[0]).unwrap();
+    assert!(explain(code).unwrap().starts_with("Unterminated string."));
+}
+
+#[test]
+fn suggestions_are_part_of_the_typed_errors() {
+    // Act
+    let errors = errors("var total = 1;\nprnt total;\nprint totl;");
+
+    // Assert
+    let Some(ParseError::UnexpectedToken {
+        misspelled_keyword: Some(misspelled),
+        ..
+    }) = errors[0].downcast_ref::<ParseError>()
+    else {
+        panic!("no keyword suggested: {:?}", errors[0]);
+    };
+    assert_eq!("print", misspelled.keyword);
+    assert_eq!(2, misspelled.span.start.line);
+}
@@ -0,0 +1,64 @@
+use rlox::{format_source, Interpreter, OutputBuffer};
+
+use crate::test_backend;
+
+const MESSY: &str = r#"
+// counts down
+var n=3;    // start
+while (n>0){print n;n=n-1;}
+
+
+for{var i=0;}{i<2}{i=i+1;}{ if (i==0) { print "zero" ; } else {print -i;} }
+// done
+"#;
+
+fn output_of(src: &str) -> String {
+    let output = OutputBuffer::default();
+    let mut interpreter = Interpreter::default()
+        .with_backend(test_backend())
+        .with_output(output.clone())
+        .quiet();
+    interpreter.interpret_src_str(src).unwrap();
+    output.contents()
+}
+
+#[test]
+fn formatted_program_behaves_the_same() {
+    // Act
+    let formatted = format_source(MESSY).unwrap();
+    // Assert
+    assert_eq!(output_of(MESSY), output_of(&formatted));
+}
+
+#[test]
+fn formatting_is_canonical() {
+    // Act
+    let formatted = format_source(MESSY).unwrap();
+    // Assert
+    let expected = r#"// counts down
+var n = 3; // start
+while (n > 0) {
+    print n;
+    n = n - 1;
+}
+
+for {var i = 0;} {i < 2} {i = i + 1;} {
+    if (i == 0) {
+        print "zero";
+    } else {
+        print -i;
+    }
+}
+// done
+"#;
+    assert_eq!(expected, formatted);
+    assert_eq!(expected, format_source(&formatted).unwrap());
+}
+
+#[test]
+fn syntax_errors_are_reported() {
+    // Act
+    let errors = format_source("print (1;").unwrap_err();
+    // Assert
+    assert_eq!(1, errors.len());
+}
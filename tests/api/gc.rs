@@ -0,0 +1,71 @@
+use rlox::{Interpreter, ValueType};
+
+use crate::{test_backend, TestApp};
+
+fn spawn_with_gc_stress() -> TestApp {
+    TestApp {
+        interpreter: Interpreter::default()
+            .with_backend(test_backend())
+            .with_gc_stress(true),
+    }
+}
+
+const CONCATENATION_LOOP: &str = r#"
+    var s = "";
+    for {var i = 0;} {i < 5} {i = i + 1;} {
+        var piece = "ab";
+        s = s + piece;
+    }
+    s;
+"#;
+
+#[test]
+fn stress_mode_keeps_reachable_values() {
+    // Arrange
+    let mut app = spawn_with_gc_stress();
+
+    // Act
+    let value = app.process_input(CONCATENATION_LOOP).unwrap();
+
+    // Assert
+    assert_eq!(
+        Some(ValueType::string("ababababab")),
+        value.map(|v| v.v_type)
+    );
+    let s = app.interpreter_state().get_var_value("s").unwrap();
+    assert_eq!(ValueType::string("ababababab"), s.v_type);
+}
+
+#[test]
+fn stress_mode_collects_unreachable_values() {
+    // Arrange
+    let mut app = spawn_with_gc_stress();
+
+    // Act
+    app.process_input(CONCATENATION_LOOP).unwrap();
+
+    // Assert
+    let stats = app.interpreter.gc_stats();
+    assert!(stats.collections > 0);
+    assert!(stats.freed > 0);
+    assert!(stats.freed <= stats.released);
+    assert_eq!(stats.allocations - stats.released, stats.live_objects);
+}
+
+#[test]
+fn collection_keeps_globals() {
+    // Arrange
+    let mut app = TestApp::spawn();
+    app.process_input(r#"var a = "x" + "y"; "tmp";"#).unwrap();
+    app.process_input("1;").unwrap();
+
+    // Act
+    app.interpreter.collect_garbage();
+
+    // Assert
+    let stats = app.interpreter.gc_stats();
+    assert_eq!(1, stats.collections);
+    assert_eq!(1, stats.live_objects);
+    let a = app.interpreter_state().get_var_value("a").unwrap();
+    assert_eq!(ValueType::string("xy"), a.v_type);
+}
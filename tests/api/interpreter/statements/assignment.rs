@@ -59,3 +59,47 @@ fn no_assignment_before_declaration() {
     // Assert - check that variable is present and its value is correct
     assert!(result.is_err(), "assignment before declaration should fail");
 }
+
+#[test]
+fn reading_unassigned_var_is_reported_before_running() {
+    // Arrange
+    let mut test_app = TestApp::spawn();
+
+    // Act
+    let errors = test_app.process_input("var a; print a;").unwrap_err();
+
+    // Assert
+    assert_eq!(1, errors.len());
+    assert_eq!(Some("L0204"), rlox::errors::error_code(&errors[0]));
+}
+
+#[test]
+fn reading_var_unassigned_on_the_taken_path_fails_at_runtime() {
+    // Arrange
+    let mut test_app = TestApp::spawn();
+
+    // Act
+    let errors = test_app
+        .process_input("var a; if false { a = 1; } { var b; if false { b = 1; } print b; } a;")
+        .unwrap_err();
+
+    // Assert
+    let codes: Vec<_> = errors.iter().filter_map(rlox::errors::error_code).collect();
+    assert_eq!(vec!["L0408", "L0408"], codes);
+}
+
+#[test]
+fn reading_var_of_failed_initializer_fails_at_runtime() {
+    // Arrange
+    let mut test_app = TestApp::spawn();
+    test_app.process_input("var a = -nil;").unwrap_err();
+
+    // Act
+    let errors = test_app.process_input("a;").unwrap_err();
+
+    // Assert
+    assert_eq!(Some("L0408"), rlox::errors::error_code(&errors[0]));
+    assert!(errors[0]
+        .to_string()
+        .contains("variable 'a' is read before a value is assigned to it"));
+}
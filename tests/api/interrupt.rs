@@ -0,0 +1,117 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use rlox::{Interpreter, InterruptHandle, Limits, ValueType};
+
+use crate::{test_backend, TestApp};
+
+///
+/// Spawns an app whose runs are stopped by a fuel limit, so that a test cannot hang if an
+/// interrupt gets lost
+///
+fn spawn_with_fuel_backstop() -> TestApp {
+    TestApp {
+        interpreter: Interpreter::default()
+            .with_backend(test_backend())
+            .with_limits(Limits {
+                fuel: Some(100_000_000),
+                ..Limits::default()
+            }),
+    }
+}
+
+///
+/// Interrupts from another thread until it is stopped. The interrupt is repeated, as interrupts
+/// which arrive before a run starts are discarded.
+///
+struct Interrupter {
+    stopped: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl Interrupter {
+    fn start(handle: InterruptHandle) -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stop_requested = stopped.clone();
+        let thread = thread::spawn(move || {
+            while !stop_requested.load(Ordering::Relaxed) {
+                handle.interrupt();
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+        Self { stopped, thread }
+    }
+
+    fn stop(self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        self.thread.join().unwrap();
+    }
+}
+
+#[test]
+fn interrupt_stops_infinite_loop() {
+    // Arrange
+    let mut app = spawn_with_fuel_backstop();
+    app.process_input("var a = 1;").unwrap();
+    let interrupter = Interrupter::start(app.interpreter.interrupt_handle());
+
+    // Act
+    let result = app.process_input("var b = 0; { while true { b = b + 1; } } var c = 1;");
+    interrupter.stop();
+
+    // Assert
+    let errors = result.unwrap_err();
+    assert_eq!(1, errors.len());
+    assert!(
+        errors[0].to_string().contains("execution interrupted"),
+        "{}",
+        errors[0]
+    );
+    let state = app.interpreter_state();
+    assert_eq!(
+        ValueType::Number(1.0),
+        state.get_var_value("a").unwrap().v_type
+    );
+    assert!(state.get_var_value("c").is_none());
+}
+
+#[test]
+fn interpreter_is_usable_after_interrupt() {
+    // Arrange
+    let mut app = spawn_with_fuel_backstop();
+    let interrupter = Interrupter::start(app.interpreter.interrupt_handle());
+    let errors = app
+        .process_input("var a = 0; while true { a = a + 1; }")
+        .unwrap_err();
+    interrupter.stop();
+    assert!(
+        errors[0].to_string().contains("interrupted"),
+        "{}",
+        errors[0]
+    );
+
+    // Act
+    let value = app.process_input("{ var b = 2; a = b; } a;");
+
+    // Assert
+    assert_eq!(ValueType::Number(2.0), value.unwrap().unwrap().v_type);
+}
+
+#[test]
+fn interrupt_before_run_is_discarded() {
+    // Arrange
+    let mut app = TestApp::spawn();
+    app.interpreter.interrupt_handle().interrupt();
+
+    // Act
+    let value = app.process_input("var a = 0; while a < 3 { a = a + 1; } a;");
+
+    // Assert
+    assert_eq!(ValueType::Number(3.0), value.unwrap().unwrap().v_type);
+}
@@ -0,0 +1,108 @@
+use rlox::{
+    domain::{
+        grammar::{Declaration, Statement, VarDeclaration},
+        scanning::TokenType,
+    },
+    dump, DumpFormat, DumpStage, OptLevel, ValueType,
+};
+
+use crate::TestApp;
+
+#[test]
+fn scan_returns_the_tokens_of_the_source() {
+    // Act
+    let tokens = rlox::scan("var a = 1;").unwrap();
+    // Assert
+    let types: Vec<_> = tokens.iter().map(|token| token.t_type().clone()).collect();
+    assert_eq!(
+        vec![
+            TokenType::VAR,
+            TokenType::Identifier("a".into()),
+            TokenType::Equal,
+            TokenType::Number(1.0),
+            TokenType::Semicolon,
+            TokenType::EOF
+        ],
+        types
+    );
+}
+
+#[test]
+fn scan_returns_all_errors() {
+    // Act
+    let errors = rlox::scan("a @ b # c").unwrap_err();
+    // Assert
+    assert_eq!(2, errors.len());
+}
+
+#[test]
+fn parsing_tokens_and_source_gives_the_same_program() {
+    // Arrange
+    let src = "var a = 1; if (a < 2) { print a; }";
+    let tokens = rlox::scan(src).unwrap();
+    // Act
+    let from_tokens = rlox::parse(tokens.as_slice()).unwrap();
+    let from_source = rlox::parse(src).unwrap();
+    // Assert
+    assert_eq!(from_source.to_string(), from_tokens.to_string());
+}
+
+#[test]
+fn parsed_program_exposes_its_nodes() {
+    // Act
+    let program =
+        rlox::parse("var answer = 42; while (answer > 0) { answer = answer - 1; }").unwrap();
+    // Assert
+    assert_eq!(2, program.len());
+    let Declaration::Declaration(VarDeclaration::DeclareAndAssign(name, init)) = &program[0] else {
+        panic!("expected a variable declaration, got {:?}", program[0]);
+    };
+    assert_eq!("answer", name.value());
+    assert_eq!(1, name.span().start.line);
+    assert_eq!("42", init.to_string());
+    let Declaration::Statement(Statement::While(while_loop), _) = &program[1] else {
+        panic!("expected a while loop, got {:?}", program[1]);
+    };
+    assert_eq!("(group (> answer 0))", while_loop.condition().to_string());
+}
+
+#[test]
+fn parsed_program_can_be_run() {
+    // Arrange
+    let mut test_app = TestApp::spawn();
+    let program = rlox::parse("var a = 20; a = a + 1; a * 2;").unwrap();
+    // Act
+    let output = test_app.run(program);
+    // Assert
+    assert_eq!(
+        Some(ValueType::Number(42.0)),
+        output.unwrap().map(|v| v.v_type)
+    );
+}
+
+#[test]
+fn deserialized_for_loops_are_desugared_before_running() {
+    // Arrange
+    let src = "var a = 0; for {var i = 0;} {i < 3} {i = i + 1;} { for {var j = 0;} {j < 2} {j = j + 1;} { a = a + 1; } } a;";
+    let json = dump(src, DumpStage::Ast, DumpFormat::Json).unwrap();
+    for opt_level in [OptLevel::O0, OptLevel::O1] {
+        let mut test_app = TestApp::spawn_with_opt_level(opt_level);
+        let program = rlox::ast_json::from_json(&json).unwrap();
+        // Act
+        let output = test_app.run(program).unwrap().unwrap();
+        // Assert
+        assert_eq!(ValueType::Number(6.0), output.v_type);
+    }
+}
+
+#[test]
+fn runs_share_the_state_of_the_interpreter() {
+    // Arrange
+    let mut test_app = TestApp::spawn();
+    test_app.process_input("var a = 1;").unwrap();
+    let program = rlox::parse("a + 1;").unwrap();
+    // Act
+    let output = test_app.run(program).unwrap().unwrap();
+    // Assert
+    assert_eq!(ValueType::Number(2.0), output.v_type);
+}
@@ -0,0 +1,291 @@
+use rlox::{
+    errors::{ParseError, RuntimeError},
+    render_error, Backend, Interpreter, Limits, OptLevel, ValueType,
+};
+
+use crate::{test_backend, TestApp};
+
+fn spawn_with_limits(limits: Limits) -> TestApp {
+    TestApp {
+        interpreter: Interpreter::default()
+            .with_backend(test_backend())
+            .with_limits(limits),
+    }
+}
+
+#[test]
+fn infinite_loop_runs_out_of_fuel() {
+    // Arrange
+    let mut app = spawn_with_limits(Limits {
+        fuel: Some(1000),
+        ..Default::default()
+    });
+    let input = "var a = 0;\nwhile true {\n a = a + 1;\n}\nvar b = 1;";
+
+    // Act
+    let errors = app.process_input(input).unwrap_err();
+
+    // Assert
+    assert_eq!(1, errors.len());
+    let msg = errors[0].to_string();
+    assert!(msg.contains("fuel of 1000 steps"), "{msg}");
+    assert!(app.interpreter_state().get_var_value("b").is_none());
+}
+
+#[test]
+fn infinite_for_loop_runs_out_of_fuel() {
+    // Arrange
+    let mut app = spawn_with_limits(Limits {
+        fuel: Some(1000),
+        ..Default::default()
+    });
+
+    // Act
+    let errors = app.process_input("for {} {true} {} {}").unwrap_err();
+
+    // Assert
+    assert_eq!(1, errors.len());
+    let msg = errors[0].to_string();
+    assert!(msg.contains("fuel of 1000 steps"), "{msg}");
+}
+
+#[test]
+fn environment_is_usable_after_limit() {
+    // Arrange
+    let mut app = spawn_with_limits(Limits {
+        fuel: Some(100),
+        ..Default::default()
+    });
+    app.process_input("var a = 0; { var b = 1; while true { a = a + b; } }")
+        .unwrap_err();
+
+    // Act
+    let value = app.process_input("a = 1; { var c = a + 1; a = c; } a;");
+
+    // Assert
+    let value = value.unwrap().unwrap();
+    assert_eq!(ValueType::Number(2.0), value.v_type);
+}
+
+#[test]
+fn nesting_depth_is_limited() {
+    // Arrange
+    let mut app = TestApp {
+        interpreter: Interpreter::default()
+            .with_backend(Backend::TreeWalker)
+            .with_limits(Limits {
+                max_depth: Some(3),
+                ..Default::default()
+            }),
+    };
+
+    // Act
+    let shallow = app.process_input("{ var a = 1; }");
+    let deep = app.process_input("{ { { var a = ((1)); } } }");
+
+    // Assert
+    assert!(shallow.is_ok());
+    let errors = deep.unwrap_err();
+    assert!(errors[0].to_string().contains("nesting depth of 3"));
+}
+
+#[test]
+fn string_length_is_limited() {
+    // Arrange
+    let mut app = spawn_with_limits(Limits {
+        max_string_len: Some(8),
+        ..Default::default()
+    });
+
+    // Act
+    let short = app.process_input(r#""abcd" + "efgh";"#);
+    let long = app.process_input(r#"var s = "abcd"; while true { s = s + s; }"#);
+
+    // Assert
+    assert!(short.is_ok());
+    let errors = long.unwrap_err();
+    assert!(errors[0].to_string().contains("string length of 8 bytes"));
+}
+
+#[test]
+fn unlimited_by_default() {
+    // Arrange
+    let mut app = TestApp::spawn();
+
+    // Act
+    let value = app.process_input("var a = 0; while a < 10000 { a = a + 1; } a;");
+
+    // Assert
+    assert_eq!(ValueType::Number(10000.0), value.unwrap().unwrap().v_type);
+}
+
+fn nesting_error(errors: &[anyhow::Error]) -> Option<usize> {
+    match errors[0].downcast_ref::<ParseError>() {
+        Some(ParseError::TooDeeplyNested { limit, .. }) => Some(*limit),
+        _ => None,
+    }
+}
+
+///
+/// Runs the test on a thread with the stack size of the main thread: debug builds need several
+/// times the stack of release builds per nesting level
+///
+fn with_main_thread_stack(test: impl FnOnce() + Send + 'static) {
+    std::thread::Builder::new()
+        .stack_size(8 << 20)
+        .spawn(test)
+        .expect("failed to spawn the test thread")
+        .join()
+        .expect("the test failed");
+}
+
+#[test]
+fn deeply_nested_groupings_are_rejected_while_parsing() {
+    with_main_thread_stack(|| {
+        // Arrange
+        let mut app = TestApp::spawn();
+        let input = format!("print {}1{};", "(".repeat(500), ")".repeat(500));
+
+        // Act
+        let errors = app.process_input(&input).unwrap_err();
+
+        // Assert
+        assert_eq!(1, errors.len());
+        assert_eq!(Some(128), nesting_error(&errors));
+    });
+}
+
+#[test]
+fn long_operator_chains_are_rejected_while_parsing() {
+    with_main_thread_stack(|| {
+        // Arrange
+        let mut app = TestApp::spawn();
+        let input = format!("print 1{};", "+1".repeat(2000));
+
+        // Act
+        let errors = app.check(&input).unwrap_err();
+
+        // Assert
+        assert_eq!(1, errors.len());
+        assert!(matches!(
+            errors[0].downcast_ref::<ParseError>(),
+            Some(ParseError::ChainTooLong { limit: 1024, .. })
+        ));
+        assert!(rlox::parse(input.as_str()).is_err());
+    });
+}
+
+#[test]
+fn flat_operator_chains_do_not_count_as_nesting() {
+    with_main_thread_stack(|| {
+        // Arrange
+        let mut app = spawn_with_limits(Limits {
+            max_depth: Some(3),
+            ..Default::default()
+        });
+        let input = format!("1{};", " + 1".repeat(500));
+
+        // Act
+        let value = app.process_input(&input);
+
+        // Assert
+        assert_eq!(ValueType::Number(501.0), value.unwrap().unwrap().v_type);
+    });
+}
+
+#[test]
+fn max_depth_above_the_default_is_honoured() {
+    with_main_thread_stack(|| {
+        // Arrange
+        let mut app = spawn_with_limits(Limits {
+            max_depth: Some(200),
+            ..Default::default()
+        });
+        let input = format!("{}var a = 1;{}", "{".repeat(150), "}".repeat(150));
+
+        // Act
+        let value = app.process_input(&input);
+
+        // Assert
+        assert!(value.is_ok());
+    });
+}
+
+#[test]
+fn deeply_nested_blocks_are_reported_once() {
+    with_main_thread_stack(|| {
+        // Arrange
+        let mut app = TestApp::spawn();
+        let input = format!("{}var a = 1;{}", "{".repeat(8000), "}".repeat(8000));
+
+        // Act
+        let errors = app.check(&input).unwrap_err();
+
+        // Assert
+        assert_eq!(1, errors.len());
+        assert_eq!(Some(128), nesting_error(&errors));
+    });
+}
+
+#[test]
+fn nesting_up_to_the_parser_limit_runs_on_all_passes() {
+    with_main_thread_stack(|| {
+        // Arrange
+        let chain = format!("var a = 1{};", "+1".repeat(127));
+        let groupings = format!("var b = {}1{};", "(-".repeat(63), ")".repeat(63));
+        let blocks = format!("{}a = a + 1;{}", "{".repeat(127), "}".repeat(127));
+        let input = format!("{chain}\n{groupings}\n{blocks}\na + b;");
+
+        for backend in [Backend::TreeWalker, Backend::Vm] {
+            let mut app = TestApp {
+                interpreter: Interpreter::default()
+                    .with_backend(backend)
+                    .with_opt_level(OptLevel::O1),
+            };
+
+            // Act
+            let value = app.process_input(&input);
+
+            // Assert
+            assert_eq!(ValueType::Number(128.0), value.unwrap().unwrap().v_type);
+        }
+    });
+}
+
+#[test]
+fn empty_nested_blocks_are_depth_checked() {
+    // Arrange
+    let mut app = spawn_with_limits(Limits {
+        max_depth: Some(3),
+        ..Default::default()
+    });
+
+    // Act
+    let shallow = app.process_input("{{{}}}");
+    let deep = app.process_input("{{{{}}}}");
+
+    // Assert
+    assert!(shallow.is_ok());
+    let errors = deep.unwrap_err();
+    assert_eq!(Some(3), nesting_error(&errors));
+    assert!(errors[0].to_string().contains("nesting depth of 3"));
+}
+
+#[test]
+fn errors_on_long_lines_are_reported() {
+    // Arrange
+    let mut app = TestApp::spawn();
+    let input = format!("var a = 1;\nprint \"{}\" + true;", "a".repeat(70_000));
+
+    // Act
+    let errors = app.process_input(&input).unwrap_err();
+
+    // Assert
+    let error = errors[0].downcast_ref::<RuntimeError>().unwrap();
+    assert_eq!(
+        (2, u16::MAX),
+        (error.span().start.line, error.span().start.column)
+    );
+    let rendered = render_error(&errors[0], &input, "main.lox", false);
+    assert!(rendered.contains(" --> main.lox:2:65535\n"), "{rendered}");
+}
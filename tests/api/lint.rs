@@ -0,0 +1,67 @@
+use rlox::{lint_source, LintConfig, Rule, Severity};
+
+const PROGRAM: &str = r#"
+var limit = 3;
+var unused = "x";
+{
+    var limit = 10;
+    print limit;
+}
+var i = 0;
+while (i < limit) {
+    print i;
+}
+if ("yes") {
+    print 1;
+}
+while (true) {}
+"#;
+
+fn rules(lints: &[rlox::Lint]) -> Vec<Rule> {
+    lints.iter().map(|lint| lint.rule()).collect()
+}
+
+#[test]
+fn lint_reports_every_rule_in_source_order() {
+    // Act
+    let lints = lint_source(PROGRAM, &LintConfig::default()).unwrap();
+    // Assert
+    let expected = vec![
+        Rule::UnusedVariable,
+        Rule::Shadowing,
+        Rule::UnmodifiedLoopCondition,
+        Rule::NonBooleanCondition,
+        Rule::ConstantCondition,
+        Rule::EmptyBlock,
+    ];
+    assert_eq!(expected, rules(&lints));
+    assert!(lints
+        .iter()
+        .all(|lint| lint.diagnostic().severity() == Severity::Warning));
+}
+
+#[test]
+fn lint_rules_can_be_allowed_per_file_and_globally() {
+    // Arrange
+    let source = format!("// lint: allow(constant-condition, empty-block)\n{PROGRAM}");
+    let config = LintConfig::default().allow(Rule::Shadowing);
+    // Act
+    let lints = lint_source(&source, &config).unwrap();
+    // Assert
+    let expected = vec![
+        Rule::UnusedVariable,
+        Rule::UnmodifiedLoopCondition,
+        Rule::NonBooleanCondition,
+    ];
+    assert_eq!(expected, rules(&lints));
+}
+
+#[test]
+fn lint_reports_static_errors_instead_of_lints() {
+    // Act
+    let errors = lint_source("{ var a = 1; var a = a; }", &LintConfig::default()).unwrap_err();
+    // Assert
+    assert_eq!(1, errors.len());
+    let resolve_error = errors[0].downcast_ref::<rlox::errors::ResolveError>();
+    assert!(resolve_error.is_some());
+}
@@ -0,0 +1,290 @@
+use std::thread::JoinHandle;
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response, ResponseError};
+use serde_json::{json, Value};
+
+const URI: &str = "file:///test.lox";
+
+const SOURCE: &str = r#"// counts
+var count = 1;
+{
+    var count = "inner";
+    print count;
+}
+count = count + 1;
+"#;
+
+///
+/// A client talking to a server which runs in a thread of the test, over an in-memory connection
+///
+struct TestClient {
+    connection: Connection,
+    server: Option<JoinHandle<anyhow::Result<()>>>,
+    next_id: i32,
+}
+
+impl TestClient {
+    fn start() -> Self {
+        let (server, connection) = Connection::memory();
+        let server = std::thread::spawn(move || rlox::lsp::serve(server));
+        let mut client = Self {
+            connection,
+            server: Some(server),
+            next_id: 0,
+        };
+        let capabilities = client.request(
+            "initialize",
+            json!({ "processId": null, "rootUri": null, "capabilities": {} }),
+        );
+        assert!(capabilities["capabilities"]["hoverProvider"] == json!(true));
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.try_request(method, params)
+            .unwrap_or_else(|error| panic!("request failed: {error:?}"))
+    }
+
+    fn try_request(&mut self, method: &str, params: Value) -> Result<Value, ResponseError> {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        let request = Request::new(id.clone(), method.to_owned(), params);
+        self.connection.sender.send(request.into()).unwrap();
+        loop {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Response(Response {
+                    id: response_id,
+                    result,
+                    error,
+                }) if response_id == id => {
+                    return match error {
+                        Some(error) => Err(error),
+                        None => Ok(result.unwrap_or(Value::Null)),
+                    };
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        let notification = Notification::new(method.to_owned(), params);
+        self.connection.sender.send(notification.into()).unwrap();
+    }
+
+    fn diagnostics(&self) -> Value {
+        loop {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Notification(n) if n.method == "textDocument/publishDiagnostics" => {
+                    return n.params["diagnostics"].clone();
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    fn open(&self, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": {
+                "uri": URI, "languageId": "lox", "version": 1, "text": text
+            } }),
+        );
+        self.diagnostics()
+    }
+
+    fn at(line: u32, character: u32) -> Value {
+        json!({ "textDocument": { "uri": URI }, "position": { "line": line, "character": character } })
+    }
+}
+
+impl Drop for TestClient {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+        let server = self.server.take().unwrap();
+        server.join().unwrap().unwrap();
+    }
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Value {
+    json!({
+        "start": { "line": start.0, "character": start.1 },
+        "end": { "line": end.0, "character": end.1 }
+    })
+}
+
+#[test]
+fn diagnostics_are_published_on_change() {
+    // Arrange
+    let client = TestClient::start();
+    assert_eq!(json!([]), client.open(SOURCE));
+    // Act
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "var a = 1;\nvar b = a @ 2;\n{ var c = c; }" }]
+        }),
+    );
+    let scan_errors = client.diagnostics();
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 3 },
+            "contentChanges": [{ "text": "{ var c = c; }" }]
+        }),
+    );
+    let resolve_errors = client.diagnostics();
+    // Assert
+    assert_eq!(1, scan_errors.as_array().unwrap().len());
+    assert_eq!(json!("L0001"), scan_errors[0]["code"]);
+    assert_eq!(range((1, 10), (1, 11)), scan_errors[0]["range"]);
+    assert_eq!(1, resolve_errors.as_array().unwrap().len());
+    assert_eq!(json!("L0202"), resolve_errors[0]["code"]);
+    assert_eq!(range((0, 10), (0, 11)), resolve_errors[0]["range"]);
+}
+
+#[test]
+fn syntax_errors_are_published() {
+    // Arrange
+    let client = TestClient::start();
+    // Act
+    let diagnostics = client.open("var a = ;");
+    // Assert
+    assert_eq!(1, diagnostics.as_array().unwrap().len());
+    assert_eq!(json!("rlox"), diagnostics[0]["source"]);
+}
+
+#[test]
+fn hover_shows_declaration_and_inferred_type() {
+    // Arrange
+    let mut client = TestClient::start();
+    client.open(SOURCE);
+    // Act
+    let outer = client.request("textDocument/hover", TestClient::at(6, 9));
+    let inner = client.request("textDocument/hover", TestClient::at(4, 11));
+    // Assert
+    let outer = outer["contents"]["value"].as_str().unwrap();
+    assert!(outer.contains("var count = 1;"), "{outer}");
+    assert!(outer.contains("type: `Number`"), "{outer}");
+    let inner = inner["contents"]["value"].as_str().unwrap();
+    assert!(inner.contains("var count = \"inner\";"), "{inner}");
+    assert!(inner.contains("type: `String`"), "{inner}");
+}
+
+#[test]
+fn definition_and_references_follow_scopes() {
+    // Arrange
+    let mut client = TestClient::start();
+    client.open(SOURCE);
+    // Act
+    let definition = client.request("textDocument/definition", TestClient::at(4, 12));
+    let mut params = TestClient::at(1, 5);
+    params["context"] = json!({ "includeDeclaration": true });
+    let references = client.request("textDocument/references", params);
+    // Assert
+    assert_eq!(range((3, 8), (3, 13)), definition["range"]);
+    let ranges: Vec<_> = references
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|location| location["range"].clone())
+        .collect();
+    let expected = vec![
+        range((1, 4), (1, 9)),
+        range((6, 8), (6, 13)),
+        range((6, 0), (6, 5)),
+    ];
+    assert_eq!(expected, ranges);
+}
+
+#[test]
+fn rename_changes_the_declaration_and_its_uses() {
+    // Arrange
+    let mut client = TestClient::start();
+    client.open(SOURCE);
+    let mut params = TestClient::at(6, 10);
+    params["newName"] = json!("total");
+    // Act
+    let edit = client.request("textDocument/rename", params);
+    // Assert
+    let edits = edit["changes"][URI].as_array().unwrap();
+    let ranges: Vec<_> = edits.iter().map(|edit| edit["range"].clone()).collect();
+    let expected = vec![
+        range((1, 4), (1, 9)),
+        range((6, 8), (6, 13)),
+        range((6, 0), (6, 5)),
+    ];
+    assert_eq!(expected, ranges);
+    assert!(edits.iter().all(|edit| edit["newText"] == json!("total")));
+}
+
+#[test]
+fn rename_to_an_invalid_name_fails() {
+    // Arrange
+    let mut client = TestClient::start();
+    client.open(SOURCE);
+    let mut params = TestClient::at(4, 12);
+    params["newName"] = json!("while");
+    // Act
+    let error = client
+        .try_request("textDocument/rename", params)
+        .unwrap_err();
+    // Assert
+    assert!(error.message.contains("'while'"), "{}", error.message);
+}
+
+#[test]
+fn completion_offers_keywords_and_variables() {
+    // Arrange
+    let mut client = TestClient::start();
+    client.open(SOURCE);
+    // Act
+    let items = client.request("textDocument/completion", TestClient::at(6, 0));
+    // Assert
+    let labels: Vec<_> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["label"].as_str().unwrap())
+        .collect();
+    assert!(labels.contains(&"while"));
+    assert!(labels.contains(&"print"));
+    let count: Vec<_> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|item| item["label"] == "count")
+        .collect();
+    assert_eq!(1, count.len());
+    // the inner declaration is out of scope
+    assert_eq!(json!("Number"), count[0]["detail"]);
+}
+
+#[test]
+fn semantic_tokens_are_built_from_tokens() {
+    // Arrange
+    let mut client = TestClient::start();
+    client.open("// c\nvar a = \"x\";");
+    // Act
+    let tokens = client.request(
+        "textDocument/semanticTokens/full",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    // Assert
+    // delta line, delta start, length, type (comment, keyword, variable, operator, string) and
+    // modifiers (the declared name)
+    let expected = json!([
+        0, 0, 4, 5, 0, //
+        1, 0, 3, 0, 0, //
+        0, 4, 1, 1, 1, //
+        0, 2, 1, 4, 0, //
+        0, 2, 3, 3, 0,
+    ]);
+    assert_eq!(expected, tokens["data"]);
+}
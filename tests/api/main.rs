@@ -1,18 +1,61 @@
 use anyhow::Result;
-use rlox::{Environment, Interpreter, Value};
+use rlox::{domain::grammar::Program, Backend, Environment, Interpreter, OptLevel, Value};
 
+mod ast_json;
+mod cache;
+mod debugger;
+mod diagnostics;
+mod dump;
+mod error_format;
+mod error_policy;
+mod errors;
+mod formatter;
+mod gc;
 mod interpreter;
+mod interrupt;
+mod library;
+mod limits;
+mod lint;
+#[cfg(feature = "lsp")]
+mod lsp;
+mod optimizer;
+mod output;
 mod parser;
+mod resolver;
 mod scanner;
+mod vm;
+
+fn test_backend() -> Backend {
+    match std::env::var("RLOX_TEST_BACKEND").as_deref() {
+        Ok("vm") => Backend::Vm,
+        _ => Backend::TreeWalker,
+    }
+}
 
 struct TestApp {
     interpreter: Interpreter,
 }
 
 impl TestApp {
+    ///
+    /// Spawns an app with the backend selected by the `RLOX_TEST_BACKEND` environment variable
+    /// (`vm` or the tree walker otherwise), so that the whole suite can be run on either backend
+    ///
     fn spawn() -> Self {
+        Self::spawn_with_backend(test_backend())
+    }
+
+    fn spawn_with_backend(backend: Backend) -> Self {
         Self {
-            interpreter: Interpreter::default(),
+            interpreter: Interpreter::default().with_backend(backend),
+        }
+    }
+
+    fn spawn_with_opt_level(opt_level: OptLevel) -> Self {
+        Self {
+            interpreter: Interpreter::default()
+                .with_backend(test_backend())
+                .with_opt_level(opt_level),
         }
     }
 
@@ -20,7 +63,15 @@ impl TestApp {
         self.interpreter.interpret_src_str(input)
     }
 
+    fn run(&mut self, program: Program) -> Result<Option<Value>, Vec<anyhow::Error>> {
+        self.interpreter.run(program)
+    }
+
+    fn check(&mut self, input: &str) -> Result<(), Vec<anyhow::Error>> {
+        self.interpreter.check(input)
+    }
+
     fn interpreter_state(&self) -> &Environment {
-        &self.interpreter.environment()
+        self.interpreter.environment()
     }
 }
@@ -0,0 +1,93 @@
+use rlox::{Interpreter, Limits, OptLevel};
+
+use crate::{test_backend, TestApp};
+
+///
+/// Runs the input with and without optimizations and checks that the results and error messages
+/// agree
+///
+fn assert_same_outcome(input: &str) {
+    let mut plain = TestApp::spawn_with_opt_level(OptLevel::O0);
+    let mut optimized = TestApp::spawn_with_opt_level(OptLevel::O1);
+
+    let expected = plain.process_input(input);
+    let actual = optimized.process_input(input);
+
+    match (expected, actual) {
+        (Ok(expected), Ok(actual)) => assert_eq!(expected, actual),
+        (Err(expected), Err(actual)) => {
+            let expected: Vec<_> = expected.iter().map(|e| e.to_string()).collect();
+            let actual: Vec<_> = actual.iter().map(|e| e.to_string()).collect();
+            assert_eq!(expected, actual);
+        }
+        (expected, actual) => panic!("outcomes differ: {expected:?} vs {actual:?}"),
+    }
+}
+
+#[test]
+fn folded_expressions() {
+    assert_same_outcome("(1 + 2) * 3 - -4 / 2;");
+    assert_same_outcome(r#""a" + "b" == "ab";"#);
+    assert_same_outcome("!(1 >= 2) and 3 < 4;");
+}
+
+#[test]
+fn dead_branches() {
+    assert_same_outcome(
+        r#"
+        var a = 0;
+        if 1 < 2 { a = 1; } else { a = 2; }
+        if false { a = 3; }
+        while 1 > 2 { a = 4; }
+        a;
+    "#,
+    );
+}
+
+#[test]
+fn errors_in_folded_code() {
+    assert_same_outcome("1 + 2 + true;");
+    assert_same_outcome("-(1 == 1);");
+    assert_same_outcome("if 1 + 1 { }");
+}
+
+#[test]
+fn folding_keeps_the_string_length_limit() {
+    // Arrange
+    let spawn = |opt_level| TestApp {
+        interpreter: Interpreter::default()
+            .with_backend(test_backend())
+            .with_opt_level(opt_level)
+            .with_limits(Limits {
+                max_string_len: Some(4),
+                ..Default::default()
+            }),
+    };
+    let input = r#"print "abc" + "def";"#;
+
+    // Act
+    let plain = spawn(OptLevel::O0).process_input(input).unwrap_err();
+    let optimized = spawn(OptLevel::O1).process_input(input).unwrap_err();
+
+    // Assert
+    assert!(plain[0]
+        .to_string()
+        .contains("maximum string length of 4 bytes"));
+    assert_eq!(plain[0].to_string(), optimized[0].to_string());
+}
+
+#[test]
+fn variables_are_not_folded() {
+    // Arrange
+    let mut app = TestApp::spawn_with_opt_level(OptLevel::O1);
+    let input = "var a = 1; { var b = a + 2 * 3; a = b; } a;";
+
+    // Act
+    let value = app
+        .process_input(input)
+        .expect("no errors")
+        .expect("a value");
+
+    // Assert
+    assert_eq!("7", value.to_string());
+}
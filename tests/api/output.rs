@@ -0,0 +1,82 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rlox::{Interpreter, Output, OutputBuffer};
+
+use crate::test_backend;
+
+#[test]
+fn print_writes_to_buffer() {
+    // Arrange
+    let output = OutputBuffer::default();
+    let mut interpreter = Interpreter::default()
+        .with_backend(test_backend())
+        .with_output(output.clone())
+        .quiet();
+
+    // Act
+    interpreter
+        .interpret_src_str(r#"var a = 1; print a + 2; print "a" + "b"; print true;"#)
+        .unwrap();
+
+    // Assert
+    assert_eq!("3\n'ab'\nTRUE\n", output.contents());
+}
+
+#[test]
+fn print_calls_callback() {
+    // Arrange
+    let lines = Rc::new(RefCell::new(vec![]));
+    let sink = lines.clone();
+    let mut interpreter = Interpreter::default()
+        .with_backend(test_backend())
+        .with_output(Output::callback(move |line| {
+            sink.borrow_mut().push(line.to_owned())
+        }))
+        .quiet();
+
+    // Act
+    interpreter
+        .interpret_src_str("for {var i = 0;} {i < 3} {i = i + 1;} { print i; }")
+        .unwrap();
+
+    // Assert
+    assert_eq!(vec!["0", "1", "2"], *lines.borrow());
+}
+
+#[test]
+fn diagnostics_are_separate_from_output() {
+    // Arrange
+    let output = OutputBuffer::default();
+    let diagnostics = OutputBuffer::default();
+    let mut interpreter = Interpreter::default()
+        .with_backend(test_backend())
+        .with_output(output.clone())
+        .with_diagnostics(diagnostics.clone());
+
+    // Act
+    interpreter.interpret_src_str("print 1;").unwrap();
+
+    // Assert
+    assert_eq!("1\n", output.contents());
+    assert!(diagnostics
+        .contents()
+        .contains("interpreting the following: 'print 1;'"));
+}
+
+#[test]
+fn quiet_mode_emits_only_output() {
+    // Arrange
+    let output = OutputBuffer::default();
+    let mut interpreter = Interpreter::default()
+        .with_backend(test_backend())
+        .with_disassembly(true)
+        .with_output(output.clone())
+        .with_diagnostics(output.clone())
+        .quiet();
+
+    // Act
+    interpreter.interpret_src_str("print 1 + 2; 4;").unwrap();
+
+    // Assert
+    assert_eq!("3\n", output.contents());
+}
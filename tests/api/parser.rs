@@ -1,4 +1,5 @@
 use claim::assert_err;
+use rlox::errors::ParseError;
 
 use crate::TestApp;
 
@@ -15,3 +16,84 @@ fn parser_syntax_error_missing_closing_parenthesis() {
         assert_eq!(1, errors.len());
     }
 }
+
+fn error_messages(input: &str) -> Vec<String> {
+    let mut test_app = TestApp::spawn();
+    let errors = test_app.process_input(input).unwrap_err();
+    errors.iter().map(|e| e.to_string()).collect()
+}
+
+#[test]
+fn parser_unexpected_tokens_are_errors() {
+    for input in [";", ")", "}", "print ;", "var a = *;", "else { }"] {
+        // Act
+        let errors = error_messages(input);
+        // Assert
+        assert!(!errors.is_empty(), "no error for {input}");
+        assert!(errors[0].starts_with("Expected one of"), "{errors:?}");
+    }
+}
+
+#[test]
+fn parser_unterminated_block() {
+    // Arrange
+    let input = "{ print 1;\n{ print 2; }";
+    // Act
+    let errors = error_messages(input);
+    // Assert
+    assert_eq!(1, errors.len());
+    assert!(errors[0].contains("closing the block opened in line 1"));
+}
+
+#[test]
+fn parser_reports_several_errors_per_block() {
+    // Arrange
+    let input = "{ print ; var = 2; print 3; a = ; }\nprint 4 5;";
+    // Act
+    let errors = error_messages(input);
+    // Assert
+    assert_eq!(4, errors.len(), "{errors:?}");
+    assert!(errors[1].contains("Context: 'variable name after 'var''"));
+    assert!(errors[3].contains("Context: 'semicolon after statement'"));
+}
+
+#[test]
+fn parser_recovers_at_statement_keywords() {
+    // Arrange
+    let input = "if true print 1; var a = 1 var b = 2; print b;";
+    // Act
+    let errors = error_messages(input);
+    // Assert
+    assert_eq!(2, errors.len());
+    assert!(errors[0].contains("opening brace of a block"));
+    assert!(errors[1].contains("semicolon after rhs of declaration"));
+}
+
+#[test]
+fn parser_reports_stray_closing_brace_after_recovering() {
+    // Arrange
+    let input = ";\n)\n}\n{ var x = 1;";
+    let mut test_app = TestApp::spawn();
+    // Act
+    let errors = test_app.process_input(input).unwrap_err();
+    // Assert
+    let lines: Vec<_> = errors
+        .iter()
+        .map(|e| e.downcast_ref::<ParseError>().unwrap().span().start.line)
+        .collect();
+    assert_eq!(vec![1, 2, 3, 4], lines);
+}
+
+#[test]
+fn parser_errors_do_not_repeat_the_location() {
+    // Act
+    let errors = error_messages("print ;");
+    // Assert
+    assert_eq!(
+        vec![
+            "Expected one of number, string, identifier, 'true', 'false', 'nil', '(' but got ';'; \
+             Context: 'expression'"
+        ],
+        errors
+    );
+}
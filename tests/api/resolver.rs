@@ -0,0 +1,84 @@
+use claim::{assert_err, assert_ok};
+use rlox::ValueType;
+
+use crate::TestApp;
+
+#[test]
+fn resolver_reports_use_before_declaration() {
+    // Arrange
+    let input = r#"
+        {
+            var b = a;
+            var a = 1;
+        }
+    "#;
+    let mut test_app = TestApp::spawn();
+    // Act
+    let output = test_app.process_input(input);
+    // Assert
+    assert_err!(&output);
+    if let Err(errors) = output {
+        assert_eq!(1, errors.len());
+    }
+}
+
+#[test]
+fn resolver_reports_all_static_errors() {
+    // Arrange
+    let input = r#"
+        {
+            var a = 1;
+            var a = 2;
+            var b = b;
+        }
+    "#;
+    let mut test_app = TestApp::spawn();
+    // Act
+    let output = test_app.process_input(input);
+    // Assert
+    assert_err!(&output);
+    if let Err(errors) = output {
+        assert_eq!(2, errors.len());
+    }
+}
+
+#[test]
+fn resolver_error_prevents_execution() {
+    // Arrange
+    let input = r#"
+        var a = 1;
+        { print c; var c = 2; }
+    "#;
+    let mut test_app = TestApp::spawn();
+    // Act
+    let output = test_app.process_input(input);
+    // Assert
+    assert_err!(output);
+    assert!(test_app.interpreter_state().get_var_value("a").is_none());
+}
+
+#[test]
+fn globals_of_previous_inputs_are_known() {
+    // Arrange
+    let mut test_app = TestApp::spawn();
+    assert_ok!(test_app.process_input("var a = 1;"));
+    // Act
+    let output = test_app.process_input("var b = a; var a = 2;");
+    // Assert
+    assert_ok!(output);
+    let var = test_app.interpreter_state().get_var_value("b");
+    assert_eq!(ValueType::Number(1.0), var.unwrap().v_type);
+}
+
+#[test]
+fn redeclared_global_is_initialized_from_its_previous_value() {
+    // Arrange
+    let mut test_app = TestApp::spawn();
+    assert_ok!(test_app.process_input("var a = 1; var a = a + 1;"));
+    // Act
+    let output = test_app.process_input("var a = a + 1;");
+    // Assert
+    assert_ok!(output);
+    let var = test_app.interpreter_state().get_var_value("a");
+    assert_eq!(ValueType::Number(3.0), var.unwrap().v_type);
+}
@@ -0,0 +1,82 @@
+use rlox::Backend;
+
+use crate::TestApp;
+
+///
+/// Runs the input on both backends and checks that the results and error messages agree
+///
+fn assert_same_outcome(input: &str) {
+    let mut tree_walker = TestApp::spawn_with_backend(Backend::TreeWalker);
+    let mut vm = TestApp::spawn_with_backend(Backend::Vm);
+
+    let expected = tree_walker.process_input(input);
+    let actual = vm.process_input(input);
+
+    match (expected, actual) {
+        (Ok(expected), Ok(actual)) => assert_eq!(expected, actual),
+        (Err(expected), Err(actual)) => {
+            let expected: Vec<_> = expected.iter().map(|e| e.to_string()).collect();
+            let actual: Vec<_> = actual.iter().map(|e| e.to_string()).collect();
+            assert_eq!(expected, actual);
+        }
+        (expected, actual) => panic!("outcomes differ: {expected:?} vs {actual:?}"),
+    }
+}
+
+#[test]
+fn arithmetic_and_strings() {
+    assert_same_outcome(r#"(1 + 2) * 3 - -4 / 2;"#);
+    assert_same_outcome(r#""a" + "b" == "ab";"#);
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    assert_same_outcome("true or 1;");
+    assert_same_outcome("false and 1;");
+    assert_same_outcome("false or 1;");
+}
+
+#[test]
+fn scoping_and_loops() {
+    assert_same_outcome(
+        r#"
+        var a = 0;
+        var b;
+        for {var i = 0;} {i < 10} {i = i + 1;} {
+            var a = i;
+            b = a;
+        }
+        while a < 5 { a = a + 1; }
+        if a == 5 { a = a * b; } else { a = 0; }
+        a;
+    "#,
+    );
+}
+
+#[test]
+fn runtime_errors() {
+    assert_same_outcome(
+        r#"
+        var a = 1 + true;
+        b = 2;
+        if 1 { }
+        while nil { }
+        var c = !3;
+        print b;
+        a;
+    "#,
+    );
+}
+
+#[test]
+fn names_used_more_often_than_the_constant_pool_holds() {
+    // Arrange
+    let mut app = TestApp::spawn_with_backend(Backend::Vm);
+    let input = format!("var a = 1;{}", " a = a;".repeat(40_000));
+
+    // Act
+    let value = app.process_input(&format!("{input} a;"));
+
+    // Assert
+    assert!(value.is_ok(), "{value:?}");
+}